@@ -2,18 +2,8 @@ use anyhow::{bail, Context, Result};
 use std::io::Write;
 use std::process::{Command, Stdio};
 
-const BASE_PROMPT: &str = r#"Generate a git commit message for the following diff.
-Follow conventional commit format: type(scope): description
-Types: feat, fix, docs, style, refactor, test, chore
-Only output the commit message, nothing else."#;
-
-pub fn generate(diff: &str, style: Option<&str>, model: Option<&str>) -> Result<String> {
-    let style_instruction = match style {
-        Some(s) => format!("\nStyle: {}", s),
-        None => "\nKeep the first line under 72 characters. Be concise.".to_string(),
-    };
-    let input = format!("{}{}\n\n```diff\n{}\n```", BASE_PROMPT, style_instruction, diff);
-
+/// Pipe the assembled prompt to the CLI and return its reply
+pub fn generate(input: &str, model: Option<&str>) -> Result<String> {
     let mut cmd = Command::new("codex");
     cmd.arg("exec")
         .arg("--full-auto")
@@ -1,7 +1,9 @@
 use anyhow::{bail, Result};
+use globset::GlobSet;
 use std::process::Command;
 
 use super::{claude, codex, gemini};
+use crate::config::{build_glob_set, Config};
 
 /// Max characters to send to AI providers
 /// Claude CLI pipe mode has strict limits; keep conservative to avoid "Prompt is too long"
@@ -58,6 +60,39 @@ fn is_command_available(cmd: &str) -> bool {
         .unwrap_or(false)
 }
 
+/// Cut the contents of `ai_diff_exclude` files out of a diff bound for the
+/// provider; callers skip this for `--include-all`
+pub fn exclude_ai_diff_files(diff: &str, config: &Config) -> String {
+    match build_glob_set(&config.ai_diff_exclude) {
+        Some(exclude) => omit_excluded_files(diff, &exclude),
+        None => diff.to_string(),
+    }
+}
+
+/// Drop the chunks of files matching `exclude`, listing their names up front
+/// so the model still knows they changed
+fn omit_excluded_files(diff: &str, exclude: &GlobSet) -> String {
+    let (omitted, kept): (Vec<&str>, Vec<&str>) = split_diff_by_file(diff).into_iter().partition(|chunk| {
+        chunk
+            .lines()
+            .find(|l| l.starts_with("diff --git"))
+            .is_some_and(|l| exclude.is_match(extract_filename(l)))
+    });
+    if omitted.is_empty() {
+        return diff.to_string();
+    }
+
+    let names: Vec<String> = omitted
+        .iter()
+        .filter_map(|chunk| chunk.lines().next().map(extract_filename))
+        .collect();
+    format!(
+        "[CHANGED, CONTENTS OMITTED]\n{}\n\n{}\n",
+        names.join("\n"),
+        kept.join("\n")
+    )
+}
+
 /// Truncate diff to fit AI provider limits, preserving file summary context
 fn truncate_diff(diff: &str) -> String {
     if diff.len() <= MAX_DIFF_CHARS {
@@ -209,18 +244,52 @@ pub fn strip_code_blocks(text: &str) -> String {
     }
 }
 
-/// Generate commit message using the specified provider
-pub fn generate_commit_message(provider: AiProvider, diff: &str, style: Option<&str>, model: Option<&str>) -> Result<String> {
+const BASE_PROMPT: &str = r#"Generate a git commit message for the following diff.
+Follow conventional commit format: type(scope): description
+Types: feat, fix, docs, style, refactor, test, chore
+Only output the commit message, nothing else."#;
+
+/// The `[ai.<provider>] prompt` override from config, else the shared prompt
+fn base_prompt(config: &Config, provider: AiProvider) -> &str {
+    config.provider_prompt(provider.name()).unwrap_or(BASE_PROMPT)
+}
+
+/// Full prompt: base instructions, style and scope hints, then the diff
+fn build_prompt(base: &str, diff: &str, style: Option<&str>, scope: Option<&str>) -> String {
+    let style_instruction = match style {
+        Some(s) => format!("\nStyle: {}", s),
+        None => "\nKeep the first line under 72 characters. Be concise.".to_string(),
+    };
+    let scope_instruction = scope
+        .map(|s| format!("\nUse `{}` as the scope.", s))
+        .unwrap_or_default();
+    format!(
+        "{}{}{}\n\n```diff\n{}\n```",
+        base, style_instruction, scope_instruction, diff
+    )
+}
+
+/// Generate commit message using the specified provider; `scope` pins the
+/// conventional-commit scope when known
+pub fn generate_commit_message(
+    provider: AiProvider,
+    diff: &str,
+    style: Option<&str>,
+    scope: Option<&str>,
+    model: Option<&str>,
+) -> Result<String> {
     if diff.is_empty() {
         bail!("No staged changes to generate commit message for");
     }
 
+    let config = Config::load().unwrap_or_default();
     let diff = truncate_diff(diff);
+    let input = build_prompt(base_prompt(&config, provider), &diff, style, scope);
 
     let message = match provider {
-        AiProvider::Claude => claude::generate(&diff, style, model),
-        AiProvider::Codex => codex::generate(&diff, style, model),
-        AiProvider::Gemini => gemini::generate(&diff, style, model),
+        AiProvider::Claude => claude::generate(&input, model),
+        AiProvider::Codex => codex::generate(&input, model),
+        AiProvider::Gemini => gemini::generate(&input, model),
     }?;
 
     Ok(strip_code_blocks(&message))
@@ -230,6 +299,19 @@ pub fn generate_commit_message(provider: AiProvider, diff: &str, style: Option<&
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_prompt_override_per_provider() {
+        let config: Config = toml::from_str("[ai.claude]\nprompt = \"Write a haiku commit.\"").unwrap();
+        assert_eq!(base_prompt(&config, AiProvider::Claude), "Write a haiku commit.");
+        assert_eq!(base_prompt(&config, AiProvider::Gemini), BASE_PROMPT);
+        assert_eq!(base_prompt(&Config::default(), AiProvider::Claude), BASE_PROMPT);
+
+        let prompt = build_prompt("Write a haiku commit.", "+x", None, Some("api"));
+        assert!(prompt.starts_with("Write a haiku commit.\nKeep the first line"));
+        assert!(prompt.contains("Use `api` as the scope."));
+        assert!(prompt.ends_with("```diff\n+x\n```"));
+    }
+
     #[test]
     fn test_strip_code_blocks() {
         // Plain text - no changes
@@ -314,6 +396,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_omit_excluded_files() {
+        let diff = "diff --git a/src/main.rs b/src/main.rs\n+fn main() {}\n\
+                    diff --git a/Cargo.lock b/Cargo.lock\n+[[package]]\n\
+                    diff --git a/web/package-lock.json b/web/package-lock.json\n+{}\n";
+        let exclude = build_glob_set(&Config::default().ai_diff_exclude).unwrap();
+        let result = omit_excluded_files(diff, &exclude);
+        assert!(result.starts_with("[CHANGED, CONTENTS OMITTED]\nCargo.lock\nweb/package-lock.json\n\n"));
+        assert!(result.contains("+fn main() {}"));
+        assert!(!result.contains("[[package]]"));
+
+        let plain = "diff --git a/a.rs b/a.rs\n+x\n";
+        assert_eq!(omit_excluded_files(plain, &exclude), plain);
+    }
+
+    #[test]
+    fn test_exclude_ai_diff_files_uses_config() {
+        let diff = "diff --git a/Cargo.lock b/Cargo.lock\n+[[package]]\n";
+        assert!(!exclude_ai_diff_files(diff, &Config::default()).contains("[[package]]"));
+
+        let config = Config { ai_diff_exclude: Vec::new(), ..Config::default() };
+        assert_eq!(exclude_ai_diff_files(diff, &config), diff);
+    }
+
     #[test]
     fn test_split_diff_by_file() {
         let diff = "diff --git a/foo.rs b/foo.rs\n+foo\ndiff --git a/bar.rs b/bar.rs\n+bar";
@@ -0,0 +1,308 @@
+use std::io::{stdout, Write};
+use std::process::{Command, Stdio};
+
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use crossterm::{
+    event::{Event, KeyCode, KeyEventKind},
+};
+use git2::Repository;
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame, Terminal,
+};
+
+use crate::craft::split::{generate_patch_for_hunks, get_unstaged_hunks, DiffLine, Hunk};
+use crate::render::help::{footer_spans, render_help_overlay, KeyHelp};
+use crate::render::redraw::Redraw;
+use crate::terminal::TuiGuard;
+
+/// Checkbox list of unstaged hunks, like `git add -p` but all at once
+struct HunkPicker {
+    hunks: Vec<Hunk>,
+    selected: Vec<bool>,
+    cursor: usize,
+    scroll: u16,
+    show_help: bool,
+    done: Option<bool>,
+}
+
+impl HunkPicker {
+    fn new(hunks: Vec<Hunk>) -> Self {
+        let selected = vec![false; hunks.len()];
+        Self { hunks, selected, cursor: 0, scroll: 0, show_help: false, done: None }
+    }
+
+    fn keys() -> Vec<KeyHelp> {
+        KeyHelp::list(&[
+            ("j/k", "nav"),
+            ("space", "toggle"),
+            ("f", "toggle file"),
+            ("a/n", "all/none"),
+            ("PgUp/PgDn", "scroll diff"),
+            ("Enter", "stage"),
+            ("q", "cancel"),
+        ])
+    }
+
+    fn selection(&self) -> Vec<usize> {
+        (0..self.hunks.len()).filter(|&i| self.selected[i]).collect()
+    }
+
+    fn move_cursor(&mut self, delta: isize) {
+        let last = self.hunks.len().saturating_sub(1) as isize;
+        self.cursor = (self.cursor as isize + delta).clamp(0, last) as usize;
+        self.scroll = 0;
+    }
+
+    /// Select every hunk of the cursor's file, or clear them if all are selected
+    fn toggle_file(&mut self) {
+        let file = &self.hunks[self.cursor].file_path;
+        let idxs: Vec<usize> = (0..self.hunks.len())
+            .filter(|&i| &self.hunks[i].file_path == file)
+            .collect();
+        let value = !idxs.iter().all(|&i| self.selected[i]);
+        for i in idxs {
+            self.selected[i] = value;
+        }
+    }
+
+    fn handle_key(&mut self, code: KeyCode) {
+        if self.show_help {
+            if matches!(code, KeyCode::Char('?') | KeyCode::Esc) {
+                self.show_help = false;
+            }
+            return;
+        }
+        match code {
+            KeyCode::Char('j') | KeyCode::Down => self.move_cursor(1),
+            KeyCode::Char('k') | KeyCode::Up => self.move_cursor(-1),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_add(10),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(10),
+            KeyCode::Char(' ') => {
+                self.selected[self.cursor] = !self.selected[self.cursor];
+                self.move_cursor(1);
+            }
+            KeyCode::Char('f') => self.toggle_file(),
+            KeyCode::Char('a') => self.selected.iter_mut().for_each(|s| *s = true),
+            KeyCode::Char('n') => self.selected.iter_mut().for_each(|s| *s = false),
+            KeyCode::Enter => self.done = Some(true),
+            KeyCode::Char('q') | KeyCode::Esc => self.done = Some(false),
+            KeyCode::Char('?') => self.show_help = true,
+            _ => {}
+        }
+    }
+}
+
+fn ui(f: &mut Frame, app: &HunkPicker) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(5), Constraint::Length(1)])
+        .split(f.size());
+    let panes = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+        .split(chunks[0]);
+
+    let items: Vec<ListItem> = app
+        .hunks
+        .iter()
+        .enumerate()
+        .map(|(i, h)| {
+            let check = if app.selected[i] { "[x]" } else { "[ ]" };
+            let style = if app.selected[i] {
+                Style::default().fg(Color::Green)
+            } else {
+                Style::default()
+            };
+            ListItem::new(format!(" {} {} {}", check, h.summary(), h.header)).style(style)
+        })
+        .collect();
+    let count = app.selected.iter().filter(|s| **s).count();
+    let list = List::new(items)
+        .highlight_style(Style::default().bg(Color::DarkGray).fg(Color::White))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan))
+                .title(format!(" Stage hunks {}/{} ", count, app.hunks.len())),
+        );
+    let mut state = ListState::default().with_selected(Some(app.cursor));
+    f.render_stateful_widget(list, panes[0], &mut state);
+
+    let hunk = &app.hunks[app.cursor];
+    let mut lines = vec![Line::styled(hunk.header.clone(), Style::default().fg(Color::Cyan))];
+    lines.extend(hunk.lines.iter().map(|l| match l {
+        DiffLine::Added(s) => Line::styled(format!("+{}", s.trim_end()), Style::default().fg(Color::Green)),
+        DiffLine::Removed(s) => Line::styled(format!("-{}", s.trim_end()), Style::default().fg(Color::Red)),
+        DiffLine::Context(s) => Line::from(format!(" {}", s.trim_end())),
+    }));
+    let detail = Paragraph::new(lines)
+        .scroll((app.scroll, 0))
+        .block(Block::default().borders(Borders::ALL).title(format!(" {} ", hunk.file_path)));
+    f.render_widget(detail, panes[1]);
+
+    let keys = HunkPicker::keys();
+    f.render_widget(Paragraph::new(Line::from(footer_spans(&keys))), chunks[1]);
+
+    if app.show_help {
+        render_help_overlay(f, "Stage hunks", &keys, f.size());
+    }
+}
+
+/// Pick hunks in a TUI; `None` if cancelled
+fn run_hunk_picker(hunks: Vec<Hunk>) -> Result<Option<(Vec<Hunk>, Vec<usize>)>> {
+    let mut app = HunkPicker::new(hunks);
+
+    let guard = TuiGuard::enter()?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
+
+    let mut redraw = Redraw::new();
+    let result = loop {
+        if redraw.take(false) {
+            if let Err(e) = terminal.draw(|f| ui(f, &app)) {
+                break Err(e.into());
+            }
+        }
+        match redraw.next_event(false) {
+            Ok(Some(Event::Key(key))) if key.kind == KeyEventKind::Press => app.handle_key(key.code),
+            Ok(_) => {}
+            Err(e) => break Err(e),
+        }
+        if app.done.is_some() {
+            break Ok(());
+        }
+    };
+
+    drop(guard);
+    result?;
+
+    let selection = app.selection();
+    Ok(match app.done {
+        Some(true) => Some((app.hunks, selection)),
+        _ => None,
+    })
+}
+
+/// Stage the `selected` hunks with `git apply --cached`
+pub fn stage_hunks(repo: &Repository, hunks: &[Hunk], selected: &[usize]) -> Result<()> {
+    let workdir = repo.workdir().context("Bare repos not supported")?;
+    let patch = generate_patch_for_hunks(hunks, selected);
+
+    let mut child = Command::new("git")
+        .arg("-C")
+        .arg(workdir)
+        .args(["apply", "--cached", "-"])
+        .stdin(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run git apply")?;
+    child
+        .stdin
+        .take()
+        .context("git apply stdin")?
+        .write_all(patch.as_bytes())?;
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        bail!(
+            "git apply --cached failed:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    // git wrote the index behind libgit2's back; drop the cached copy
+    repo.index()?.read(true).context("Failed to reload index")?;
+    Ok(())
+}
+
+/// `commit --patch`: choose unstaged hunks and stage them. Returns false if
+/// there was nothing to pick or the picker was cancelled.
+pub fn run_patch_staging(repo: &Repository) -> Result<bool> {
+    let hunks = get_unstaged_hunks(repo)?;
+    if hunks.is_empty() {
+        println!("{} no unstaged hunks in tracked files", "·".dimmed());
+        return Ok(false);
+    }
+
+    let Some((hunks, selected)) = run_hunk_picker(hunks)? else {
+        println!("{}", "cancelled".dimmed());
+        return Ok(false);
+    };
+    if selected.is_empty() {
+        println!("  {}", "No hunks selected.".dimmed());
+        return Ok(false);
+    }
+
+    stage_hunks(repo, &hunks, &selected)?;
+    println!("{} Staged {} of {} hunk(s)", "✓".green(), selected.len(), hunks.len());
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, init_repo};
+    use std::fs;
+    use std::path::Path;
+
+    fn numbered(count: usize, changed: &[usize]) -> String {
+        (1..=count)
+            .map(|i| if changed.contains(&i) { format!("changed {}\n", i) } else { format!("line {}\n", i) })
+            .collect()
+    }
+
+    #[test]
+    fn test_stage_selected_hunk_only() {
+        let (dir, repo) = init_repo();
+        let file = dir.path().join("a.txt");
+        commit_file(&repo, "a.txt", &numbered(30, &[]), "init");
+
+        fs::write(&file, numbered(30, &[2, 28])).unwrap();
+        let hunks = get_unstaged_hunks(&repo).unwrap();
+        assert_eq!(hunks.len(), 2);
+
+        // second hunk only: its new-side start must not assume the first applied
+        stage_hunks(&repo, &hunks, &[1]).unwrap();
+        let index = repo.index().unwrap();
+        let entry = index.get_path(Path::new("a.txt"), 0).unwrap();
+        let blob = repo.find_blob(entry.id).unwrap();
+        assert_eq!(std::str::from_utf8(blob.content()).unwrap(), numbered(30, &[28]));
+        assert_eq!(get_unstaged_hunks(&repo).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_picker_keys() {
+        let hunk = |file: &str| Hunk {
+            file_path: file.to_string(),
+            header: "@@ -1 +1 @@".to_string(),
+            lines: Vec::new(),
+            old_start: 1,
+            new_start: 1,
+            file: crate::craft::split::FileChange {
+                status: git2::Delta::Modified,
+                old_id: git2::Oid::zero(),
+                new_id: git2::Oid::zero(),
+                old_mode: 0o100644,
+                new_mode: 0o100644,
+            },
+        };
+        let mut app = HunkPicker::new(vec![hunk("a"), hunk("b"), hunk("b")]);
+
+        app.handle_key(KeyCode::Char(' '));
+        assert_eq!(app.selection(), vec![0]);
+        assert_eq!(app.cursor, 1);
+        app.handle_key(KeyCode::Char('f'));
+        assert_eq!(app.selection(), vec![0, 1, 2]);
+        app.handle_key(KeyCode::Char('f'));
+        assert_eq!(app.selection(), vec![0]);
+        app.handle_key(KeyCode::Char('a'));
+        assert_eq!(app.selection().len(), 3);
+        app.handle_key(KeyCode::Char('n'));
+        assert!(app.selection().is_empty());
+        app.handle_key(KeyCode::Enter);
+        assert_eq!(app.done, Some(true));
+    }
+}
@@ -0,0 +1,175 @@
+use std::fmt;
+
+use crate::config::CommitConfig;
+
+/// A conventional-commit rule the message breaks
+#[derive(Debug, Clone, PartialEq)]
+pub enum LintWarning {
+    EmptyMessage,
+    SubjectTooLong { len: usize, max: usize },
+    UnknownType(String),
+    MissingType,
+    NoBlankLineBeforeBody,
+    SubjectEndsWithPeriod,
+}
+
+impl fmt::Display for LintWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyMessage => write!(f, "message is empty"),
+            Self::SubjectTooLong { len, max } => write!(f, "subject is {} chars (max {})", len, max),
+            Self::UnknownType(t) => write!(f, "unrecognized type '{}'", t),
+            Self::MissingType => write!(f, "subject has no `type: ` prefix"),
+            Self::NoBlankLineBeforeBody => write!(f, "no blank line between subject and body"),
+            Self::SubjectEndsWithPeriod => write!(f, "subject ends with a period"),
+        }
+    }
+}
+
+/// Types `repo verify --conventional` accepts and the `[commit]` lint
+/// defaults to
+pub const CONVENTIONAL_TYPES: &[&str] = &[
+    "feat", "fix", "docs", "style", "refactor", "perf", "test", "build", "ci", "chore", "revert",
+];
+
+/// Type/scope/description parsed from a `type(scope)!: description` subject
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConventionalSubject<'a> {
+    /// Empty for a `BREAKING CHANGE: ...` subject
+    pub kind: &'a str,
+    pub scope: Option<&'a str>,
+    pub breaking: bool,
+    pub description: &'a str,
+}
+
+/// Parse `type(scope)!: description`. The type is lowercase (digits and `-`
+/// allowed), the scope non-empty, and `: ` separates a non-empty description.
+/// Any type parses; check it against a list where that matters.
+pub fn parse_conventional(subject: &str) -> Option<ConventionalSubject<'_>> {
+    if let Some(desc) = subject.strip_prefix("BREAKING CHANGE:") {
+        return Some(ConventionalSubject {
+            kind: "",
+            scope: None,
+            breaking: true,
+            description: desc.trim(),
+        });
+    }
+
+    let (head, description) = subject.split_once(": ")?;
+    let description = description.trim();
+    let (head, breaking) = match head.strip_suffix('!') {
+        Some(h) => (h, true),
+        None => (head, false),
+    };
+    let (kind, scope) = match head.split_once('(') {
+        Some((kind, rest)) => (kind, Some(rest.strip_suffix(')').filter(|s| !s.is_empty())?)),
+        None => (head, None),
+    };
+    let valid_kind = kind.starts_with(|c: char| c.is_ascii_lowercase())
+        && kind.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    if !valid_kind || description.is_empty() {
+        return None;
+    }
+
+    Some(ConventionalSubject { kind, scope, breaking, description })
+}
+
+/// Check `msg` against the `[commit]` rules. Comment lines are ignored.
+pub fn lint_message(msg: &str, rules: &CommitConfig) -> Vec<LintWarning> {
+    let lines: Vec<&str> = msg
+        .lines()
+        .filter(|l| !l.starts_with('#'))
+        .collect();
+    let Some(subject) = lines.iter().map(|l| l.trim_end()).find(|l| !l.is_empty()) else {
+        return vec![LintWarning::EmptyMessage];
+    };
+
+    let mut warnings = Vec::new();
+    let len = subject.chars().count();
+    if len > rules.max_subject_len {
+        warnings.push(LintWarning::SubjectTooLong { len, max: rules.max_subject_len });
+    }
+    if !rules.types.is_empty() {
+        match parse_conventional(subject).map(|c| c.kind).filter(|k| !k.is_empty()) {
+            Some(t) if !rules.types.iter().any(|known| known == t) => {
+                warnings.push(LintWarning::UnknownType(t.to_string()))
+            }
+            Some(_) => {}
+            None => warnings.push(LintWarning::MissingType),
+        }
+    }
+    if subject.ends_with('.') {
+        warnings.push(LintWarning::SubjectEndsWithPeriod);
+    }
+    let first = lines.iter().position(|l| !l.trim().is_empty()).unwrap_or(0);
+    if lines.get(first + 1).is_some_and(|l| !l.trim().is_empty()) {
+        warnings.push(LintWarning::NoBlankLineBeforeBody);
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_messages() {
+        let rules = CommitConfig::default();
+        for msg in [
+            "feat: add sync",
+            "fix(api)!: drop v1 endpoints\n\nBREAKING CHANGE: gone",
+            "chore: bump deps\n# Please enter the commit message\n",
+        ] {
+            assert!(lint_message(msg, &rules).is_empty(), "{}", msg);
+        }
+    }
+
+    #[test]
+    fn test_rule_violations() {
+        let rules = CommitConfig::default();
+        assert_eq!(lint_message("  \n# only comments\n", &rules), vec![LintWarning::EmptyMessage]);
+        assert_eq!(
+            lint_message("Add sync.\nmore detail", &rules),
+            vec![
+                LintWarning::MissingType,
+                LintWarning::SubjectEndsWithPeriod,
+                LintWarning::NoBlankLineBeforeBody
+            ]
+        );
+        assert_eq!(
+            lint_message("feature: x", &rules),
+            vec![LintWarning::UnknownType("feature".into())]
+        );
+        let long = format!("feat: {}", "a".repeat(70));
+        assert_eq!(
+            lint_message(&long, &rules),
+            vec![LintWarning::SubjectTooLong { len: 76, max: 72 }]
+        );
+
+        let relaxed = CommitConfig { max_subject_len: 100, types: Vec::new() };
+        assert!(lint_message(&long, &relaxed).is_empty());
+        assert!(lint_message("Add sync", &relaxed).is_empty());
+    }
+
+    #[test]
+    fn test_parse_conventional() {
+        let c = parse_conventional("feat(tui)!: drop legacy keys").unwrap();
+        assert_eq!(
+            c,
+            ConventionalSubject {
+                kind: "feat",
+                scope: Some("tui"),
+                breaking: true,
+                description: "drop legacy keys",
+            }
+        );
+        assert_eq!(parse_conventional("fix: typo").unwrap().scope, None);
+        assert!(parse_conventional("BREAKING CHANGE: new config").unwrap().breaking);
+        assert!(parse_conventional("Merge branch 'main'").is_none());
+        assert!(parse_conventional("WIP: stuff").is_none());
+        assert!(parse_conventional("feat(ui: broken").is_none());
+        assert!(parse_conventional("feat:missing space").is_none());
+        assert!(parse_conventional("feat(): empty scope").is_none());
+        assert!(parse_conventional("feat: ").is_none());
+    }
+}
@@ -1,6 +1,12 @@
+mod hunks;
+mod lint;
+mod post_commit;
+mod scope;
+mod secrets;
 pub mod tui;
 
-use std::io::{self, Write};
+use std::io::{self, IsTerminal, Write};
+use std::sync::mpsc::Receiver;
 use std::time::Duration;
 
 use anyhow::{bail, Context, Result};
@@ -8,26 +14,169 @@ use colored::Colorize;
 use crossterm::{
     cursor,
     event::{self, Event, KeyCode, KeyEventKind},
-    terminal::{self, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+    terminal::{self, ClearType},
     ExecutableCommand,
 };
 use git2::Repository;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
-use crate::ai::{detect_provider, generate_commit_message, AiProvider};
+use crate::ai::{detect_provider, exclude_ai_diff_files, generate_commit_message, AiProvider};
 use crate::git::{
-    amend_commit, create_commit, get_amend_diff, get_last_commit_message, get_staged_diff,
-    get_staged_files, get_unstaged_diff, get_unstaged_files, has_staged_changes, stage_all,
-    stage_files,
+    amend_commit, cleanup_message, create_commit, get_amend_diff, get_cleanup_config,
+    get_last_commit_message, get_staged_diff, get_staged_files, get_unstaged_diff,
+    get_unstaged_files, get_working_tree_status, has_staged_changes, is_pushed, marker_label,
+    stage_all, stage_paths, BINARY_MARKER,
 };
 
 use crate::config::{build_ignore_set, Config, MessageBoxStyle};
+use crate::error::RepoError;
+use crate::reword::edit_with_editor;
+use crate::terminal::{colorize_diff_line, is_quiet, with_spinner, TuiGuard};
 use crate::update;
 use tui::{run_commit_tui, CommitApp, TuiResult};
 
-/// Silently check for updates and print hint if available
-fn notify_update_available() {
-    // Don't fail commit if update check fails
-    if let Ok(Some(release)) = update::check_for_update() {
+pub use hunks::{run_patch_staging, stage_hunks};
+pub use lint::{
+    lint_message, parse_conventional, ConventionalSubject, LintWarning, CONVENTIONAL_TYPES,
+};
+
+/// The message to amend with after `--edit-after`; None if the edit left it
+/// unchanged (or emptied it), so nothing is rewritten
+fn changed_message(committed: &str, edited: Option<String>) -> Option<String> {
+    edited.filter(|m| !m.trim().is_empty() && m.trim() != committed.trim())
+}
+
+/// Warn about staged lines that look like credentials; continuing needs an
+/// explicit `y`, and non-interactive runs refuse outright
+fn check_secrets(diff: &str, interactive: bool) -> Result<()> {
+    let findings = secrets::scan_diff(diff);
+    if findings.is_empty() {
+        return Ok(());
+    }
+
+    println!("{} possible secrets in staged changes:", "⚠".yellow());
+    for f in &findings {
+        println!("   {} {}:{} {}", "✗".red(), f.file, f.line, f.kind.dimmed());
+    }
+    if !interactive {
+        bail!("refusing to commit possible secrets (set scan_secrets = false to skip the check)");
+    }
+
+    print!("{} Commit anyway? [y/N] ", "?".yellow().bold());
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    if !input.trim().eq_ignore_ascii_case("y") {
+        bail!("Cancelled.");
+    }
+    Ok(())
+}
+
+/// Amending a commit HEAD's upstream already has means a force-push later;
+/// continuing needs an explicit `y`, and non-interactive runs refuse unless
+/// `--force-amend` was given
+fn check_amend_pushed(repo: &Repository, interactive: bool) -> Result<()> {
+    let head = repo.head()?.peel_to_commit()?;
+    if !is_pushed(repo, head.id()) {
+        return Ok(());
+    }
+
+    let short = head.id().to_string()[..7].to_string();
+    if !interactive {
+        bail!(
+            "last commit {} is already pushed; amending it needs a force-push (pass --force-amend to amend anyway)",
+            short
+        );
+    }
+
+    print!(
+        "{} Last commit {} is already pushed — amending needs a force-push. Continue? [y/N] ",
+        "⚠".yellow(),
+        short.yellow()
+    );
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    if !input.trim().eq_ignore_ascii_case("y") {
+        bail!("Cancelled.");
+    }
+    Ok(())
+}
+
+/// `--amend --no-edit`: fold what's staged into the last commit with its
+/// message unchanged. No AI call, no staging prompt.
+fn amend_no_edit(repo: &Repository, interactive: bool, allow_conflict_markers: bool) -> Result<()> {
+    let staged = get_staged_diff(repo)?;
+    if !allow_conflict_markers {
+        check_conflicts(repo, &staged)?;
+    }
+    let config = Config::load().unwrap_or_default();
+    if config.scan_secrets {
+        check_secrets(&staged, interactive)?;
+    }
+    let update_hint = update::spawn_hint_check();
+
+    let oid = amend_commit(repo, &get_last_commit_message(repo)?)?;
+    print_committed("Amended", oid);
+    post_commit(repo, &config, oid);
+    notify_update_available(&update_hint);
+    Ok(())
+}
+
+/// Files whose added lines in `diff` open or close a merge conflict
+/// (`<<<<<<<` / `>>>>>>>`). A lone `=======` is too common to count.
+pub fn has_conflict_markers(diff: &str) -> Vec<String> {
+    let is_marker = |line: &str, marker: &str| marker_label(line, marker).is_some();
+    let mut files: Vec<String> = Vec::new();
+    let mut file = "";
+    for line in diff.lines() {
+        if let Some(path) = line.strip_prefix("+++ ") {
+            file = path.strip_prefix("b/").unwrap_or(path);
+        } else if let Some(added) = line.strip_prefix('+') {
+            if (is_marker(added, "<<<<<<<") || is_marker(added, ">>>>>>>"))
+                && files.last().map(String::as_str) != Some(file)
+            {
+                files.push(file.to_string());
+            }
+        }
+    }
+    files
+}
+
+/// Refuse unresolved merge conflicts and staged conflict markers
+fn check_conflicts(repo: &Repository, diff: &str) -> Result<()> {
+    let conflicted = get_working_tree_status(repo)?.conflicted;
+    if conflicted > 0 {
+        bail!(
+            "{} file(s) have unresolved conflicts (resolve them, or pass --allow-conflict-markers)",
+            conflicted
+        );
+    }
+    let files = has_conflict_markers(diff);
+    if files.is_empty() {
+        return Ok(());
+    }
+    println!("{} conflict markers in staged changes:", "⚠".yellow());
+    for file in &files {
+        println!("   {} {}", "✗".red(), file);
+    }
+    bail!("refusing to commit conflict markers (pass --allow-conflict-markers to commit anyway)");
+}
+
+/// `✓ Committed: abc1234`, or just the short SHA under `--quiet`
+fn print_committed(action_word: &str, oid: git2::Oid) {
+    let short = &oid.to_string()[..7];
+    if is_quiet() {
+        println!("{}", short);
+    } else {
+        println!("{} {}: {}", "✓".green(), action_word, short.dimmed());
+    }
+}
+
+/// Print the update hint if the background check from
+/// [`update::spawn_hint_check`] has already found a newer release
+fn notify_update_available(pending: &Option<Receiver<update::Release>>) {
+    if let Some(release) = pending.as_ref().and_then(|rx| rx.try_recv().ok()) {
         println!(
             "\n{} Update available: {} → {} (run: repo update)",
             "↑".yellow(),
@@ -37,17 +186,150 @@ fn notify_update_available() {
     }
 }
 
-fn print_message_box(message: &str, style: MessageBoxStyle) {
+const MIN_BOX_WIDTH: usize = 40;
+const MAX_BOX_WIDTH: usize = 100;
+const FALLBACK_BOX_WIDTH: usize = 50;
+
+/// Pick the inner box width from terminal columns (None = not a TTY) and the longest line
+fn select_box_width(term_cols: Option<u16>, longest_line: usize) -> usize {
+    let Some(cols) = term_cols else {
+        return FALLBACK_BOX_WIDTH;
+    };
+    // Leave room for the two border characters
+    let max = (cols as usize)
+        .saturating_sub(2)
+        .clamp(MIN_BOX_WIDTH, MAX_BOX_WIDTH);
+    (longest_line + 4).clamp(MIN_BOX_WIDTH, max)
+}
+
+/// Word-wrap a line to `max` display columns, hard-breaking words that don't fit
+fn wrap_line(line: &str, max: usize) -> Vec<String> {
+    if line.width() <= max || max == 0 {
+        return vec![line.to_string()];
+    }
+    let mut out = Vec::new();
+    let mut current = String::new();
+    for word in line.split(' ') {
+        let sep = if current.is_empty() { 0 } else { 1 };
+        if current.width() + sep + word.width() <= max {
+            if sep == 1 {
+                current.push(' ');
+            }
+            current.push_str(word);
+            continue;
+        }
+        if !current.is_empty() {
+            out.push(std::mem::take(&mut current));
+        }
+        for c in word.chars() {
+            let cw = c.width().unwrap_or(0);
+            if current.width() + cw > max {
+                out.push(std::mem::take(&mut current));
+            }
+            current.push(c);
+        }
+    }
+    out.push(current);
+    out
+}
+
+/// Hard-wrap the body of a commit message (everything after the first blank
+/// line) at `width` columns. The subject, comment lines and indented lines are
+/// left alone, as is a closing trailer block (`Signed-off-by: ...`); long
+/// words are never split, and list items (`- `, `* `) keep their
+/// continuation lines indented under the text.
+pub fn wrap_body(message: &str, width: usize) -> String {
     let lines: Vec<&str> = message.lines().collect();
-    let max_content = lines.iter().map(|l| l.len() + 4).max().unwrap_or(0);
-    let width = max_content.max(50);
+    let trailers = trailer_block_start(&lines);
+    let mut out = Vec::new();
+    let mut in_body = false;
+    for (i, &line) in lines.iter().enumerate() {
+        if !in_body {
+            in_body = line.trim().is_empty();
+            out.push(line.to_string());
+            continue;
+        }
+        if width == 0
+            || i >= trailers
+            || line.width() <= width
+            || (line.starts_with(['#', ' ', '\t']) && !is_list_item(line))
+        {
+            out.push(line.to_string());
+            continue;
+        }
+
+        let indent = line.len() - line.trim_start().len();
+        let marker = if is_list_item(line) { 2 } else { 0 };
+        let hang = " ".repeat(indent + marker);
+        let mut current = line[..indent + marker].to_string();
+        let mut has_word = false;
+        for word in line[indent + marker..].split_whitespace() {
+            if has_word && current.width() + 1 + word.width() > width {
+                out.push(std::mem::replace(&mut current, hang.clone()));
+                has_word = false;
+            }
+            if has_word {
+                current.push(' ');
+            }
+            current.push_str(word);
+            has_word = true;
+        }
+        out.push(current);
+    }
+    let mut wrapped = out.join("\n");
+    if message.ends_with('\n') {
+        wrapped.push('\n');
+    }
+    wrapped
+}
+
+/// Index of the first line of the trailer block: the last paragraph, when
+/// it follows a blank line and every line is `Token: value` (`Co-authored-by:`,
+/// `Signed-off-by:`, ...) or indented continuation. `lines.len()` if none.
+fn trailer_block_start(lines: &[&str]) -> usize {
+    let end = lines.iter().rposition(|l| !l.trim().is_empty()).map_or(0, |i| i + 1);
+    let Some(blank) = lines[..end].iter().rposition(|l| l.trim().is_empty()) else {
+        return lines.len();
+    };
+    let is_trailer = |line: &str| {
+        line.split_once(": ").is_some_and(|(token, _)| {
+            !token.is_empty() && token.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+    };
+    let block = &lines[blank + 1..end];
+    let valid = block.first().is_some_and(|l| is_trailer(l))
+        && block.iter().all(|l| is_trailer(l) || l.starts_with([' ', '\t']));
+    if valid && blank > 0 {
+        blank + 1
+    } else {
+        lines.len()
+    }
+}
+
+fn is_list_item(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    trimmed.starts_with("- ") || trimmed.starts_with("* ")
+}
+
+fn print_message_box(message: &str, style: MessageBoxStyle) {
+    let longest = message.lines().map(|l| l.width()).max().unwrap_or(0);
+    let term_cols = if io::stdout().is_terminal() {
+        terminal::size().ok().map(|(w, _)| w)
+    } else {
+        None
+    };
+    let width = select_box_width(term_cols, longest);
+    let lines: Vec<String> = message
+        .lines()
+        .flat_map(|l| wrap_line(l, width - 4))
+        .collect();
 
     match style {
         MessageBoxStyle::Box => {
             println!("{}", format!("╭{}╮", "─".repeat(width)).dimmed());
             for line in &lines {
                 let content = format!("  {}", line);
-                let pad = width.saturating_sub(content.len());
+                let pad = width.saturating_sub(content.width());
                 println!(
                     "{}{}{}{}",
                     "│".dimmed(),
@@ -67,7 +349,7 @@ fn print_message_box(message: &str, style: MessageBoxStyle) {
         }
         MessageBoxStyle::TitleBox => {
             let title = " Commit Message ";
-            let side = (width.saturating_sub(title.len())) / 2;
+            let side = width.saturating_sub(title.len()) / 2;
             println!(
                 "{}{}{}",
                 "─".repeat(side).dimmed(),
@@ -87,18 +369,87 @@ fn print_message_box(message: &str, style: MessageBoxStyle) {
     }
 }
 
+/// `commit --dry-run`: generate a message for the staged changes (or, with
+/// `amend`, the last commit plus them) and print it to stdout, wrapped and
+/// linted as a real commit would be. Stages and commits nothing; fails if
+/// there is nothing to describe, or with `strict` if the message fails lint.
+pub fn run_commit_dry_run(
+    repo: &Repository,
+    cli_ai: Option<String>,
+    cli_model: Option<String>,
+    amend: bool,
+    strict: bool,
+    include_all: bool,
+) -> Result<()> {
+    let config = Config::load().unwrap_or_default();
+    let provider = resolve_provider(cli_ai, &config)?;
+    let model = cli_model.or_else(|| config.commit_model.clone());
+
+    let diff = if amend {
+        get_amend_diff(repo)?
+    } else {
+        get_staged_diff(repo)?
+    };
+    if diff.trim().is_empty() {
+        bail!("Nothing staged to generate a message from.");
+    }
+    if config.scan_secrets {
+        check_secrets(&get_staged_diff(repo)?, false)?;
+    }
+
+    let diff = if include_all { diff } else { exclude_ai_diff_files(&diff, &config) };
+
+    let staged_files = get_staged_files(repo)?;
+    let scope = scope::infer_scope(&config.scope_map, &staged_files);
+    let style = config.commit_style.as_deref();
+    let message = with_spinner(
+        format!("Generating commit message with {}...", provider.name().bold()),
+        || generate_commit_message(provider, &diff, style, scope.as_deref(), model.as_deref()),
+    )?;
+    let message = apply_body_wrap(message, &config);
+    println!("{}", message);
+
+    // warnings go to stderr so stdout stays just the message
+    let warnings = lint_message(&message, &config.commit);
+    for warning in &warnings {
+        eprintln!("  {} {}", "⚠".yellow(), warning);
+    }
+    if strict && !warnings.is_empty() {
+        bail!("Commit message fails lint (--strict)");
+    }
+    Ok(())
+}
+
 /// Main entry point for the commit workflow
+#[allow(clippy::too_many_arguments)]
 pub fn run_commit_workflow(
     repo: Repository,
     cli_ai: Option<String>,
     cli_model: Option<String>,
     interactive: bool,
     amend: bool,
+    edit_after: bool,
+    strict: bool,
+    allow_conflict_markers: bool,
+    force_amend: bool,
+    no_edit: bool,
+    include_all: bool,
 ) -> Result<()> {
+    if amend && !force_amend {
+        check_amend_pushed(&repo, interactive)?;
+    }
+    if amend && no_edit {
+        return amend_no_edit(&repo, interactive, allow_conflict_markers);
+    }
+
     let has_staged = has_staged_changes(&repo)?;
+    let update_hint = update::spawn_hint_check();
 
     // Load ignore patterns from config + .repoignore
     let config = Config::load().unwrap_or_default();
+
+    // Resolve AI provider up front so a missing CLI fails before any staging
+    let provider = resolve_provider(cli_ai, &config)?;
     let repo_root = repo
         .workdir()
         .map(|p| p.to_path_buf())
@@ -129,20 +480,20 @@ pub fn run_commit_workflow(
         if amend {
             // Amend with no changes: just edit message (handled below)
         } else {
-            if ignored_count > 0 {
+            if ignored_count > 0 && !is_quiet() {
                 println!(
                     "  {} {} file(s) hidden by .repoignore",
                     "⊘".dimmed(),
                     ignored_count
                 );
             }
-            bail!("Nothing to commit. Working tree clean.");
+            bail!(RepoError::NothingToCommit);
         }
     }
 
     // Offer to stage unstaged files if nothing staged yet
     if !has_staged && unstaged > 0 {
-        if ignored_count > 0 {
+        if ignored_count > 0 && !is_quiet() {
             println!(
                 "  {} {} file(s) hidden by .repoignore",
                 "⊘".dimmed(),
@@ -155,21 +506,23 @@ pub fn run_commit_workflow(
                 "{} {} unstaged file(s). Add to last commit? [Y/n] {}  ",
                 "?".yellow().bold(),
                 unstaged,
-                "l=list d=diff s=select".dimmed()
+                "l=list d=diff p=pick".dimmed()
             )
         } else {
             format!(
                 "{} {} unstaged file(s). Stage all? [y/N] {}  ",
                 "?".yellow().bold(),
                 unstaged,
-                "l=list d=diff s=select".dimmed()
+                "l=list d=diff p=pick".dimmed()
             )
         };
 
         // Non-interactive: auto-stage all
         if !interactive {
             stage_all(&repo, ignore_set.as_ref())?;
-            println!("{} Staged {} file(s)", "✓".green(), unstaged);
+            if !is_quiet() {
+                println!("{} Staged {} file(s)", "✓".green(), unstaged);
+            }
         } else {
             let all_files = visible_files;
 
@@ -201,26 +554,18 @@ pub fn run_commit_workflow(
                         let diff = get_unstaged_diff(&repo)?;
                         println!();
                         for line in diff.lines() {
-                            if line.starts_with('+') && !line.starts_with("+++") {
-                                println!("{}", line.green());
-                            } else if line.starts_with('-') && !line.starts_with("---") {
-                                println!("{}", line.red());
-                            } else if line.starts_with("@@") {
-                                println!("{}", line.cyan());
-                            } else {
-                                println!("{}", line);
-                            }
+                            println!("{}", colorize_diff_line(line));
                         }
                         println!();
                     }
-                    "s" => {
+                    "p" | "s" => {
                         match run_file_selector(&all_files, repo.path(), &repo)? {
                             FileSelection::Selected(paths) if paths.is_empty() => {
                                 println!("  {}", "No files selected.".dimmed());
                             }
                             FileSelection::Selected(paths) => {
                                 let count = paths.len();
-                                stage_files(&repo, &paths)?;
+                                stage_paths(&repo, &paths)?;
                                 println!("{} Staged {} file(s):", "✓".green(), count);
                                 for p in &paths {
                                     println!("  {}", p);
@@ -241,11 +586,11 @@ pub fn run_commit_workflow(
                                 .collect();
                             if tracked.is_empty() {
                                 bail!(
-                                    "No tracked changes to commit. Only untracked files present — rerun and answer 'y' to include them, or use 's' to select."
+                                    "No tracked changes to commit. Only untracked files present — rerun and answer 'y' to include them, or use 'p' to pick files."
                                 );
                             }
                             let count = tracked.len();
-                            stage_files(&repo, &tracked)?;
+                            stage_paths(&repo, &tracked)?;
                             println!(
                                 "{} Staged {} tracked file(s) {}",
                                 "✓".green(),
@@ -266,12 +611,12 @@ pub fn run_commit_workflow(
                         if amend {
                             println!(
                                 "  {}",
-                                "y=stage all  n=skip  l=list  d=diff  s=select files".dimmed()
+                                "y=stage all  n=skip  l=list  d=diff  p=pick files".dimmed()
                             );
                         } else {
                             println!(
                                 "  {}",
-                                "y=stage all  n=tracked only  c=cancel  l=list  d=diff  s=select"
+                                "y=stage all  n=tracked only  c=cancel  l=list  d=diff  p=pick"
                                     .dimmed()
                             );
                         }
@@ -304,7 +649,7 @@ pub fn run_commit_workflow(
             "{} {} file(s) not staged. Include? [y/N] {}  ",
             "?".yellow().bold(),
             note,
-            "s=select l=list d=diff".dimmed()
+            "p=pick l=list d=diff".dimmed()
         );
 
         loop {
@@ -330,26 +675,18 @@ pub fn run_commit_workflow(
                     let diff = get_unstaged_diff(&repo)?;
                     println!();
                     for line in diff.lines() {
-                        if line.starts_with('+') && !line.starts_with("+++") {
-                            println!("{}", line.green());
-                        } else if line.starts_with('-') && !line.starts_with("---") {
-                            println!("{}", line.red());
-                        } else if line.starts_with("@@") {
-                            println!("{}", line.cyan());
-                        } else {
-                            println!("{}", line);
-                        }
+                        println!("{}", colorize_diff_line(line));
                     }
                     println!();
                 }
-                "s" => {
+                "p" | "s" => {
                     match run_file_selector(&all_files, repo.path(), &repo)? {
                         FileSelection::Selected(paths) if paths.is_empty() => {
                             println!("  {}", "No files selected.".dimmed());
                         }
                         FileSelection::Selected(paths) => {
                             let count = paths.len();
-                            stage_files(&repo, &paths)?;
+                            stage_paths(&repo, &paths)?;
                             println!("{} Staged {} file(s):", "✓".green(), count);
                             for p in &paths {
                                 println!("  {}", p);
@@ -364,7 +701,7 @@ pub fn run_commit_workflow(
                 _ => {
                     println!(
                         "  {}",
-                        "y=stage all  n=proceed with current  s=select  l=list  d=diff  c=cancel"
+                        "y=stage all  n=proceed with current  p=pick  l=list  d=diff  c=cancel"
                             .dimmed()
                     );
                 }
@@ -373,18 +710,28 @@ pub fn run_commit_workflow(
     }
 
     // For amend mode indicator
-    let commit_fn: Box<dyn Fn(&Repository, &str) -> Result<git2::Oid>> = if amend {
-        println!("{} Amending last commit", "●".yellow());
-        Box::new(|r, m| amend_commit(r, m))
+    let commit_op: fn(&Repository, &str) -> Result<git2::Oid> = if amend {
+        if !is_quiet() {
+            println!("{} Amending last commit", "●".yellow());
+        }
+        amend_commit
     } else {
-        Box::new(|r, m| create_commit(r, m))
+        create_commit
+    };
+    // Apply commit.cleanup like `git commit`; `edited` = came through an editor
+    let commit_fn = |r: &Repository, m: &str, edited: bool| {
+        let (mode, comment) = get_cleanup_config(r);
+        let m = apply_body_wrap(m.to_string(), &config);
+        commit_op(r, &cleanup_message(&m, mode, comment, edited))
     };
 
-    // Load config
-    let config = Config::load().unwrap_or_default();
-
-    // Resolve AI provider: CLI flag > config > auto-detect
-    let provider = resolve_provider(cli_ai, &config)?;
+    // Before the diff goes anywhere, including the AI provider
+    if !allow_conflict_markers {
+        check_conflicts(&repo, &get_staged_diff(&repo)?)?;
+    }
+    if config.scan_secrets {
+        check_secrets(&get_staged_diff(&repo)?, interactive)?;
+    }
 
     // Resolve model: CLI flag > config > provider default
     let model = cli_model.or_else(|| config.commit_model.clone());
@@ -395,50 +742,81 @@ pub fn run_commit_workflow(
     } else {
         get_staged_diff(&repo)?
     };
+    // what the AI sees; the diff view and stat keep every file
+    let ai_diff = if include_all { diff.clone() } else { exclude_ai_diff_files(&diff, &config) };
     let staged_files = get_staged_files(&repo)?;
+    let scope = scope::infer_scope(&config.scope_map, &staged_files);
 
     // For amend: keep existing message (squash-like behavior)
     // For new commit: generate with AI
     let mut message = if amend {
         let existing = get_last_commit_message(&repo)?;
-        println!(
-            "{} Keeping existing message {}",
-            "●".cyan(),
-            "(r=regenerate)".dimmed()
-        );
+        if !is_quiet() {
+            println!(
+                "{} Keeping existing message {}",
+                "●".cyan(),
+                "(r=regenerate)".dimmed()
+            );
+        }
         existing
     } else {
         let model_display = model.as_deref().map(|m| format!("/{}", m)).unwrap_or_default();
-        println!(
-            "{} Generating commit message with {}{}...",
-            "●".cyan(),
-            provider.name().bold(),
-            model_display.dimmed()
-        );
+        let scope_display = scope.as_deref().map(|s| format!(" (scope: {})", s)).unwrap_or_default();
         let style = config.commit_style.as_deref();
-        generate_commit_message(provider, &diff, style, model.as_deref())?
+        with_spinner(
+            format!(
+                "Generating commit message with {}{}{}...",
+                provider.name().bold(),
+                model_display.dimmed(),
+                scope_display.dimmed()
+            ),
+            || generate_commit_message(provider, &ai_diff, style, scope.as_deref(), model.as_deref()),
+        )?
     };
+    // wrap before the preview so what's shown is what gets committed
+    message = apply_body_wrap(message, &config);
 
     let action_word = if amend { "Amended" } else { "Committed" };
 
     if !interactive {
         // Non-interactive: commit directly
-        let oid = commit_fn(&repo, &message)?;
-        println!("{}", message.bold());
-        println!(
-            "{} {}: {}",
-            "✓".green(),
-            action_word,
-            &oid.to_string()[..7].dimmed()
-        );
-        notify_update_available();
+        if !report_lint(&message, &config, strict) {
+            bail!("Commit message fails lint (--strict)");
+        }
+        let mut oid = commit_fn(&repo, &message, false)?;
+        if !is_quiet() {
+            println!("{}", message.bold());
+        }
+        print_committed(action_word, oid);
+        if edit_after {
+            let committed = get_last_commit_message(&repo)?;
+            if let Some(edited) = changed_message(&committed, edit_with_editor(&committed)?) {
+                let (mode, comment) = get_cleanup_config(&repo);
+                oid = amend_commit(&repo, &cleanup_message(&edited, mode, comment, true))?;
+                print_committed("Amended", oid);
+            } else if !is_quiet() {
+                println!("  {}", "Message unchanged.".dimmed());
+            }
+        }
+        post_commit(&repo, &config, oid);
+        notify_update_available(&update_hint);
         return Ok(());
     }
 
     // Interactive: show message and prompt
+    let original = amend.then(|| message.clone());
+    if amend {
+        println!("  {} {}", "Amending with:".dimmed(), format_diff_stat(&diff));
+    }
+    let mut edited = false;
     loop {
         println!();
+        if let Some(original) = &original {
+            let subject = original.lines().next().unwrap_or("");
+            println!("  {} {}", "Current:".dimmed(), subject.dimmed());
+        }
         print_message_box(&message, config.message_box_style);
+        let lint_ok = report_lint(&message, &config, strict);
         println!();
 
         print!(
@@ -452,36 +830,37 @@ pub fn run_commit_workflow(
         io::stdin().read_line(&mut input)?;
 
         match input.trim().to_lowercase().as_str() {
+            "y" if !lint_ok => {
+                println!("  {} Fix the lint warnings first (e=edit r=regen)", "✗".red());
+            }
             "y" => {
-                let oid = commit_fn(&repo, &message)?;
-                println!(
-                    "{} {}: {}",
-                    "✓".green(),
-                    action_word,
-                    &oid.to_string()[..7].dimmed()
-                );
-                notify_update_available();
+                let oid = commit_fn(&repo, &message, edited)?;
+                print_committed(action_word, oid);
+                post_commit(&repo, &config, oid);
+                notify_update_available(&update_hint);
                 break;
             }
             "e" => {
                 // Open TUI for editing
-                let app = CommitApp::new(message.clone(), diff.clone(), provider, staged_files.clone());
+                let app = CommitApp::new(message.clone(), diff.clone(), provider, staged_files.clone())
+                    .with_editor_key(config.commit_editor_key)
+                    .with_scope(scope.clone())
+                    .with_lint(config.commit.clone(), strict)
+                    .with_original(original.clone())
+                    .with_ai_diff(ai_diff.clone());
                 let (final_message, result) = run_commit_tui(app)?;
 
                 match result {
                     TuiResult::Commit => {
-                        let oid = commit_fn(&repo, &final_message)?;
-                        println!(
-                            "{} {}: {}",
-                            "✓".green(),
-                            action_word,
-                            &oid.to_string()[..7].dimmed()
-                        );
-                        notify_update_available();
+                        let oid = commit_fn(&repo, &final_message, true)?;
+                        print_committed(action_word, oid);
+                        post_commit(&repo, &config, oid);
+                        notify_update_available(&update_hint);
                         break;
                     }
                     TuiResult::Cancel => {
                         // Return to prompt with current message
+                        edited |= final_message != message;
                         message = final_message;
                     }
                 }
@@ -513,8 +892,11 @@ pub fn run_commit_workflow(
                     _ => Some(style_input), // Custom instruction
                 };
 
-                println!("{} Regenerating...", "●".cyan());
-                message = generate_commit_message(provider, &diff, style, model.as_deref())?;
+                edited = false;
+                message = with_spinner("Regenerating...".to_string(), || {
+                    generate_commit_message(provider, &ai_diff, style, scope.as_deref(), model.as_deref())
+                })
+                .map(|m| apply_body_wrap(m, &config))?;
             }
             "d" => {
                 if diff.is_empty() {
@@ -523,15 +905,7 @@ pub fn run_commit_workflow(
                 }
                 println!();
                 for line in diff.lines() {
-                    if line.starts_with('+') && !line.starts_with("+++") {
-                        println!("{}", line.green());
-                    } else if line.starts_with('-') && !line.starts_with("---") {
-                        println!("{}", line.red());
-                    } else if line.starts_with("@@") {
-                        println!("{}", line.cyan());
-                    } else {
-                        println!("{}", line);
-                    }
+                    println!("{}", colorize_diff_line(line));
                 }
             }
             _ => {
@@ -544,6 +918,54 @@ pub fn run_commit_workflow(
     Ok(())
 }
 
+/// `3 files, +10/-2` for a unified diff (as from `get_amend_diff`)
+fn format_diff_stat(diff: &str) -> String {
+    let (mut files, mut added, mut removed) = (0, 0, 0);
+    for line in diff.lines() {
+        if line.starts_with("diff --git ") {
+            files += 1;
+        } else if line.starts_with('+') && !line.starts_with("+++") {
+            added += 1;
+        } else if line.starts_with('-') && !line.starts_with("---") {
+            removed += 1;
+        }
+    }
+    if files == 0 {
+        return "no changes".to_string();
+    }
+    let noun = if files == 1 { "file" } else { "files" };
+    format!("{} {}, +{}/-{}", files, noun, added, removed)
+}
+
+/// `message` wrapped at `body_wrap`, if set
+fn apply_body_wrap(message: String, config: &Config) -> String {
+    match config.body_wrap {
+        Some(width) => wrap_body(&message, width),
+        None => message,
+    }
+}
+
+/// Print lint warnings for `message`; false if `strict` and there are any
+fn report_lint(message: &str, config: &Config, strict: bool) -> bool {
+    let warnings = lint_message(message, &config.commit);
+    for warning in &warnings {
+        println!("  {} {}", "⚠".yellow(), warning);
+    }
+    !strict || warnings.is_empty()
+}
+
+/// Best-effort `post_commit_command`: failures are reported, never fatal
+fn post_commit(repo: &Repository, config: &Config, oid: git2::Oid) {
+    let Some(command) = config.post_commit_command.as_deref() else {
+        return;
+    };
+    match post_commit::run_post_commit_command(repo, command, oid) {
+        Ok(status) if status.success() => {}
+        Ok(status) => eprintln!("{} post_commit_command exited with {}", "⚠".yellow(), status),
+        Err(e) => eprintln!("{} post_commit_command: {}", "⚠".yellow(), e),
+    }
+}
+
 /// Print numbered file list
 fn print_file_list(files: &[(String, char)]) {
     println!();
@@ -599,9 +1021,8 @@ fn run_file_selector(
     let mut confirm_stats: Vec<crate::git::FileStat> = Vec::new();
     let mut confirm_paths: Vec<String> = Vec::new();
 
-    terminal::enable_raw_mode()?;
+    let _guard = TuiGuard::enter()?;
     let mut out = stdout();
-    out.execute(EnterAlternateScreen)?;
     out.execute(cursor::Hide)?;
 
     let result = (|| -> Result<FileSelection> {
@@ -782,9 +1203,6 @@ fn run_file_selector(
         }
     })();
 
-    out.execute(cursor::Show)?;
-    out.execute(LeaveAlternateScreen)?;
-    terminal::disable_raw_mode()?;
     result
 }
 
@@ -1014,6 +1432,8 @@ fn draw_confirm(
     for line in &lines[start..end] {
         if line.starts_with("+++") || line.starts_with("---") {
             write!(out, "  {}\r\n", line.dimmed())?;
+        } else if line.starts_with(BINARY_MARKER) {
+            write!(out, "  {}\r\n", line.magenta().italic())?;
         } else if line.starts_with('+') {
             write!(out, "  {}\r\n", line.green())?;
         } else if line.starts_with('-') {
@@ -1049,16 +1469,32 @@ fn draw_confirm(
 }
 
 fn truncate_path(path: &str, max: usize) -> String {
-    if path.len() <= max {
+    let len = path.chars().count();
+    if len <= max {
         format!("{:<width$}", path, width = max)
     } else {
-        let keep = max.saturating_sub(1);
-        let tail = &path[path.len().saturating_sub(keep)..];
+        let tail: String = path.chars().skip(len - max.saturating_sub(1)).collect();
         format!("…{}", tail)
     }
 }
 
+/// Fail with `AiUnavailable` unless a provider resolves, for callers that
+/// touch the index before `run_commit_workflow` runs
+pub fn ensure_provider(cli_ai: Option<&str>) -> Result<()> {
+    let config = Config::load().unwrap_or_default();
+    resolve_provider(cli_ai.map(str::to_string), &config).map(|_| ())
+}
+
+/// Resolve AI provider: CLI flag > config > auto-detect
 fn resolve_provider(cli_ai: Option<String>, config: &Config) -> Result<AiProvider> {
+    resolve_provider_with(cli_ai, config, detect_provider)
+}
+
+fn resolve_provider_with(
+    cli_ai: Option<String>,
+    config: &Config,
+    detect: impl FnOnce() -> Option<AiProvider>,
+) -> Result<AiProvider> {
     // Priority 1: CLI flag
     if let Some(ref name) = cli_ai {
         return AiProvider::from_str(name)
@@ -1073,7 +1509,128 @@ fn resolve_provider(cli_ai: Option<String>, config: &Config) -> Result<AiProvide
     }
 
     // Priority 3: Auto-detect
-    detect_provider().context(
-        "No AI CLI found. Install claude, codex, or gemini CLI, or specify with --ai flag.",
-    )
+    detect().ok_or_else(|| RepoError::AiUnavailable.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changed_message_decides_amend() {
+        let committed = "feat: add login";
+        assert_eq!(changed_message(committed, None), None);
+        assert_eq!(changed_message(committed, Some("feat: add login\n".into())), None);
+        assert_eq!(changed_message(committed, Some("  ".into())), None);
+        assert_eq!(
+            changed_message(committed, Some("feat: add OAuth login".into())).as_deref(),
+            Some("feat: add OAuth login")
+        );
+    }
+
+    #[test]
+    fn test_has_conflict_markers() {
+        let diff = "\
++++ b/src/a.rs
+@@ -1,2 +1,6 @@
++<<<<<<< HEAD
++let x = 1;
++=======
++let x = 2;
++>>>>>>> feature
++++ b/README.md
+@@ -1 +1,3 @@
++Title
++=======
++<<<<<<<< not a marker
++++ b/src/b.rs
+@@ -1 +1 @@
+ <<<<<<< context lines don't count
++>>>>>>>
+";
+        assert_eq!(has_conflict_markers(diff), ["src/a.rs", "src/b.rs"]);
+        assert!(has_conflict_markers("+++ b/x\n+fine\n").is_empty());
+    }
+
+    #[test]
+    fn test_wrap_body() {
+        let subject = format!("feat: {}", "long ".repeat(20));
+        assert_eq!(wrap_body(&subject, 20), subject);
+
+        let bullets = "fix: x\n\n- first point that runs on\n  * nested item here\n";
+        assert_eq!(
+            wrap_body(bullets, 16),
+            "fix: x\n\n- first point\n  that runs on\n  * nested item\n    here\n"
+        );
+
+        let para = "docs: y\n\nA paragraph that is longer than the width, with https://example.com/a/very/long/url kept whole.";
+        assert_eq!(
+            wrap_body(para, 24),
+            "docs: y\n\nA paragraph that is\nlonger than the width,\nwith\nhttps://example.com/a/very/long/url\nkept whole."
+        );
+        assert_eq!(wrap_body("chore: z\n\n# comment that stays on one long line", 10), "chore: z\n\n# comment that stays on one long line");
+
+        let signed = "fix: x\n\nBody text that wraps here\n\nSigned-off-by: Some Long Name <some.long.name@example.com>\nCo-authored-by: Another Person <another@example.com>\n";
+        assert_eq!(
+            wrap_body(signed, 20),
+            "fix: x\n\nBody text that wraps\nhere\n\nSigned-off-by: Some Long Name <some.long.name@example.com>\nCo-authored-by: Another Person <another@example.com>\n"
+        );
+        // a body paragraph that merely contains a colon still wraps
+        assert_eq!(wrap_body("fix: x\n\nSee the docs: it is long", 14), "fix: x\n\nSee the docs:\nit is long");
+    }
+
+    #[test]
+    fn test_truncate_path() {
+        assert_eq!(truncate_path("src/a.rs", 10), "src/a.rs  ");
+        assert_eq!(truncate_path("src/long/path.rs", 8), "…path.rs");
+        assert_eq!(truncate_path("docs/日本語のファイル.md", 8), "…ファイル.md");
+    }
+
+    #[test]
+    fn test_format_diff_stat() {
+        let diff = "diff --git a/a.rs b/a.rs\n--- a/a.rs\n+++ b/a.rs\n@@ -1,2 +1,2 @@\n-old\n+new\n+more\n\
+                    diff --git a/b.rs b/b.rs\n--- a/b.rs\n+++ b/b.rs\n@@ -1 +0,0 @@\n-gone\n";
+        assert_eq!(format_diff_stat(diff), "2 files, +2/-2");
+        assert_eq!(format_diff_stat(""), "no changes");
+    }
+
+    #[test]
+    fn test_resolve_provider_guidance() {
+        let config = Config::default();
+        let err = resolve_provider_with(None, &config, || None).unwrap_err();
+        assert!(err.to_string().contains("--ai"));
+        assert!(err.to_string().contains("default_ai"));
+
+        let found = resolve_provider_with(None, &config, || AiProvider::from_str("codex"));
+        assert_eq!(found.unwrap().name(), "codex");
+        assert!(resolve_provider_with(Some("nope".into()), &config, || None).is_err());
+    }
+
+    #[test]
+    fn test_select_box_width() {
+        // Not a TTY: fixed fallback regardless of content
+        assert_eq!(select_box_width(None, 10), 50);
+        assert_eq!(select_box_width(None, 200), 50);
+        // Short message: clamped up to the minimum
+        assert_eq!(select_box_width(Some(120), 10), 40);
+        // Fits the longest line plus padding
+        assert_eq!(select_box_width(Some(120), 60), 64);
+        // Long message: capped at the maximum
+        assert_eq!(select_box_width(Some(200), 150), 100);
+        // Narrow terminal: capped at terminal width minus borders
+        assert_eq!(select_box_width(Some(70), 150), 68);
+        // Very narrow terminal never goes below the minimum
+        assert_eq!(select_box_width(Some(20), 150), 40);
+    }
+
+    #[test]
+    fn test_wrap_line() {
+        assert_eq!(wrap_line("short", 10), vec!["short"]);
+        assert_eq!(
+            wrap_line("feat: add the thing", 10),
+            vec!["feat: add", "the thing"]
+        );
+        assert_eq!(wrap_line("abcdefghij", 4), vec!["abcd", "efgh", "ij"]);
+        assert_eq!(wrap_line("日本語テキスト", 6), vec!["日本語", "テキス", "ト"]);
+    }
 }
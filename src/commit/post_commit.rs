@@ -0,0 +1,60 @@
+use std::process::{Command, ExitStatus};
+
+use anyhow::{Context, Result};
+use git2::{Oid, Repository};
+
+/// Run the configured `post_commit_command` through `sh -c` in the repo root
+/// with `REPO_COMMIT_SHA`, `REPO_COMMIT_MSG` and `REPO_BRANCH` set
+pub fn run_post_commit_command(repo: &Repository, command: &str, oid: Oid) -> Result<ExitStatus> {
+    let commit = repo.find_commit(oid)?;
+    let branch = repo
+        .head()
+        .ok()
+        .filter(|h| h.is_branch())
+        .and_then(|h| h.shorthand().map(String::from))
+        .unwrap_or_default();
+    let workdir = repo.workdir().unwrap_or_else(|| repo.path());
+
+    Command::new("sh")
+        .args(["-c", command])
+        .current_dir(workdir)
+        .env("REPO_COMMIT_SHA", oid.to_string())
+        .env("REPO_COMMIT_MSG", commit.message().unwrap_or(""))
+        .env("REPO_BRANCH", branch)
+        .status()
+        .with_context(|| format!("failed to run `{}`", command))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_post_commit_command_env() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        let tree = repo.find_tree(repo.index().unwrap().write_tree().unwrap()).unwrap();
+        let oid = repo
+            .commit(Some("HEAD"), &sig, &sig, "feat: add thing\n", &tree, &[])
+            .unwrap();
+
+        let status = run_post_commit_command(
+            &repo,
+            r#"printf '%s|%s|%s' "$REPO_COMMIT_SHA" "$REPO_BRANCH" "$REPO_COMMIT_MSG" > out.txt"#,
+            oid,
+        )
+        .unwrap();
+        assert!(status.success());
+
+        let branch = repo.head().unwrap().shorthand().unwrap().to_string();
+        assert_eq!(
+            fs::read_to_string(dir.path().join("out.txt")).unwrap(),
+            format!("{}|{}|feat: add thing\n", oid, branch)
+        );
+
+        assert!(!run_post_commit_command(&repo, "exit 3", oid).unwrap().success());
+    }
+}
@@ -0,0 +1,97 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+use globset::{Glob, GlobMatcher};
+
+/// Pick the conventional-commit scope for the staged files from a
+/// `scope_map` of path globs. Each file counts toward the most specific glob
+/// it matches (see [`specificity`]); the most common scope wins and ties are
+/// listed as `a,b`.
+pub fn infer_scope(scope_map: &BTreeMap<String, String>, files: &[String]) -> Option<String> {
+    let matchers: Vec<(GlobMatcher, &str, (usize, usize))> = scope_map
+        .iter()
+        .filter_map(|(pattern, scope)| {
+            Glob::new(pattern)
+                .ok()
+                .map(|g| (g.compile_matcher(), scope.as_str(), specificity(pattern)))
+        })
+        .collect();
+
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for file in files {
+        let best = matchers
+            .iter()
+            .filter(|(m, _, _)| m.is_match(file))
+            .min_by_key(|(_, _, rank)| Reverse(*rank));
+        if let Some((_, scope, _)) = best {
+            *counts.entry(scope).or_default() += 1;
+        }
+    }
+
+    let max = *counts.values().max()?;
+    let top: Vec<&str> = counts
+        .into_iter()
+        .filter(|&(_, n)| n == max)
+        .map(|(scope, _)| scope)
+        .collect();
+    Some(top.join(","))
+}
+
+/// Longer literal prefix (before the first `*?[{`) first, then longer
+/// pattern: `src/ui/**` beats `src/**`, which beats `**/*.rs`. Equal ranks
+/// fall back to the map's (lexicographic) order.
+fn specificity(pattern: &str) -> (usize, usize) {
+    let literal = pattern.find(['*', '?', '[', '{']).unwrap_or(pattern.len());
+    (literal, pattern.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map() -> BTreeMap<String, String> {
+        BTreeMap::from([
+            ("packages/api/**".to_string(), "api".to_string()),
+            ("packages/web/**".to_string(), "web".to_string()),
+        ])
+    }
+
+    fn files(paths: &[&str]) -> Vec<String> {
+        paths.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn test_infer_scope_dominant() {
+        let staged = files(&[
+            "packages/api/src/main.rs",
+            "packages/api/Cargo.toml",
+            "packages/web/index.ts",
+            "README.md",
+        ]);
+        assert_eq!(infer_scope(&map(), &staged).as_deref(), Some("api"));
+    }
+
+    #[test]
+    fn test_infer_scope_tie() {
+        let staged = files(&["packages/web/app.ts", "packages/api/lib.rs"]);
+        assert_eq!(infer_scope(&map(), &staged).as_deref(), Some("api,web"));
+    }
+
+    #[test]
+    fn test_infer_scope_most_specific_glob() {
+        let overlapping = BTreeMap::from([
+            ("**/*.rs".to_string(), "rust".to_string()),
+            ("packages/**".to_string(), "packages".to_string()),
+            ("packages/api/**".to_string(), "api".to_string()),
+        ]);
+        let staged = files(&["packages/api/src/lib.rs", "packages/api/build.rs", "packages/web/a.ts"]);
+        assert_eq!(infer_scope(&overlapping, &staged).as_deref(), Some("api"));
+        assert_eq!(infer_scope(&overlapping, &files(&["src/main.rs"])).as_deref(), Some("rust"));
+    }
+
+    #[test]
+    fn test_infer_scope_unmapped() {
+        assert_eq!(infer_scope(&map(), &files(&["docs/a.md"])), None);
+        assert_eq!(infer_scope(&BTreeMap::new(), &files(&["packages/api/a"])), None);
+    }
+}
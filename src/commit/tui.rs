@@ -1,8 +1,11 @@
-use std::io::stdout;
+use std::cell::Cell;
+use std::io::{stdout, Stdout};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
 
 use anyhow::Result;
 use crossterm::{
-    event::{self, Event, KeyCode, KeyEventKind},
+    event::{Event, KeyCode, KeyEventKind},
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
     ExecutableCommand,
 };
@@ -12,6 +15,19 @@ use ratatui::{
 };
 
 use crate::ai::{generate_commit_message, AiProvider};
+use crate::commit::lint::lint_message;
+use crate::config::CommitConfig;
+use crate::git::BINARY_MARKER;
+use crate::render::help::{footer_spans, render_help_overlay, KeyHelp};
+use crate::render::redraw::Redraw;
+use crate::render::scroll::{cursor_line, follow_cursor, move_lines, next_char, prev_char, PAGE_LINES};
+use crate::reword::edit_with_editor;
+use crate::terminal::TuiGuard;
+
+const SPINNER_FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// Review-mode keys that `commit_editor_key` can't take over
+pub const REVIEW_KEYS: [char; 6] = ['y', 'q', 'r', 'e', 'd', '?'];
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Mode {
@@ -32,11 +48,25 @@ pub struct CommitApp {
     pub provider: AiProvider,
     pub staged_files: Vec<String>,
     mode: Mode,
+    /// Byte offset into `message`, always on a char boundary
     cursor_pos: usize,
+    message_scroll: Cell<u16>,
     diff_scroll: u16,
     should_quit: bool,
     result: Option<TuiResult>,
     status: String,
+    editor_key: char,
+    editor_requested: bool,
+    scope: Option<String>,
+    show_help: bool,
+    lint_rules: Option<CommitConfig>,
+    strict: bool,
+    original: Option<String>,
+    /// What regeneration sends the AI when it differs from `diff`
+    ai_diff: Option<String>,
+    /// Background regeneration started by `r`
+    pending: Option<Receiver<Result<String>>>,
+    spinner_frame: usize,
 }
 
 impl CommitApp {
@@ -53,14 +83,142 @@ impl CommitApp {
             provider,
             staged_files,
             mode: Mode::Review,
+            message_scroll: Cell::new(0),
             diff_scroll: 0,
             should_quit: false,
             result: None,
             status: String::new(),
+            editor_key: 'E',
+            editor_requested: false,
+            scope: None,
+            show_help: false,
+            lint_rules: None,
+            strict: false,
+            original: None,
+            ai_diff: None,
+            pending: None,
+            spinner_frame: 0,
+        }
+    }
+
+    /// Override the key that opens $EDITOR from Review mode
+    pub fn with_editor_key(mut self, key: char) -> Self {
+        self.editor_key = key;
+        self
+    }
+
+    /// Scope hint passed to the AI when regenerating
+    pub fn with_scope(mut self, scope: Option<String>) -> Self {
+        self.scope = scope;
+        self
+    }
+
+    /// Show lint warnings in the footer; `strict` refuses to commit on any
+    pub fn with_lint(mut self, rules: CommitConfig, strict: bool) -> Self {
+        self.lint_rules = Some(rules);
+        self.strict = strict;
+        self
+    }
+
+    /// Message being amended, shown dimmed above the editable one
+    pub fn with_original(mut self, original: Option<String>) -> Self {
+        self.original = original;
+        self
+    }
+
+    /// Diff to regenerate from, e.g. with `ai_diff_exclude` files cut out;
+    /// the diff view still shows the full one
+    pub fn with_ai_diff(mut self, diff: String) -> Self {
+        self.ai_diff = Some(diff);
+        self
+    }
+
+    /// Regenerate on a background thread so the UI keeps drawing
+    fn start_regenerate(&mut self) {
+        let (tx, rx) = mpsc::channel();
+        let diff = self.ai_diff.clone().unwrap_or_else(|| self.diff.clone());
+        let (provider, scope) = (self.provider, self.scope.clone());
+        thread::spawn(move || {
+            let _ = tx.send(generate_commit_message(provider, &diff, None, scope.as_deref(), None));
+        });
+        self.pending = Some(rx);
+        self.status = "Regenerating...".to_string();
+    }
+
+    /// Pick up a finished regeneration; true while one is still running
+    fn poll_regenerate(&mut self) -> bool {
+        let Some(rx) = &self.pending else {
+            return false;
+        };
+        let result = match rx.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => {
+                self.spinner_frame = (self.spinner_frame + 1) % SPINNER_FRAMES.len();
+                return true;
+            }
+            Err(TryRecvError::Disconnected) => Err(anyhow::anyhow!("generation thread exited")),
+        };
+        self.pending = None;
+        match result {
+            Ok(msg) => {
+                self.message = msg;
+                self.cursor_pos = self.message.len();
+                self.status = "Message regenerated".to_string();
+            }
+            Err(e) => self.status = format!("Error: {}", e),
+        }
+        false
+    }
+
+    fn lint_summary(&self) -> Option<String> {
+        let warnings = lint_message(&self.message, self.lint_rules.as_ref()?);
+        if warnings.is_empty() {
+            return None;
+        }
+        let list: Vec<String> = warnings.iter().map(|w| w.to_string()).collect();
+        Some(list.join("; "))
+    }
+
+    /// Keys for the current mode, shown in the footer and the `?` overlay
+    fn mode_keys(&self) -> Vec<KeyHelp> {
+        match self.mode {
+            Mode::Review => vec![
+                KeyHelp::new("y/Enter", "commit"),
+                KeyHelp::new("q/Esc", "cancel"),
+                KeyHelp::new("r", "regenerate"),
+                KeyHelp::new("e", "edit"),
+                KeyHelp::new(self.editor_key.to_string(), "$EDITOR"),
+                KeyHelp::new("d", "view diff"),
+                KeyHelp::new("?", "help"),
+            ],
+            Mode::Edit => KeyHelp::list(&[
+                ("Esc", "done editing"),
+                ("←→ Home/End", "move cursor"),
+                ("PgUp/PgDn", "move a page"),
+                ("Enter", "new line"),
+            ]),
+            Mode::DiffView => KeyHelp::list(&[
+                ("d/Esc", "close"),
+                ("j/k", "scroll"),
+                ("PgUp/PgDn", "fast scroll"),
+                ("?", "help"),
+            ]),
         }
     }
 
     fn handle_key(&mut self, key: KeyCode) {
+        if self.show_help {
+            if matches!(key, KeyCode::Char('?') | KeyCode::Esc) {
+                self.show_help = false;
+            }
+            return;
+        }
+        // `?` is a literal character while editing
+        if key == KeyCode::Char('?') && self.mode != Mode::Edit {
+            self.show_help = true;
+            return;
+        }
+
         match self.mode {
             Mode::Review => self.handle_review_key(key),
             Mode::Edit => self.handle_edit_key(key),
@@ -69,8 +227,16 @@ impl CommitApp {
     }
 
     fn handle_review_key(&mut self, key: KeyCode) {
+        // only cancelling works until the new message arrives
+        if self.pending.is_some() && !matches!(key, KeyCode::Char('q') | KeyCode::Esc) {
+            return;
+        }
         match key {
             KeyCode::Char('y') | KeyCode::Enter => {
+                if self.strict && self.lint_summary().is_some() {
+                    self.status = "Fix lint warnings first (--strict)".to_string();
+                    return;
+                }
                 self.result = Some(TuiResult::Commit);
                 self.should_quit = true;
             }
@@ -78,18 +244,10 @@ impl CommitApp {
                 self.result = Some(TuiResult::Cancel);
                 self.should_quit = true;
             }
-            KeyCode::Char('r') => {
-                self.status = "Regenerating...".to_string();
-                match generate_commit_message(self.provider, &self.diff, None, None) {
-                    Ok(msg) => {
-                        self.message = msg;
-                        self.cursor_pos = self.message.len();
-                        self.status = "Message regenerated".to_string();
-                    }
-                    Err(e) => {
-                        self.status = format!("Error: {}", e);
-                    }
-                }
+            KeyCode::Char('r') => self.start_regenerate(),
+            // checked before `e` so a custom key can't be shadowed by the inline editor
+            KeyCode::Char(c) if c == self.editor_key => {
+                self.editor_requested = true;
             }
             KeyCode::Char('e') => {
                 self.mode = Mode::Edit;
@@ -114,7 +272,7 @@ impl CommitApp {
             }
             KeyCode::Backspace => {
                 if self.cursor_pos > 0 {
-                    self.cursor_pos -= 1;
+                    self.cursor_pos = prev_char(&self.message, self.cursor_pos);
                     self.message.remove(self.cursor_pos);
                 }
             }
@@ -124,10 +282,10 @@ impl CommitApp {
                 }
             }
             KeyCode::Left => {
-                self.cursor_pos = self.cursor_pos.saturating_sub(1);
+                self.cursor_pos = prev_char(&self.message, self.cursor_pos);
             }
             KeyCode::Right => {
-                self.cursor_pos = (self.cursor_pos + 1).min(self.message.len());
+                self.cursor_pos = next_char(&self.message, self.cursor_pos);
             }
             KeyCode::Home => {
                 self.cursor_pos = 0;
@@ -135,9 +293,15 @@ impl CommitApp {
             KeyCode::End => {
                 self.cursor_pos = self.message.len();
             }
+            KeyCode::PageUp => {
+                self.cursor_pos = move_lines(&self.message, self.cursor_pos, -PAGE_LINES);
+            }
+            KeyCode::PageDown => {
+                self.cursor_pos = move_lines(&self.message, self.cursor_pos, PAGE_LINES);
+            }
             KeyCode::Char(c) => {
                 self.message.insert(self.cursor_pos, c);
-                self.cursor_pos += 1;
+                self.cursor_pos += c.len_utf8();
             }
             _ => {}
         }
@@ -165,31 +329,82 @@ impl CommitApp {
     }
 }
 
-pub fn run_commit_tui(mut app: CommitApp) -> Result<(String, TuiResult)> {
-    enable_raw_mode()?;
-    stdout().execute(EnterAlternateScreen)?;
+/// Hand the terminal to a child process and take it back afterwards
+trait Suspend {
+    fn suspend(&mut self) -> Result<()>;
+    fn resume(&mut self) -> Result<()>;
+}
+
+impl Suspend for Terminal<CrosstermBackend<Stdout>> {
+    fn suspend(&mut self) -> Result<()> {
+        disable_raw_mode()?;
+        stdout().execute(LeaveAlternateScreen)?;
+        Ok(())
+    }
+
+    fn resume(&mut self) -> Result<()> {
+        stdout().execute(EnterAlternateScreen)?;
+        enable_raw_mode()?;
+        self.clear()?;
+        Ok(())
+    }
+}
+
+/// Suspend the TUI, run `edit` on the message, restore the TUI (even if the
+/// editor fails) and load the result back into the app
+fn edit_externally(
+    app: &mut CommitApp,
+    term: &mut impl Suspend,
+    edit: impl FnOnce(&str) -> Result<Option<String>>,
+) -> Result<()> {
+    term.suspend()?;
+    let edited = edit(&app.message);
+    term.resume()?;
+
+    match edited {
+        Ok(Some(msg)) => {
+            app.message = msg;
+            app.cursor_pos = app.message.len();
+            app.status = "Message updated from editor".to_string();
+        }
+        Ok(None) => app.status = "Message unchanged".to_string(),
+        Err(e) => app.status = format!("Editor error: {}", e),
+    }
+    Ok(())
+}
 
+pub fn run_commit_tui(mut app: CommitApp) -> Result<(String, TuiResult)> {
+    let _guard = TuiGuard::enter()?;
     let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
 
+    let mut redraw = Redraw::new();
     loop {
-        terminal.draw(|f| ui(f, &app))?;
+        let was_busy = app.pending.is_some();
+        let busy = app.poll_regenerate();
+        if was_busy && !busy {
+            redraw.mark();
+        }
+        if redraw.take(busy) {
+            terminal.draw(|f| ui(f, &app))?;
+        }
 
-        if event::poll(std::time::Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    app.handle_key(key.code);
-                }
+        if let Some(Event::Key(key)) = redraw.next_event(busy)? {
+            if key.kind == KeyEventKind::Press {
+                app.handle_key(key.code);
             }
         }
 
+        if app.editor_requested {
+            app.editor_requested = false;
+            edit_externally(&mut app, &mut terminal, edit_with_editor)?;
+            redraw.mark();
+        }
+
         if app.should_quit {
             break;
         }
     }
 
-    disable_raw_mode()?;
-    stdout().execute(LeaveAlternateScreen)?;
-
     let result = app.result.unwrap_or(TuiResult::Cancel);
     Ok((app.message, result))
 }
@@ -219,11 +434,17 @@ fn ui(f: &mut Frame, app: &CommitApp) {
 
     // Footer
     render_footer(f, app, chunks[3]);
+
+    if app.show_help {
+        render_help_overlay(f, "Commit keys", &app.mode_keys(), f.size());
+    }
 }
 
 fn render_header(f: &mut Frame, app: &CommitApp, area: Rect) {
     let title = format!(" repo commit ({}) ", app.provider.name());
-    let status = if app.status.is_empty() {
+    let status = if app.pending.is_some() {
+        format!(" {} {} ", SPINNER_FRAMES[app.spinner_frame], app.status)
+    } else if app.status.is_empty() {
         String::new()
     } else {
         format!(" {} ", app.status)
@@ -270,6 +491,22 @@ fn render_staged_files(f: &mut Frame, app: &CommitApp, area: Rect) {
 }
 
 fn render_message(f: &mut Frame, app: &CommitApp, area: Rect) {
+    let area = match &app.original {
+        Some(original) => {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(3), Constraint::Min(5)])
+                .split(area);
+            let subject = original.lines().next().unwrap_or("");
+            let widget = Paragraph::new(format!("  Original: {}", subject))
+                .style(Style::default().fg(Color::DarkGray))
+                .block(Block::default().borders(Borders::ALL).title(" Original "));
+            f.render_widget(widget, chunks[0]);
+            chunks[1]
+        }
+        None => area,
+    };
+
     let title = match app.mode {
         Mode::Edit => " Commit Message (EDITING) ",
         _ => " Commit Message ",
@@ -280,16 +517,20 @@ fn render_message(f: &mut Frame, app: &CommitApp, area: Rect) {
         _ => Color::White,
     };
 
-    // Show cursor in edit mode
-    let text = if app.mode == Mode::Edit {
+    // Show cursor in edit mode, scrolled so its line stays in view
+    let (text, scroll) = if app.mode == Mode::Edit {
         let (before, after) = app.message.split_at(app.cursor_pos.min(app.message.len()));
-        format!("{}|{}", before, after)
+        let line = cursor_line(&app.message, app.cursor_pos);
+        let scroll = follow_cursor(app.message_scroll.get(), line, area.height.saturating_sub(2));
+        app.message_scroll.set(scroll);
+        (format!("{}|{}", before, after), scroll)
     } else {
-        app.message.clone()
+        (app.message.clone(), 0)
     };
 
     let widget = Paragraph::new(text)
         .wrap(Wrap { trim: false })
+        .scroll((scroll, 0))
         .block(
             Block::default()
                 .borders(Borders::ALL)
@@ -305,7 +546,9 @@ fn render_diff(f: &mut Frame, app: &CommitApp, area: Rect) {
         .diff
         .lines()
         .map(|line| {
-            let style = if line.starts_with('+') && !line.starts_with("+++") {
+            let style = if line.starts_with(BINARY_MARKER) {
+                Style::default().fg(Color::Magenta).add_modifier(Modifier::ITALIC)
+            } else if line.starts_with('+') && !line.starts_with("+++") {
                 Style::default().fg(Color::Green)
             } else if line.starts_with('-') && !line.starts_with("---") {
                 Style::default().fg(Color::Red)
@@ -331,15 +574,162 @@ fn render_diff(f: &mut Frame, app: &CommitApp, area: Rect) {
 }
 
 fn render_footer(f: &mut Frame, app: &CommitApp, area: Rect) {
-    let help = match app.mode {
-        Mode::Review => "y/Enter: commit | q/Esc: cancel | r: regenerate | e: edit | d: view diff",
-        Mode::Edit => "Esc: done editing | Type to edit message",
-        Mode::DiffView => "d/Esc: close | j/k: scroll | PgUp/PgDn: fast scroll",
-    };
+    let mut spans = vec![Span::raw(" ")];
+    spans.extend(footer_spans(&app.mode_keys()));
 
-    let footer = Paragraph::new(format!(" {}", help))
-        .style(Style::default().fg(Color::DarkGray))
-        .block(Block::default().borders(Borders::ALL));
+    let mut block = Block::default().borders(Borders::ALL);
+    if let Some(lint) = app.lint_summary() {
+        block = block.title(Span::styled(format!(" ⚠ {} ", lint), Style::default().fg(Color::Yellow)));
+    }
+    let footer = Paragraph::new(Line::from(spans)).block(block);
 
     f.render_widget(footer, area);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct MockTerm {
+        calls: Vec<&'static str>,
+    }
+
+    impl Suspend for MockTerm {
+        fn suspend(&mut self) -> Result<()> {
+            self.calls.push("suspend");
+            Ok(())
+        }
+
+        fn resume(&mut self) -> Result<()> {
+            self.calls.push("resume");
+            Ok(())
+        }
+    }
+
+    fn app(message: &str) -> CommitApp {
+        CommitApp::new(message.to_string(), String::new(), AiProvider::Claude, Vec::new())
+    }
+
+    #[test]
+    fn test_editor_key_requests_edit() {
+        let mut a = app("feat: x");
+        a.handle_key(KeyCode::Char('E'));
+        assert!(a.editor_requested);
+        assert_eq!(a.mode, Mode::Review);
+
+        let mut a = app("feat: x").with_editor_key('v');
+        a.handle_key(KeyCode::Char('E'));
+        assert!(!a.editor_requested);
+        a.handle_key(KeyCode::Char('v'));
+        assert!(a.editor_requested);
+    }
+
+    #[test]
+    fn test_help_overlay_keys_per_mode() {
+        let mut a = app("feat: x").with_editor_key('v');
+        a.handle_key(KeyCode::Char('?'));
+        assert!(a.show_help);
+        assert!(a.mode_keys().contains(&KeyHelp::new("v", "$EDITOR")));
+        a.handle_key(KeyCode::Char('y'));
+        assert!(!a.should_quit, "keys are swallowed while help is open");
+        a.handle_key(KeyCode::Esc);
+        assert!(!a.show_help);
+
+        a.handle_key(KeyCode::Char('d'));
+        assert!(a.mode_keys().iter().any(|k| k.key == "PgUp/PgDn"));
+
+        // `?` types into the message while editing
+        a.mode = Mode::Edit;
+        a.cursor_pos = a.message.len();
+        a.handle_key(KeyCode::Char('?'));
+        assert!(!a.show_help);
+        assert_eq!(a.message, "feat: x?");
+        assert!(a.mode_keys().iter().any(|k| k.desc == "done editing"));
+    }
+
+    #[test]
+    fn test_edit_externally_suspends_and_reloads() {
+        let mut a = app("feat: x");
+        let mut term = MockTerm::default();
+        edit_externally(&mut a, &mut term, |current| {
+            assert_eq!(current, "feat: x");
+            Ok(Some("feat: x\n\nlonger body".to_string()))
+        })
+        .unwrap();
+        assert_eq!(term.calls, vec!["suspend", "resume"]);
+        assert_eq!(a.message, "feat: x\n\nlonger body");
+        assert_eq!(a.cursor_pos, a.message.len());
+    }
+
+    #[test]
+    fn test_edit_externally_restores_on_editor_error() {
+        let mut a = app("feat: x");
+        let mut term = MockTerm::default();
+        edit_externally(&mut a, &mut term, |_| anyhow::bail!("editor exited")).unwrap();
+        assert_eq!(term.calls, vec!["suspend", "resume"]);
+        assert_eq!(a.message, "feat: x");
+        assert!(a.status.contains("editor exited"));
+    }
+
+    #[test]
+    fn test_strict_lint_blocks_commit() {
+        let mut a = app("Add sync.").with_lint(CommitConfig::default(), true);
+        assert!(a.lint_summary().unwrap().contains("period"));
+        a.handle_key(KeyCode::Char('y'));
+        assert_eq!(a.result, None);
+
+        a.message = "feat: add sync".to_string();
+        assert_eq!(a.lint_summary(), None);
+        a.handle_key(KeyCode::Char('y'));
+        assert_eq!(a.result, Some(TuiResult::Commit));
+    }
+
+    #[test]
+    fn test_background_regenerate_result() {
+        let mut a = app("old");
+        let (tx, rx) = mpsc::channel();
+        a.pending = Some(rx);
+        assert!(a.poll_regenerate(), "still waiting");
+        a.handle_key(KeyCode::Char('y'));
+        assert_eq!(a.result, None, "commit waits for the new message");
+
+        tx.send(Ok("feat: new".to_string())).unwrap();
+        assert!(!a.poll_regenerate());
+        assert_eq!(a.message, "feat: new");
+        assert_eq!(a.status, "Message regenerated");
+    }
+
+    #[test]
+    fn test_edit_page_keys_move_cursor_lines() {
+        let body: String = (0..30).map(|i| format!("line {}\n", i)).collect();
+        let mut a = app(&format!("feat: x\n\n{}", body));
+        a.handle_key(KeyCode::Char('e'));
+        a.handle_key(KeyCode::Home);
+        a.handle_key(KeyCode::PageDown);
+        assert_eq!(cursor_line(&a.message, a.cursor_pos), 10);
+        a.handle_key(KeyCode::PageUp);
+        assert_eq!(a.cursor_pos, 0);
+    }
+
+    #[test]
+    fn test_edit_cursor_crosses_emoji() {
+        let mut a = app("fix: ");
+        a.handle_key(KeyCode::Char('e'));
+        for c in "🎉é!".chars() {
+            a.handle_key(KeyCode::Char(c));
+        }
+        assert_eq!(a.message, "fix: 🎉é!");
+        a.handle_key(KeyCode::Left);
+        a.handle_key(KeyCode::Left);
+        a.handle_key(KeyCode::Left);
+        a.handle_key(KeyCode::Backspace);
+        assert_eq!(a.message, "fix:🎉é!");
+        a.handle_key(KeyCode::Delete);
+        assert_eq!(a.message, "fix:é!");
+        a.handle_key(KeyCode::Right);
+        a.handle_key(KeyCode::Char('x'));
+        assert_eq!(a.message, "fix:éx!");
+        let _ = a.message.split_at(a.cursor_pos);
+    }
+}
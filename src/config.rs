@@ -1,10 +1,15 @@
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 use globset::{Glob, GlobSet, GlobSetBuilder};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Once;
 
-#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+use crate::commit::tui::REVIEW_KEYS;
+use crate::models::TimeMode;
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum MessageBoxStyle {
     Box,
@@ -19,7 +24,88 @@ impl Default for MessageBoxStyle {
     }
 }
 
-#[derive(Debug, Deserialize)]
+/// How often `repo commit` looks for a new release to hint about
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateCheck {
+    Never,
+    #[default]
+    Daily,
+    Always,
+}
+
+/// Also accepts the old `update_check = true | false`
+impl<'de> Deserialize<'de> for UpdateCheck {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Flag(bool),
+            Name(String),
+        }
+        match Raw::deserialize(deserializer)? {
+            Raw::Flag(true) => Ok(Self::Daily),
+            Raw::Flag(false) => Ok(Self::Never),
+            Raw::Name(name) => match name.as_str() {
+                "never" => Ok(Self::Never),
+                "daily" => Ok(Self::Daily),
+                "always" => Ok(Self::Always),
+                other => Err(serde::de::Error::custom(format!(
+                    "unknown update_check '{}' (use never, daily or always)",
+                    other
+                ))),
+            },
+        }
+    }
+}
+
+/// Per-provider settings under `[ai.<provider>]`
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct AiProviderConfig {
+    /// Replaces the built-in commit prompt for this provider
+    #[serde(default)]
+    pub prompt: Option<String>,
+}
+
+/// Commit message lint rules under `[commit]`
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct CommitConfig {
+    /// Longest allowed subject line (default: 72)
+    #[serde(default = "default_max_subject_len")]
+    pub max_subject_len: usize,
+
+    /// Accepted conventional-commit types; empty disables the type check
+    #[serde(default = "default_commit_types")]
+    pub types: Vec<String>,
+}
+
+impl Default for CommitConfig {
+    fn default() -> Self {
+        Self {
+            max_subject_len: default_max_subject_len(),
+            types: default_commit_types(),
+        }
+    }
+}
+
+fn default_max_subject_len() -> usize {
+    72
+}
+
+fn default_commit_types() -> Vec<String> {
+    crate::commit::CONVENTIONAL_TYPES.iter().map(|t| t.to_string()).collect()
+}
+
+/// What bare `repo` does
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DefaultAction {
+    #[default]
+    Summary,
+    Tui,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
 pub struct Config {
     #[serde(default)]
     pub default_ai: Option<String>,
@@ -27,13 +113,33 @@ pub struct Config {
     #[serde(default = "default_true")]
     pub show_github_stats: bool,
 
+    /// GitHub host for remote detection, e.g. a GitHub Enterprise domain (default: github.com)
+    #[serde(default = "default_github_host")]
+    pub github_host: String,
+
+    /// Seconds before cached GitHub stats are refetched (default: 3600)
+    #[serde(default = "default_github_stats_ttl")]
+    pub github_stats_ttl_secs: u64,
+
     #[serde(default)]
     pub commit_style: Option<String>,
 
+    /// Hard-wrap commit body lines at this column (the subject is never wrapped)
+    #[serde(default)]
+    pub body_wrap: Option<usize>,
+
     /// Automatically fetch from remotes before showing summary
     #[serde(default)]
     pub auto_fetch: bool,
 
+    /// Prune deleted remote branches on `repo sync` (same as `--prune`)
+    #[serde(default)]
+    pub auto_prune: bool,
+
+    /// Key in the commit TUI that opens $EDITOR on the message (default: E)
+    #[serde(default = "default_editor_key", deserialize_with = "editor_key")]
+    pub commit_editor_key: char,
+
     /// Style for commit message display: box, double_line, title_box, gutter
     #[serde(default)]
     pub message_box_style: MessageBoxStyle,
@@ -50,6 +156,10 @@ pub struct Config {
     #[serde(default)]
     pub commit_model: Option<String>,
 
+    /// Max rows `repo stars` / `repo forks` print without --limit (default: 50)
+    #[serde(default = "default_list_display_limit")]
+    pub list_display_limit: usize,
+
     /// Days before a branch is considered stale (default: 30)
     #[serde(default = "default_stale_days")]
     pub stale_branch_days: u64,
@@ -57,6 +167,49 @@ pub struct Config {
     /// Glob patterns for files to never stage/commit (global)
     #[serde(default)]
     pub ignore_files: Vec<String>,
+
+    /// Files whose diff is left out of the AI prompt (only their names are
+    /// sent); `--include-all` sends them anyway
+    #[serde(default = "default_ai_diff_exclude")]
+    pub ai_diff_exclude: Vec<String>,
+
+    /// Path glob → commit scope, e.g. `"packages/api/**" = "api"`
+    #[serde(default)]
+    pub scope_map: BTreeMap<String, String>,
+
+    /// Shell command run after each `repo commit`, with REPO_COMMIT_SHA,
+    /// REPO_COMMIT_MSG and REPO_BRANCH set
+    #[serde(default)]
+    pub post_commit_command: Option<String>,
+
+    /// How often to check GitHub for new releases after a commit
+    /// (overridden by REPO_NO_UPDATE_CHECK / --offline)
+    #[serde(default)]
+    pub update_check: UpdateCheck,
+
+    /// `[ai.claude]`, `[ai.codex]`, `[ai.gemini]` overrides
+    #[serde(default)]
+    pub ai: BTreeMap<String, AiProviderConfig>,
+
+    /// Check staged additions for likely credentials before committing
+    #[serde(default)]
+    pub scan_secrets: bool,
+
+    /// `tui` makes bare `repo` behave like `repo -i`
+    #[serde(default)]
+    pub default_action: DefaultAction,
+
+    /// Ask once more before craft rewrites history (`craft -y` skips it)
+    #[serde(default = "default_true")]
+    pub craft_confirm: bool,
+
+    /// Commit dates: relative (3d), short (2024-05-01) or iso (same as `--date`)
+    #[serde(default)]
+    pub date_format: TimeMode,
+
+    /// `[commit]` message lint rules
+    #[serde(default)]
+    pub commit: CommitConfig,
 }
 
 fn default_true() -> bool {
@@ -67,36 +220,270 @@ fn default_stale_days() -> u64 {
     30
 }
 
+fn default_list_display_limit() -> usize {
+    50
+}
+
+fn default_editor_key() -> char {
+    'E'
+}
+
+/// Rejects keys the commit TUI already binds, which would never reach $EDITOR
+fn editor_key<'de, D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<char, D::Error> {
+    let key = char::deserialize(deserializer)?;
+    if REVIEW_KEYS.contains(&key) {
+        return Err(serde::de::Error::custom(format!(
+            "commit_editor_key '{}' is already bound in the commit TUI (taken: {})",
+            key,
+            REVIEW_KEYS.iter().map(char::to_string).collect::<Vec<_>>().join(" ")
+        )));
+    }
+    Ok(key)
+}
+
+fn default_ai_diff_exclude() -> Vec<String> {
+    [
+        "Cargo.lock",
+        "package-lock.json",
+        "yarn.lock",
+        "pnpm-lock.yaml",
+        "poetry.lock",
+        "Gemfile.lock",
+        "composer.lock",
+        "go.sum",
+        "*.min.js",
+        "*.min.css",
+    ]
+    .map(String::from)
+    .to_vec()
+}
+
+fn default_github_host() -> String {
+    "github.com".to_string()
+}
+
+fn default_github_stats_ttl() -> u64 {
+    3600
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
             default_ai: None,
             show_github_stats: true,
+            github_host: default_github_host(),
+            github_stats_ttl_secs: 3600,
             commit_style: None,
+            body_wrap: None,
             auto_fetch: false,
+            auto_prune: false,
+            commit_editor_key: default_editor_key(),
             message_box_style: MessageBoxStyle::default(),
             ai_provider: None,
             ai_model: None,
             commit_model: None,
+            list_display_limit: default_list_display_limit(),
             stale_branch_days: 30,
             ignore_files: Vec::new(),
+            ai_diff_exclude: default_ai_diff_exclude(),
+            scope_map: BTreeMap::new(),
+            post_commit_command: None,
+            update_check: UpdateCheck::default(),
+            ai: BTreeMap::new(),
+            scan_secrets: false,
+            default_action: DefaultAction::default(),
+            craft_confirm: true,
+            date_format: TimeMode::default(),
+            commit: CommitConfig::default(),
         }
     }
 }
 
 impl Config {
+    /// The config file with `REPO_<KEY>` environment overrides applied
     pub fn load() -> Result<Self> {
-        let config_path = Self::config_path();
+        Self::load_from(&Self::config_path(), std::env::vars())
+    }
 
+    fn load_from(path: &Path, vars: impl IntoIterator<Item = (String, String)>) -> Result<Self> {
+        Ok(Self::load_file_at(path)?.with_env(vars))
+    }
+
+    /// Just the config file (defaults if there is none)
+    pub fn load_file() -> Result<Self> {
+        Self::load_file_at(&Self::config_path())
+    }
+
+    fn load_file_at(config_path: &Path) -> Result<Self> {
         if !config_path.exists() {
             return Ok(Self::default());
         }
 
-        let content = fs::read_to_string(&config_path)?;
-        let config: Config = toml::from_str(&content)?;
+        let content = fs::read_to_string(config_path)?;
+        let raw: toml::Table = toml::from_str(&content)?;
+        let config: Config = toml::Value::Table(raw.clone()).try_into()?;
+
+        let unknown = match toml::Value::try_from(&config)? {
+            toml::Value::Table(known) => unknown_keys(&raw, &known, ""),
+            _ => Vec::new(),
+        };
+        if !unknown.is_empty() {
+            static WARNED: Once = Once::new();
+            warn_once(
+                &WARNED,
+                format!(
+                    "{}: ignoring unrecognized key(s): {}",
+                    config_path.display(),
+                    unknown.join(", ")
+                ),
+            );
+        }
+        Ok(config)
+    }
+
+    /// Override top-level keys from `REPO_<KEY>` variables, e.g.
+    /// `REPO_AUTO_FETCH=1` or `REPO_DEFAULT_AI=gemini`. Values parse like
+    /// `config --set`; `1`/`0`, `yes`/`no` and `on`/`off` work for booleans.
+    /// Variables that don't name a key (REPO_BRANCH, ...) are skipped.
+    pub fn with_env(self, vars: impl IntoIterator<Item = (String, String)>) -> Config {
+        let mut config = self;
+        let mut invalid = Vec::new();
+        for (name, value) in vars {
+            let Some(key) = name.strip_prefix(ENV_PREFIX) else {
+                continue;
+            };
+            let key = key.to_ascii_lowercase();
+            if key.is_empty() || key.contains('.') {
+                continue;
+            }
+            let flag = match value.to_ascii_lowercase().as_str() {
+                "1" | "yes" | "on" => Some("true"),
+                "0" | "no" | "off" => Some("false"),
+                _ => None,
+            };
+            match config
+                .with_key(&key, &value)
+                .or_else(|e| flag.map_or(Err(e), |f| config.with_key(&key, f)))
+            {
+                Ok(updated) => config = updated,
+                Err(e) if e.is::<UnknownKey>() => {}
+                Err(e) => invalid.push(format!("{}: {}", name, e)),
+            }
+        }
+        if !invalid.is_empty() {
+            static WARNED: Once = Once::new();
+            warn_once(&WARNED, format!("ignoring {}", invalid.join("; ")));
+        }
+        config
+    }
+
+    /// Whether bare `repo` opens the TUI: `-i` forces it, `force_summary`
+    /// (`--static`, or stdout not a terminal) forces the summary, otherwise
+    /// `default_action` decides
+    pub fn opens_tui(&self, interactive: bool, force_summary: bool) -> bool {
+        if interactive {
+            return true;
+        }
+        !force_summary && self.default_action == DefaultAction::Tui
+    }
+
+    /// Commit prompt override for a provider (`claude`, `codex`, `gemini`)
+    pub fn provider_prompt(&self, provider: &str) -> Option<&str> {
+        self.ai
+            .get(provider)
+            .and_then(|p| p.prompt.as_deref())
+            .filter(|p| !p.trim().is_empty())
+    }
+
+    /// Current value of a key as TOML (strings unquoted); dotted keys reach
+    /// into tables, e.g. `ai.claude.prompt`. None if unset or unknown.
+    pub fn get_key(&self, key: &str) -> Option<String> {
+        let value = toml::Value::try_from(self).ok()?;
+        let found = key.split('.').try_fold(&value, |v, part| v.get(part))?;
+        Some(match found {
+            toml::Value::String(s) => s.clone(),
+            other => other.to_string(),
+        })
+    }
+
+    /// Copy of the config with `key` set. `raw` is parsed as a TOML value
+    /// (`true`, `42`, `["a"]`) when the key's type takes one, and as a plain
+    /// string otherwise, so `ai_model = 4` sets the string `"4"`.
+    pub fn with_key(&self, key: &str, raw: &str) -> Result<Config> {
+        let parsed = toml::from_str::<toml::Table>(&format!("v = {}", raw))
+            .ok()
+            .and_then(|mut t| t.remove("v"));
+        let as_string = toml::Value::String(raw.to_string());
+        let Some(value) = parsed else {
+            return self.with_value(key, as_string);
+        };
+        match self.with_value(key, value) {
+            // e.g. `4` for a string key: retry as the literal text
+            Err(e) if !e.is::<UnknownKey>() => self.with_value(key, as_string).map_err(|_| e),
+            result => result,
+        }
+    }
+
+    fn with_value(&self, key: &str, value: toml::Value) -> Result<Config> {
+        let mut root = toml::Value::try_from(self)?;
+        let parts: Vec<&str> = key.split('.').collect();
+        let (last, parents) = parts.split_last().context("empty key")?;
+        let mut table = root.as_table_mut().context("config is not a table")?;
+        for part in parents {
+            table = table
+                .entry(part.to_string())
+                .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+                .as_table_mut()
+                .with_context(|| format!("'{}' is not a table", part))?;
+        }
+        table.insert(last.to_string(), value);
+
+        let config: Config = root
+            .try_into()
+            .map_err(|e: toml::de::Error| anyhow::anyhow!("invalid value for {}: {}", key, e.message()))?;
+        // serde drops keys it doesn't know, so an unknown key vanishes here
+        if config.get_key(key).is_none() {
+            bail!(UnknownKey(key.to_string()));
+        }
         Ok(config)
     }
 
+    /// `config --set`: validate `key = raw` against the file's config (no env
+    /// overrides), then write just that key into the file, keeping its
+    /// comments and layout. Returns the updated config.
+    pub fn set_in_file(key: &str, raw: &str) -> Result<Config> {
+        let path = Self::config_path();
+        let updated = Self::load_file_at(&path)?.with_key(key, raw)?;
+        let text = if path.exists() { fs::read_to_string(&path)? } else { String::new() };
+        let text = set_key_in_toml(&text, key, &updated)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, text).with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(updated)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::config_path();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, toml::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Starter file for `repo config --init`: every default spelled out
+    pub fn default_file() -> String {
+        let body = toml::to_string_pretty(&Config::default()).unwrap_or_default();
+        format!(
+            "# repo config — each key is set to its default; delete what you don't change.\n\
+             # Unset options: {}.\n\
+             # Change any key with `repo config --set <key> <value>`.\n\n{}",
+            unset_options().join(", "),
+            body
+        )
+    }
+
     pub fn config_path() -> PathBuf {
         dirs::home_dir()
             .unwrap_or_else(|| PathBuf::from("."))
@@ -106,6 +493,121 @@ impl Config {
     }
 }
 
+const ENV_PREFIX: &str = "REPO_";
+
+/// `text` with `key` (dotted for tables) set to its value in `config`.
+/// Other keys, comments and formatting are left as they are.
+fn set_key_in_toml(text: &str, key: &str, config: &Config) -> Result<String> {
+    let mut doc: toml_edit::DocumentMut = text.parse().context("config file is not valid TOML")?;
+    let root = toml::Value::try_from(config)?;
+    let value = key
+        .split('.')
+        .try_fold(&root, |v, part| v.get(part))
+        .with_context(|| format!("unknown config key '{}'", key))?;
+    let mut value: toml_edit::Value = value.to_string().parse()?;
+
+    let parts: Vec<&str> = key.split('.').collect();
+    let (last, parents) = parts.split_last().context("empty key")?;
+    let mut table = doc.as_table_mut() as &mut dyn toml_edit::TableLike;
+    for part in parents {
+        table = table
+            .entry(part)
+            .or_insert_with(toml_edit::table)
+            .as_table_like_mut()
+            .with_context(|| format!("'{}' is not a table", part))?;
+    }
+    // replace in place so comments above the key and after the value stay
+    match table.get_mut(last) {
+        Some(item) => {
+            if let Some(old) = item.as_value() {
+                *value.decor_mut() = old.decor().clone();
+            }
+            *item = toml_edit::Item::Value(value);
+        }
+        None => {
+            table.insert(last, toml_edit::Item::Value(value));
+        }
+    }
+    Ok(doc.to_string())
+}
+
+/// Top-level keys that are unset by default, i.e. the `Option` fields that
+/// [`Config::default`] leaves out when serialized
+fn unset_options() -> Vec<&'static str> {
+    let set = match toml::Value::try_from(Config::default()) {
+        Ok(toml::Value::Table(t)) => t,
+        _ => toml::Table::new(),
+    };
+    config_fields().iter().copied().filter(|f| !set.contains_key(*f)).collect()
+}
+
+/// Every top-level field name of [`Config`], read from its `Deserialize`
+/// impl so the list can't drift from the struct
+fn config_fields() -> &'static [&'static str] {
+    use serde::de::{self, Visitor};
+
+    struct FieldNames<'a>(&'a mut &'static [&'static str]);
+
+    impl<'de> de::Deserializer<'de> for FieldNames<'_> {
+        type Error = de::value::Error;
+
+        fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Self::Error> {
+            Err(de::Error::custom("only struct fields are read"))
+        }
+
+        fn deserialize_struct<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            fields: &'static [&'static str],
+            _visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            *self.0 = fields;
+            Err(de::Error::custom("only struct fields are read"))
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes
+            byte_buf option unit unit_struct newtype_struct seq tuple tuple_struct map
+            enum identifier ignored_any
+        }
+    }
+
+    let mut fields: &'static [&'static str] = &[];
+    let _ = Config::deserialize(FieldNames(&mut fields));
+    fields
+}
+
+/// [`Config::with_key`] was given a key the config doesn't have
+#[derive(Debug, thiserror::Error)]
+#[error("unknown config key '{0}'")]
+pub struct UnknownKey(pub String);
+
+/// Dimmed one-line warning, printed at most once per process however many
+/// times the config is loaded
+fn warn_once(once: &'static Once, message: String) {
+    once.call_once(|| {
+        use colored::Colorize;
+        eprintln!("{}", format!("· {}", message).dimmed());
+    });
+}
+
+/// Keys in the file that didn't survive deserializing, i.e. typos and
+/// options that don't exist. `known` is the parsed config serialized back.
+fn unknown_keys(raw: &toml::Table, known: &toml::Table, prefix: &str) -> Vec<String> {
+    let mut unknown = Vec::new();
+    for (key, value) in raw {
+        let path = format!("{}{}", prefix, key);
+        match (value, known.get(key)) {
+            (_, None) => unknown.push(path),
+            (toml::Value::Table(raw_sub), Some(toml::Value::Table(known_sub))) => {
+                unknown.extend(unknown_keys(raw_sub, known_sub, &format!("{}.", path)));
+            }
+            _ => {}
+        }
+    }
+    unknown
+}
+
 /// Load per-repo `.repoignore` patterns (gitignore-style: one glob per line, # comments)
 pub fn load_repo_ignore(repo_root: &Path) -> Vec<String> {
     let path = repo_root.join(".repoignore");
@@ -125,13 +627,18 @@ pub fn load_repo_ignore(repo_root: &Path) -> Vec<String> {
 pub fn build_ignore_set(config: &Config, repo_root: &Path) -> Option<GlobSet> {
     let mut patterns = config.ignore_files.clone();
     patterns.extend(load_repo_ignore(repo_root));
+    build_glob_set(&patterns)
+}
 
+/// GlobSet matching `patterns`; a bare filename matches in any directory.
+/// Returns None if there are no patterns.
+pub fn build_glob_set(patterns: &[String]) -> Option<GlobSet> {
     if patterns.is_empty() {
         return None;
     }
 
     let mut builder = GlobSetBuilder::new();
-    for pattern in &patterns {
+    for pattern in patterns {
         // Support both "file.txt" and "**/file.txt" style patterns
         if let Ok(glob) = Glob::new(pattern) {
             builder.add(glob);
@@ -146,3 +653,169 @@ pub fn build_ignore_set(config: &Config, repo_root: &Path) -> Option<GlobSet> {
 
     builder.build().ok()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_action_selects_tui() {
+        let config = Config::default();
+        assert!(!config.opens_tui(false, false));
+        assert!(config.opens_tui(true, false));
+
+        let config: Config = toml::from_str("default_action = \"tui\"").unwrap();
+        assert_eq!(config.default_action, DefaultAction::Tui);
+        assert!(config.opens_tui(false, false));
+        assert!(!config.opens_tui(false, true));
+        assert!(config.opens_tui(true, true));
+    }
+
+    #[test]
+    fn test_get_and_set_keys() {
+        let config = Config::default();
+        assert_eq!(config.get_key("auto_fetch").as_deref(), Some("false"));
+        assert_eq!(config.get_key("github_host").as_deref(), Some("github.com"));
+        assert_eq!(config.get_key("default_ai"), None);
+
+        let config = config.with_key("auto_fetch", "true").unwrap();
+        assert!(config.auto_fetch);
+        let config = config.with_key("default_ai", "claude").unwrap();
+        assert_eq!(config.default_ai.as_deref(), Some("claude"));
+        let config = config.with_key("ai.claude.prompt", "Be terse").unwrap();
+        assert_eq!(config.provider_prompt("claude"), Some("Be terse"));
+        let config = config.with_key("ignore_files", r#"["*.lock"]"#).unwrap();
+        assert_eq!(config.ignore_files, vec!["*.lock"]);
+
+        assert!(config.with_key("list_display_limit", "lots").is_err());
+        assert_eq!(config.with_key("commit_editor_key", "v").unwrap().commit_editor_key, 'v');
+        let err = config.with_key("commit_editor_key", "e").unwrap_err().to_string();
+        assert!(err.contains("already bound"), "{}", err);
+        let err = config.with_key("no_such_key", "1").unwrap_err().to_string();
+        assert!(err.contains("unknown config key"), "{}", err);
+
+        // round-trips through the file format
+        let parsed: Config = toml::from_str(&toml::to_string_pretty(&config).unwrap()).unwrap();
+        assert_eq!(parsed.get_key("ai.claude.prompt").as_deref(), Some("Be terse"));
+    }
+
+    #[test]
+    fn test_update_check_accepts_old_bools() {
+        let parse = |raw: &str| toml::from_str::<Config>(&format!("update_check = {}", raw)).map(|c| c.update_check);
+        assert_eq!(parse("true").unwrap(), UpdateCheck::Daily);
+        assert_eq!(parse("false").unwrap(), UpdateCheck::Never);
+        assert_eq!(parse(r#""always""#).unwrap(), UpdateCheck::Always);
+        assert!(parse(r#""hourly""#).is_err());
+        assert_eq!(Config::default().with_key("update_check", "never").unwrap().update_check, UpdateCheck::Never);
+    }
+
+    #[test]
+    fn test_default_file_parses_to_defaults() {
+        let parsed: Config = toml::from_str(&Config::default_file()).unwrap();
+        assert_eq!(
+            toml::to_string(&parsed).unwrap(),
+            toml::to_string(&Config::default()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_unknown_keys_are_reported() {
+        let raw: toml::Table = toml::from_str(
+            "auto_fetc = true\nauto_prune = true\ndefault_ai = \"claude\"\n\
+             [scope_map]\n\"src/ui\" = \"ui\"\n[ai.claude]\nprompt = \"x\"\npromt = \"y\"\n",
+        )
+        .unwrap();
+        let config: Config = toml::Value::Table(raw.clone()).try_into().unwrap();
+        let known = match toml::Value::try_from(&config).unwrap() {
+            toml::Value::Table(t) => t,
+            _ => unreachable!(),
+        };
+        assert_eq!(unknown_keys(&raw, &known, ""), vec!["ai.claude.promt", "auto_fetc"]);
+        assert!(config.auto_prune);
+    }
+
+    #[test]
+    fn test_env_overrides() {
+        let vars = |pairs: &[(&str, &str)]| -> Vec<(String, String)> {
+            pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+        };
+        let config = Config::default().with_env(vars(&[
+            ("REPO_AUTO_FETCH", "1"),
+            ("REPO_DEFAULT_AI", "gemini"),
+            ("REPO_LIST_DISPLAY_LIMIT", "7"),
+            ("REPO_SCAN_SECRETS", "yes"),
+            ("REPO_BRANCH", "main"),
+            ("REPO_STALE_BRANCH_DAYS", "soon"),
+            ("HOME", "/tmp"),
+        ]));
+        assert!(config.auto_fetch);
+        assert!(config.scan_secrets);
+        assert_eq!(config.default_ai.as_deref(), Some("gemini"));
+        assert_eq!(config.list_display_limit, 7);
+        assert_eq!(config.stale_branch_days, 30);
+
+        // env wins over the file
+        let file: Config = toml::from_str("auto_prune = true\ncommit_style = \"plain\"").unwrap();
+        let config = file.with_env(vars(&[("REPO_AUTO_PRUNE", "off")]));
+        assert!(!config.auto_prune);
+        assert_eq!(config.commit_style.as_deref(), Some("plain"));
+    }
+
+    #[test]
+    fn test_set_key_keeps_comments() {
+        let text = "# my settings\nauto_fetch = false # off for now\n\n[ai.claude]\n# terse please\nprompt = \"x\"\n";
+        let config: Config = toml::from_str(text).unwrap();
+
+        let updated = config.with_key("auto_fetch", "true").unwrap();
+        let out = set_key_in_toml(text, "auto_fetch", &updated).unwrap();
+        assert_eq!(out, text.replace("auto_fetch = false", "auto_fetch = true"));
+
+        let updated = config.with_key("ai.claude.prompt", "Be terse").unwrap();
+        let out = set_key_in_toml(text, "ai.claude.prompt", &updated).unwrap();
+        assert!(out.contains("# terse please\nprompt = \"Be terse\"\n"), "{}", out);
+
+        let updated = config.with_key("body_wrap", "72").unwrap();
+        let out = set_key_in_toml(text, "body_wrap", &updated).unwrap();
+        assert!(out.starts_with("# my settings\n") && out.contains("body_wrap = 72"), "{}", out);
+        assert_eq!(toml::from_str::<Config>(&out).unwrap().body_wrap, Some(72));
+    }
+
+    #[test]
+    fn test_unset_options_are_the_option_fields() {
+        let unset = unset_options();
+        for key in ["default_ai", "commit_style", "body_wrap", "ai_model", "post_commit_command"] {
+            assert!(unset.contains(&key), "{:?}", unset);
+        }
+        assert!(!unset.contains(&"auto_fetch"));
+        assert!(config_fields().contains(&"auto_fetch"));
+    }
+
+    #[test]
+    fn test_load_applies_env_over_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "github_stats_ttl_secs = 10\nauto_prune = true\n").unwrap();
+        let vars = [("REPO_GITHUB_STATS_TTL_SECS".to_string(), "99".to_string())];
+        let config = Config::load_from(&path, vars).unwrap();
+        assert_eq!(config.github_stats_ttl_secs, 99);
+        assert!(config.auto_prune);
+
+        let missing = Config::load_from(&dir.path().join("none.toml"), Vec::new()).unwrap();
+        assert_eq!(missing.github_stats_ttl_secs, Config::default().github_stats_ttl_secs);
+    }
+
+    #[test]
+    fn test_with_key_parses_by_field_type() {
+        let config = Config::default().with_key("ai_model", "4").unwrap();
+        assert_eq!(config.ai_model.as_deref(), Some("4"));
+        let config = config.with_key("commit_style", "true").unwrap();
+        assert_eq!(config.commit_style.as_deref(), Some("true"));
+        assert_eq!(config.with_key("body_wrap", "72").unwrap().body_wrap, Some(72));
+        assert_eq!(config.with_key("ai_model", r#""quoted""#).unwrap().ai_model.as_deref(), Some("quoted"));
+        assert!(config.with_key("auto_fetch", "sometimes").is_err());
+        assert!(config.with_key("no_such_key", "4").unwrap_err().is::<UnknownKey>());
+
+        let config = Config::default().with_env([("REPO_AI_MODEL".to_string(), "4".to_string())]);
+        assert_eq!(config.ai_model.as_deref(), Some("4"));
+    }
+}
@@ -1,4 +1,6 @@
-#[derive(Debug, Clone)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum RebaseAction {
     Pick,
     Reword(String),
@@ -9,13 +11,13 @@ pub enum RebaseAction {
     Edit,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SplitGroup {
     pub hunk_indices: Vec<usize>,
     pub message: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TodoEntry {
     pub original_idx: usize,
     pub action: RebaseAction,
@@ -27,6 +29,121 @@ impl TodoEntry {
     }
 }
 
+/// Reorder a newest-first list so `source` sits directly above (newer than)
+/// `target`, which git needs to squash into it. Returns the new source
+/// position, or None if `target` isn't older than `source`.
+pub fn move_for_squash<T>(items: &mut [T], source: usize, target: usize) -> Option<usize> {
+    if target <= source || target >= items.len() {
+        return None;
+    }
+    items[source..target].rotate_left(1);
+    Some(target - 1)
+}
+
+/// Whether `action` folds its commit into the one before it in the todo
+pub fn folds_into_previous(action: &RebaseAction) -> bool {
+    matches!(action, RebaseAction::Squash { .. } | RebaseAction::Fixup { .. })
+}
+
+/// The run of squash/fixup entries around `idx` (itself a squash or fixup)
+/// in a newest-first plan.
+/// git folds the whole run into the commit right below it and opens the
+/// editor once for it, so the run shares one combined message.
+pub fn squash_chain(entries: &[TodoEntry], idx: usize) -> std::ops::Range<usize> {
+    let folds = |i: usize| folds_into_previous(&entries[i].action);
+    let mut start = idx;
+    while start > 0 && folds(start - 1) {
+        start -= 1;
+    }
+    let mut end = idx + 1;
+    while end < entries.len() && folds(end) {
+        end += 1;
+    }
+    start..end
+}
+
+/// Build a plan from `fixup! <subject>` / `squash! <subject>` commits, the
+/// way `git rebase --autosquash` would. `subjects` is newest-first; each
+/// marked commit is moved above the most recent older commit with that
+/// subject (after any earlier fixups of it). Unmatched commits stay `Pick`.
+pub fn autosquash_plan(subjects: &[&str]) -> Vec<TodoEntry> {
+    let mut entries: Vec<TodoEntry> = (0..subjects.len()).map(TodoEntry::pick).collect();
+    // target original_idx -> newest commit already attached to it
+    let mut chain_top: std::collections::HashMap<usize, usize> = std::collections::HashMap::new();
+
+    for src in (0..subjects.len()).rev() {
+        let (squash, rest) = if let Some(rest) = subjects[src].strip_prefix("fixup! ") {
+            (false, rest)
+        } else if let Some(rest) = subjects[src].strip_prefix("squash! ") {
+            (true, rest)
+        } else {
+            continue;
+        };
+        let Some(target) = (src + 1..subjects.len()).find(|&j| subjects[j] == rest) else {
+            continue;
+        };
+
+        let anchor = *chain_top.get(&target).unwrap_or(&target);
+        let position = |orig: usize, entries: &[TodoEntry]| {
+            entries.iter().position(|e| e.original_idx == orig).unwrap()
+        };
+        let source_pos = position(src, &entries);
+        let anchor_pos = position(anchor, &entries);
+        let Some(new_pos) = move_for_squash(&mut entries, source_pos, anchor_pos) else {
+            continue;
+        };
+
+        entries[new_pos].action = if squash {
+            RebaseAction::Squash { into_idx: anchor_pos, message: None }
+        } else {
+            RebaseAction::Fixup { into_idx: anchor_pos }
+        };
+        chain_top.insert(target, src);
+    }
+
+    entries
+}
+
+/// Entries the plan changes: every non-pick plus each pick moved from its
+/// original position
+pub fn action_count(entries: &[TodoEntry]) -> usize {
+    entries
+        .iter()
+        .enumerate()
+        .filter(|(i, e)| e.original_idx != *i || !matches!(e.action, RebaseAction::Pick))
+        .count()
+}
+
+/// One-line plan description, e.g. "2 reword, 1 drop, 1 split → 5 resulting commits"
+pub fn plan_summary(entries: &[TodoEntry]) -> String {
+    let kinds = ["reword", "squash", "fixup", "drop", "split", "edit"];
+    let mut parts: Vec<String> = kinds
+        .iter()
+        .filter_map(|kind| {
+            let n = entries.iter().filter(|e| e.action.to_string() == *kind).count();
+            (n > 0).then(|| format!("{} {}", n, kind))
+        })
+        .collect();
+    if entries.iter().enumerate().any(|(i, e)| e.original_idx != i) {
+        parts.push("reordered".to_string());
+    }
+
+    let resulting: usize = entries
+        .iter()
+        .map(|e| match &e.action {
+            RebaseAction::Squash { .. } | RebaseAction::Fixup { .. } | RebaseAction::Drop => 0,
+            RebaseAction::Split { groups } => groups.len(),
+            _ => 1,
+        })
+        .sum();
+    format!(
+        "{} → {} resulting commit{}",
+        parts.join(", "),
+        resulting,
+        if resulting == 1 { "" } else { "s" }
+    )
+}
+
 impl std::fmt::Display for RebaseAction {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -40,3 +157,89 @@ impl std::fmt::Display for RebaseAction {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_squash_chain() {
+        let squash = |i| TodoEntry { original_idx: i, action: RebaseAction::Squash { into_idx: 3, message: None } };
+        let entries = vec![
+            TodoEntry::pick(0),
+            squash(1),
+            TodoEntry { original_idx: 2, action: RebaseAction::Fixup { into_idx: 3 } },
+            TodoEntry::pick(3),
+        ];
+        assert_eq!(squash_chain(&entries, 2), 1..3);
+        assert_eq!(squash_chain(&entries, 1), 1..3);
+    }
+
+    #[test]
+    fn test_move_for_squash_adjacent() {
+        let mut items = vec!['a', 'b', 'c'];
+        assert_eq!(move_for_squash(&mut items, 1, 2), Some(1));
+        assert_eq!(items, vec!['a', 'b', 'c']);
+    }
+
+    #[test]
+    fn test_move_for_squash_non_adjacent() {
+        // newest-first: squash `a` into `d` → `a` must end up right above `d`
+        let mut items = vec!['a', 'b', 'c', 'd', 'e'];
+        assert_eq!(move_for_squash(&mut items, 0, 3), Some(2));
+        assert_eq!(items, vec!['b', 'c', 'a', 'd', 'e']);
+    }
+
+    #[test]
+    fn test_move_for_squash_rejects_newer_target() {
+        let mut items = vec!['a', 'b', 'c'];
+        assert_eq!(move_for_squash(&mut items, 2, 0), None);
+        assert_eq!(move_for_squash(&mut items, 1, 1), None);
+        assert_eq!(move_for_squash(&mut items, 0, 3), None);
+        assert_eq!(items, vec!['a', 'b', 'c']);
+    }
+
+    #[test]
+    fn test_autosquash_plan() {
+        let subjects = ["squash! add a", "other", "fixup! add a", "add b", "add a", "fixup! missing"];
+        let plan = autosquash_plan(&subjects);
+        let order: Vec<usize> = plan.iter().map(|e| e.original_idx).collect();
+        // fixup lands directly above its target; the newer squash above that
+        assert_eq!(order, vec![1, 3, 0, 2, 4, 5]);
+        assert!(matches!(plan[3].action, RebaseAction::Fixup { .. }));
+        assert!(matches!(plan[2].action, RebaseAction::Squash { .. }));
+        assert!(matches!(plan[0].action, RebaseAction::Pick));
+        assert!(matches!(plan[5].action, RebaseAction::Pick));
+    }
+
+    #[test]
+    fn test_plan_summary() {
+        let group = |msg: &str| SplitGroup { hunk_indices: vec![0], message: msg.to_string() };
+        let entries = vec![
+            TodoEntry { original_idx: 0, action: RebaseAction::Reword("new".into()) },
+            TodoEntry { original_idx: 1, action: RebaseAction::Drop },
+            TodoEntry { original_idx: 2, action: RebaseAction::Split { groups: vec![group("a"), group("b"), group("c")] } },
+            TodoEntry { original_idx: 3, action: RebaseAction::Reword("other".into()) },
+            TodoEntry::pick(4),
+        ];
+        assert_eq!(plan_summary(&entries), "2 reword, 1 drop, 1 split → 6 resulting commits");
+
+        let entries = vec![
+            TodoEntry { original_idx: 1, action: RebaseAction::Fixup { into_idx: 1 } },
+            TodoEntry::pick(0),
+        ];
+        assert_eq!(plan_summary(&entries), "1 fixup, reordered → 1 resulting commit");
+    }
+
+    #[test]
+    fn test_action_count_includes_moves() {
+        assert_eq!(action_count(&[TodoEntry::pick(0), TodoEntry::pick(1)]), 0);
+        assert_eq!(action_count(&[TodoEntry::pick(1), TodoEntry::pick(0)]), 2);
+        let entries = vec![
+            TodoEntry::pick(0),
+            TodoEntry { original_idx: 1, action: RebaseAction::Drop },
+            TodoEntry::pick(2),
+        ];
+        assert_eq!(action_count(&entries), 1);
+    }
+}
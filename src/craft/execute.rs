@@ -1,24 +1,40 @@
-use anyhow::{bail, Result};
+use anyhow::{anyhow, bail, Result};
+use colored::Colorize;
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
+use crate::git::{
+    is_rebase_conflict, resolve_rebase_conflicts, write_commit_editor, write_todo_editor, ScratchDir,
+};
 use crate::models::CommitInfo;
-use super::actions::{RebaseAction, TodoEntry};
+use super::actions::{folds_into_previous, RebaseAction, TodoEntry};
 use super::split::generate_patch_for_hunks;
 
 pub fn execute_craft_plan(
     repo_path: &Path,
     commits: &[CommitInfo],
     entries: &[TodoEntry],
-    hunks_cache: &std::collections::HashMap<usize, Vec<super::split::Hunk>>,
+    hunks_cache: &HashMap<usize, Vec<super::split::Hunk>>,
+    rebase_merges: bool,
+    dry_run: bool,
 ) -> Result<()> {
     if entries.is_empty() {
         return Ok(());
     }
 
-    let tmp_dir = std::env::temp_dir().join(format!("repo-craft-{}", std::process::id()));
-    fs::create_dir_all(&tmp_dir)?;
+    // With --rebase-merges the todo is a label/reset/merge graph; moving pick
+    // lines around it would rewire the branches
+    let reordered = entries.iter().enumerate().any(|(i, e)| e.original_idx != i);
+    if rebase_merges && reordered {
+        bail!("reordering is not supported with --rebase-merges");
+    }
+
+    // Scripts, messages and split patches; removed when this returns, after
+    // git has exited and split automation/conflict resolution are done
+    let scratch = ScratchDir::new("repo-craft")?;
+    let tmp_dir = scratch.path();
 
     // Determine base: parent of oldest commit in the plan
     let oldest_idx = entries.iter().map(|e| e.original_idx).max().unwrap();
@@ -29,156 +45,215 @@ pub fn execute_craft_plan(
         Some(commits[oldest_idx].parents[0].to_string())
     };
 
-    // Build ordered entries (oldest first = highest idx first)
-    let mut ordered: Vec<&TodoEntry> = entries.iter().collect();
-    ordered.sort_by(|a, b| b.original_idx.cmp(&a.original_idx));
+    // Plan is newest-first (as shown, including reorders); the todo is oldest-first
+    let ordered: Vec<&TodoEntry> = entries.iter().rev().collect();
 
-    // Collect reword messages (counter-based serving)
-    let mut editor_messages: Vec<String> = Vec::new();
-    // Track which commits need split automation
-    let mut split_entries: Vec<(String, Vec<(Vec<usize>, String)>)> = Vec::new();
+    // (sha, message) for the commit editor; steps without one, e.g. a squash
+    // with no edited message, keep the text git proposes
+    let mut editor_messages: Vec<(String, String)> = Vec::new();
+    // Track which commits need split automation, by original index
+    let mut split_entries: Vec<(usize, Vec<(Vec<usize>, String)>)> = Vec::new();
 
-    let seq_script = write_sequence_editor(&tmp_dir, commits, &ordered)?;
+    let seq_script = write_sequence_editor(tmp_dir, commits, &ordered, rebase_merges)?;
 
-    // Prepare messages for reword/squash actions
-    for entry in &ordered {
+    // Prepare messages for reword/squash actions. git opens the editor once per
+    // squash/fixup chain, at its last line, so that's the SHA the chain's
+    // combined message is served for.
+    let mut chain_message: Option<String> = None;
+    for (i, entry) in ordered.iter().enumerate() {
         match &entry.action {
             RebaseAction::Reword(msg) => {
-                editor_messages.push(msg.clone());
+                editor_messages.push((commits[entry.original_idx].id.to_string(), msg.clone()));
             }
             RebaseAction::Squash { message: Some(msg), .. } => {
-                editor_messages.push(msg.clone());
+                chain_message = Some(msg.clone());
             }
             RebaseAction::Split { groups } => {
-                let sha = commits[entry.original_idx].id.to_string();
                 let hunk_groups: Vec<(Vec<usize>, String)> = groups
                     .iter()
                     .map(|g| (g.hunk_indices.clone(), g.message.clone()))
                     .collect();
-                split_entries.push((sha, hunk_groups));
+                split_entries.push((entry.original_idx, hunk_groups));
             }
             _ => {}
         }
+        let chain_ends = !ordered.get(i + 1).is_some_and(|next| folds_into_previous(&next.action));
+        if chain_ends {
+            if let Some(msg) = chain_message.take() {
+                editor_messages.push((commits[entry.original_idx].id.to_string(), msg));
+            }
+        }
     }
 
-    let msg_script = write_commit_editor(&tmp_dir, &editor_messages)?;
+    let msg_script = write_commit_editor(tmp_dir, &editor_messages)?;
 
-    // Write split automation scripts if needed
-    let split_script = if !split_entries.is_empty() {
-        Some(write_split_automation(&tmp_dir, hunks_cache, commits, &split_entries)?)
-    } else {
-        None
-    };
+    // One split script per split commit, keyed by its SHA
+    let split_scripts = write_split_automation(tmp_dir, hunks_cache, commits, &split_entries)?;
 
     // Run rebase
-    let mut args = vec![
-        "-C".to_string(),
-        repo_path.to_string_lossy().to_string(),
-        "rebase".to_string(),
-        "-i".to_string(),
-    ];
+    let mut args = vec!["-C".to_string(), repo_path.to_string_lossy().to_string()];
+    if rebase_merges {
+        // git's todo then lists full SHAs for the sed rewrite to match exactly
+        args.extend(["-c".to_string(), "core.abbrev=40".to_string()]);
+    }
+    args.extend(["rebase".to_string(), "-i".to_string()]);
+    if rebase_merges {
+        args.push("--rebase-merges".to_string());
+    }
 
     match &base_sha {
         Some(sha) => args.push(sha.clone()),
         None => args.push("--root".to_string()),
     }
 
-    let output = Command::new("git")
-        .args(&args)
-        .env("GIT_SEQUENCE_EDITOR", &seq_script)
-        .env("GIT_EDITOR", &msg_script)
-        .output()?;
+    if dry_run {
+        return print_dry_run(
+            tmp_dir,
+            &args,
+            &seq_script,
+            rebase_merges,
+            &editor_messages,
+            &split_scripts,
+        );
+    }
 
-    // If rebase stopped for edit (split), run the auto-split script
-    if let Some(ref script) = split_script {
-        let status_output = Command::new("git")
-            .args(["-C", &repo_path.to_string_lossy(), "status"])
+    let env = [("GIT_SEQUENCE_EDITOR", seq_script.as_path()), ("GIT_EDITOR", msg_script.as_path())];
+    let mut output = Command::new("git").args(&args).envs(env).output()?;
+
+    // Conflicts pause for resolution and a split's `edit` stop runs its
+    // script, then the rebase goes on; the editor scripts must outlive both
+    loop {
+        if !output.status.success() {
+            if !is_rebase_conflict(&output) {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                return Err(anyhow!("rebase failed:\n{}\n{}", stdout, stderr));
+            }
+            resolve_rebase_conflicts(repo_path, &env)?;
+        }
+        let Some(script) = stopped_at(repo_path).and_then(|sha| split_scripts.get(&sha)) else {
+            return Ok(());
+        };
+        run_split_automation(repo_path, script)?;
+        output = Command::new("git")
+            .arg("-C")
+            .arg(repo_path)
+            .args(["rebase", "--continue"])
+            .envs(env)
             .output()?;
-        let status_str = String::from_utf8_lossy(&status_output.stdout);
+    }
+}
 
-        if status_str.contains("interactive rebase") || status_str.contains("edit") {
-            run_split_automation(repo_path, script)?;
-        }
+/// SHA of the todo line an in-progress rebase last ran (the last line of
+/// `rebase-merge/done`), i.e. the commit an `edit` stopped at
+fn stopped_at(repo_path: &Path) -> Option<String> {
+    let repo = git2::Repository::open(repo_path).ok()?;
+    let done = fs::read_to_string(repo.path().join("rebase-merge/done")).ok()?;
+    let last = done.lines().last()?;
+    last.split_whitespace().nth(1).map(str::to_string)
+}
+
+fn print_section(title: &str, body: &str) {
+    println!("{} {}", "──".dimmed(), title.bold());
+    print!("{}", body);
+    if !body.ends_with('\n') {
+        println!();
     }
+    println!();
+}
 
-    // cleanup
-    fs::remove_dir_all(&tmp_dir).ok();
+/// Show what the rebase would run: the command, the todo (or the sed rewrite
+/// for --rebase-merges), each editor message, and the split script + patches
+fn print_dry_run(
+    tmp_dir: &Path,
+    args: &[String],
+    seq_script: &Path,
+    rebase_merges: bool,
+    messages: &[(String, String)],
+    split_scripts: &HashMap<String, PathBuf>,
+) -> Result<()> {
+    print_section("command", &format!("git {}\n", args.join(" ")));
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        let stdout = String::from_utf8_lossy(&output.stdout);
+    if rebase_merges {
+        print_section("sequence editor", &fs::read_to_string(seq_script)?);
+    } else {
+        print_section("todo", &fs::read_to_string(tmp_dir.join("todo"))?);
+    }
 
-        if stderr.contains("CONFLICT") || stderr.contains("could not apply") {
-            bail!(
-                "rebase conflict:\n{}\n{}\nresolve manually:\n  git rebase --continue\n  git rebase --abort",
-                stdout, stderr
-            );
-        }
+    for (sha, msg) in messages {
+        print_section(&format!("message for {}", &sha[..7]), msg);
+    }
 
-        bail!("rebase failed:\n{}\n{}", stdout, stderr);
+    let mut scripts: Vec<(&String, &PathBuf)> = split_scripts.iter().collect();
+    scripts.sort();
+    for (sha, script) in &scripts {
+        print_section(&format!("split script for {}", &sha[..7]), &fs::read_to_string(script)?);
+    }
+    if !scripts.is_empty() {
+        let mut patches: Vec<PathBuf> = fs::read_dir(tmp_dir.join("patches"))?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .collect();
+        patches.sort();
+        for patch in patches {
+            let name = patch.file_name().unwrap_or_default().to_string_lossy().to_string();
+            print_section(&name, &fs::read_to_string(&patch)?);
+        }
     }
 
     Ok(())
 }
 
+/// git todo keyword for an action
+fn todo_action(action: &RebaseAction) -> &'static str {
+    match action {
+        RebaseAction::Pick => "pick",
+        RebaseAction::Reword(_) => "reword",
+        RebaseAction::Squash { .. } => "squash",
+        RebaseAction::Fixup { .. } => "fixup",
+        RebaseAction::Drop => "drop",
+        RebaseAction::Split { .. } | RebaseAction::Edit => "edit",
+    }
+}
+
+/// Full oldest-first todo for a linear range, in the planned order
+fn build_todo(commits: &[CommitInfo], ordered: &[&TodoEntry]) -> String {
+    ordered
+        .iter()
+        .map(|e| {
+            let c = &commits[e.original_idx];
+            format!("{} {} {}\n", todo_action(&e.action), c.id, c.message)
+        })
+        .collect()
+}
+
 fn write_sequence_editor(
     tmp_dir: &Path,
     commits: &[CommitInfo],
     ordered: &[&TodoEntry],
+    rebase_merges: bool,
 ) -> Result<PathBuf> {
-    let script_path = tmp_dir.join("seq-editor.sh");
-
-    // Build sed commands for each entry
-    let mut sed_cmds: Vec<String> = Vec::new();
-
-    for entry in ordered {
-        let sha = &commits[entry.original_idx].short_id;
-        match &entry.action {
-            RebaseAction::Pick => {} // default, no change needed
-            RebaseAction::Reword(_) => {
-                sed_cmds.push(format!("s/^pick {sha}/reword {sha}/"));
-            }
-            RebaseAction::Squash { .. } => {
-                sed_cmds.push(format!("s/^pick {sha}/squash {sha}/"));
-            }
-            RebaseAction::Fixup { .. } => {
-                sed_cmds.push(format!("s/^pick {sha}/fixup {sha}/"));
-            }
-            RebaseAction::Drop => {
-                sed_cmds.push(format!("s/^pick {sha}/drop {sha}/"));
-            }
-            RebaseAction::Split { .. } => {
-                sed_cmds.push(format!("s/^pick {sha}/edit {sha}/"));
-            }
-            RebaseAction::Edit => {
-                sed_cmds.push(format!("s/^pick {sha}/edit {sha}/"));
-            }
-        }
+    if !rebase_merges {
+        return write_todo_editor(tmp_dir, &build_todo(commits, ordered));
     }
 
-    // Handle reordering: we also need to reorder the lines
-    // Build the desired order of short SHAs
-    let desired_order: Vec<String> = ordered
+    // With --rebase-merges git's todo is a label/reset/merge graph we can't
+    // rebuild, so only rewrite the `pick` lines in place
+    let script_path = tmp_dir.join("seq-editor.sh");
+    let sed_cmds: Vec<String> = ordered
         .iter()
-        .map(|e| commits[e.original_idx].short_id.clone())
+        .filter(|e| !matches!(e.action, RebaseAction::Pick))
+        .map(|e| {
+            // full SHA plus the field separator, so neither a shared short
+            // prefix nor a SHA quoted in a subject can match another line
+            let sha = &commits[e.original_idx].id;
+            format!("s/^pick {sha} /{} {sha} /", todo_action(&e.action))
+        })
         .collect();
 
-    let sed_expr = if sed_cmds.is_empty() {
-        String::new()
-    } else {
-        sed_cmds.join("; ")
-    };
-
-    // Write a script that first applies action changes, then reorders
-    let reorder_awk = build_reorder_script(&desired_order);
-
-    let script = if sed_expr.is_empty() {
-        format!("#!/bin/sh\n{}\n", reorder_awk)
+    let script = if sed_cmds.is_empty() {
+        "#!/bin/sh\n".to_string()
     } else {
-        format!(
-            "#!/bin/sh\nsed -i.bak '{sed_expr}' \"$1\"\n{}\n",
-            reorder_awk
-        )
+        format!("#!/bin/sh\nsed -i.bak '{}' \"$1\"\n", sed_cmds.join("; "))
     };
 
     fs::write(&script_path, &script)?;
@@ -187,102 +262,51 @@ fn write_sequence_editor(
     Ok(script_path)
 }
 
-fn build_reorder_script(desired_order: &[String]) -> String {
-    // Build an awk script that reorders the todo lines
-    // We write the desired order to a temp file and use it to sort
-    let mut script = String::new();
-
-    // Create an awk script that assigns order based on SHA position
-    script.push_str("awk '{\n");
-    for (i, sha) in desired_order.iter().enumerate() {
-        script.push_str(&format!("  if ($0 ~ /{}/) {{ order[NR] = {}; lines[NR] = $0 }}\n", sha, i));
-    }
-    script.push_str("  if (!(NR in order)) { order[NR] = NR + 1000; lines[NR] = $0 }\n");
-    script.push_str("}\nEND {\n");
-    script.push_str("  n = asorti(order, sorted, \"@val_num_asc\")\n");
-    script.push_str("  for (i = 1; i <= n; i++) print lines[sorted[i]]\n");
-    script.push_str("}' \"$1\" > \"$1.tmp\" && mv \"$1.tmp\" \"$1\"\n");
-
-    script
-}
-
-fn write_commit_editor(tmp_dir: &Path, messages: &[String]) -> Result<PathBuf> {
-    let script_path = tmp_dir.join("msg-editor.sh");
-    let counter_path = tmp_dir.join("counter");
-
-    fs::write(&counter_path, "0")?;
-
-    for (i, msg) in messages.iter().enumerate() {
-        let msg_path = tmp_dir.join(format!("msg_{}", i));
-        fs::write(&msg_path, msg)?;
-    }
-
-    let counter_str = counter_path.to_string_lossy();
-    let tmp_str = tmp_dir.to_string_lossy();
-
-    let script = format!(
-        r#"#!/bin/sh
-COUNTER_FILE="{counter_str}"
-IDX=$(cat "$COUNTER_FILE")
-MSG_FILE="{tmp_str}/msg_$IDX"
-if [ -f "$MSG_FILE" ]; then
-    cp "$MSG_FILE" "$1"
-fi
-echo $((IDX + 1)) > "$COUNTER_FILE"
-"#
-    );
-
-    fs::write(&script_path, &script)?;
-    make_executable(&script_path)?;
-
-    Ok(script_path)
-}
-
+/// Write `split-<sha>.sh` for each split commit: undo the commit and recommit
+/// its hunk groups. The rebase is continued by the caller, with the editor
+/// scripts still set.
 fn write_split_automation(
     tmp_dir: &Path,
-    hunks_cache: &std::collections::HashMap<usize, Vec<super::split::Hunk>>,
+    hunks_cache: &HashMap<usize, Vec<super::split::Hunk>>,
     commits: &[CommitInfo],
-    split_entries: &[(String, Vec<(Vec<usize>, String)>)],
-) -> Result<PathBuf> {
-    let script_path = tmp_dir.join("auto-split.sh");
+    split_entries: &[(usize, Vec<(Vec<usize>, String)>)],
+) -> Result<HashMap<String, PathBuf>> {
+    let mut scripts = HashMap::new();
+    if split_entries.is_empty() {
+        return Ok(scripts);
+    }
     let patches_dir = tmp_dir.join("patches");
     fs::create_dir_all(&patches_dir)?;
 
-    let mut script = String::from("#!/bin/sh\nset -e\n");
-    script.push_str("# Auto-split script generated by repo craft\n\n");
-
-    for (sha, groups) in split_entries {
-        // Find the commit index
-        let commit_idx = commits
-            .iter()
-            .position(|c| c.id.to_string() == *sha)
-            .unwrap_or(0);
-
-        if let Some(hunks) = hunks_cache.get(&commit_idx) {
-            // Reset the current commit
-            script.push_str("git reset HEAD^\n\n");
-
-            for (group_idx, (hunk_indices, message)) in groups.iter().enumerate() {
-                let patch_file = patches_dir.join(format!("patch_{}_{}.patch", commit_idx, group_idx));
-                let patch_content = generate_patch_for_hunks(hunks, hunk_indices);
-                fs::write(&patch_file, &patch_content)?;
-
-                let patch_path = patch_file.to_string_lossy();
-                let escaped_msg = message.replace('\'', "'\\''");
-                script.push_str(&format!(
-                    "git apply --cached '{}'\ngit commit -m '{}'\n\n",
-                    patch_path, escaped_msg
-                ));
-            }
-
-            script.push_str("git rebase --continue\n");
+    for (commit_idx, groups) in split_entries {
+        let Some(hunks) = hunks_cache.get(commit_idx) else {
+            continue;
+        };
+        let sha = commits[*commit_idx].id.to_string();
+        let mut script = String::from("#!/bin/sh\nset -e\n");
+        script.push_str("# Auto-split script generated by repo craft\n\n");
+        script.push_str("git reset HEAD^\n\n");
+
+        for (group_idx, (hunk_indices, message)) in groups.iter().enumerate() {
+            let patch_file = patches_dir.join(format!("patch_{}_{}.patch", commit_idx, group_idx));
+            let patch_content = generate_patch_for_hunks(hunks, hunk_indices);
+            fs::write(&patch_file, &patch_content)?;
+
+            let patch_path = patch_file.to_string_lossy();
+            let escaped_msg = message.replace('\'', "'\\''");
+            script.push_str(&format!(
+                "git apply --cached '{}'\ngit commit -m '{}'\n\n",
+                patch_path, escaped_msg
+            ));
         }
-    }
 
-    fs::write(&script_path, &script)?;
-    make_executable(&script_path)?;
+        let script_path = tmp_dir.join(format!("split-{}.sh", sha));
+        fs::write(&script_path, &script)?;
+        make_executable(&script_path)?;
+        scripts.insert(sha, script_path);
+    }
 
-    Ok(script_path)
+    Ok(scripts)
 }
 
 fn run_split_automation(repo_path: &Path, script: &Path) -> Result<()> {
@@ -309,3 +333,200 @@ fn make_executable(path: &Path) -> Result<()> {
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, commit_files, init_repo};
+    use chrono::Local;
+    use tempfile::TempDir;
+
+    fn commit(hex: &str, message: &str) -> CommitInfo {
+        let id = git2::Oid::from_str(&format!("{:0<40}", hex)).unwrap();
+        CommitInfo {
+            id,
+            short_id: id.to_string()[..7].to_string(),
+            message: message.to_string(),
+            author: String::new(),
+            time: Local::now(),
+            parents: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_build_todo_follows_plan_order() {
+        let commits = vec![commit("ccc", "third"), commit("bbb", "second"), commit("aaa", "first")];
+        // newest-first plan with `third` moved below `second` and `first` dropped
+        let entries = [
+            TodoEntry::pick(1),
+            TodoEntry { original_idx: 0, action: RebaseAction::Fixup { into_idx: 2 } },
+            TodoEntry { original_idx: 2, action: RebaseAction::Drop },
+        ];
+        let ordered: Vec<&TodoEntry> = entries.iter().rev().collect();
+        assert_eq!(
+            build_todo(&commits, &ordered),
+            format!(
+                "drop {} first\nfixup {} third\npick {} second\n",
+                commits[2].id, commits[0].id, commits[1].id
+            )
+        );
+    }
+
+    #[test]
+    fn test_sequence_editor_preserves_merge_lines() {
+        let dir = TempDir::new().unwrap();
+        // `aaaaaaa1`/`aaaaaaa2` share a short SHA; one subject quotes the other
+        let commits = vec![
+            commit("ccc", "Merge branch 'side'"),
+            commit("aaaaaaa2", "second, follow-up to aaaaaaa1"),
+            commit("aaaaaaa1", "first"),
+        ];
+        let entries = [
+            TodoEntry::pick(0),
+            TodoEntry { original_idx: 1, action: RebaseAction::Reword("x".into()) },
+            TodoEntry { original_idx: 2, action: RebaseAction::Drop },
+        ];
+        let ordered: Vec<&TodoEntry> = entries.iter().rev().collect();
+        let script = write_sequence_editor(dir.path(), &commits, &ordered, true).unwrap();
+
+        let (a, b, c) = (commits[2].id, commits[1].id, commits[0].id);
+        let todo = format!(
+            "label onto\nreset onto\npick {a} first\nlabel side\nreset onto\n\
+             pick {b} second, follow-up to aaaaaaa1\nmerge -C {c} side # Merge branch 'side'\n"
+        );
+        let todo_path = dir.path().join("git-rebase-todo");
+        fs::write(&todo_path, todo).unwrap();
+
+        let status = Command::new("sh").arg(&script).arg(&todo_path).status().unwrap();
+        assert!(status.success());
+        assert_eq!(
+            fs::read_to_string(&todo_path).unwrap(),
+            format!(
+                "label onto\nreset onto\ndrop {a} first\nlabel side\nreset onto\n\
+                 reword {b} second, follow-up to aaaaaaa1\nmerge -C {c} side # Merge branch 'side'\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_execute_with_shas_in_subjects() {
+        let (dir, repo) = init_repo();
+
+        // each subject quotes the previous commit's short SHA
+        let mut commits: Vec<CommitInfo> = Vec::new();
+        let mut subject = "root".to_string();
+        for name in ["f0", "f1", "f2", "f3"] {
+            let oid = commit_file(&repo, name, name, &subject);
+            let mut info = commit(&oid.to_string(), &subject);
+            info.parents = repo.find_commit(oid).unwrap().parent_ids().collect();
+            commits.insert(0, info);
+            subject = format!("{} after {}", name, &oid.to_string()[..7]);
+        }
+
+        // newest-first: move the newest below its parent and drop the one after root
+        let entries = [
+            TodoEntry::pick(1),
+            TodoEntry::pick(0),
+            TodoEntry { original_idx: 2, action: RebaseAction::Drop },
+            TodoEntry::pick(3),
+        ];
+        let hunks = std::collections::HashMap::new();
+
+        // dry run leaves history alone
+        let before = repo.head().unwrap().target();
+        execute_craft_plan(dir.path(), &commits, &entries, &hunks, false, true).unwrap();
+        assert_eq!(repo.head().unwrap().target(), before);
+
+        execute_craft_plan(dir.path(), &commits, &entries, &hunks, false, false).unwrap();
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        let subjects: Vec<String> = std::iter::successors(Some(head), |c| c.parent(0).ok())
+            .map(|c| c.summary().unwrap().to_string())
+            .collect();
+        let expected: Vec<String> = [1, 0, 3].iter().map(|&i| commits[i].message.clone()).collect();
+        assert_eq!(subjects, expected);
+        assert!(!dir.path().join("f1").exists());
+    }
+
+    #[test]
+    fn test_split_plan_creates_commits() {
+        use super::super::actions::SplitGroup;
+        use super::super::split::get_commit_hunks;
+
+        let (dir, repo) = init_repo();
+
+        let mut commits: Vec<CommitInfo> = Vec::new();
+        for (files, subject) in [
+            (&[("base.txt", "base.txt\n")][..], "root"),
+            (&[("a.txt", "a.txt\n"), ("b.txt", "b.txt\n")][..], "add a and b"),
+            (&[("c.txt", "c.txt\n")][..], "add c"),
+        ] {
+            let oid = commit_files(&repo, files, subject);
+            let mut info = commit(&oid.to_string(), subject);
+            info.parents = repo.find_commit(oid).unwrap().parent_ids().collect();
+            commits.insert(0, info);
+        }
+
+        let hunks = get_commit_hunks(&repo, commits[1].id).unwrap();
+        assert_eq!(hunks.len(), 2);
+        let group = |idx: usize, message: &str| SplitGroup {
+            hunk_indices: vec![idx],
+            message: message.to_string(),
+        };
+        // the reword after the split needs the commit editor on `--continue`
+        let entries = [
+            TodoEntry { original_idx: 0, action: RebaseAction::Reword("new c\n".into()) },
+            TodoEntry {
+                original_idx: 1,
+                action: RebaseAction::Split { groups: vec![group(0, "add a"), group(1, "add b")] },
+            },
+            TodoEntry::pick(2),
+        ];
+        let cache = HashMap::from([(1, hunks)]);
+
+        // the split script and its patches live in the temp dir and must still
+        // exist when the rebase stops at the `edit`
+        execute_craft_plan(dir.path(), &commits, &entries, &cache, false, false).unwrap();
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        let subjects: Vec<String> = std::iter::successors(Some(head), |c| c.parent(0).ok())
+            .map(|c| c.summary().unwrap().to_string())
+            .collect();
+        assert_eq!(subjects, ["new c", "add b", "add a", "root"]);
+        assert_eq!(repo.state(), git2::RepositoryState::Clean);
+        assert!(dir.path().join("a.txt").exists() && dir.path().join("b.txt").exists());
+    }
+
+    #[test]
+    fn test_squash_chain_uses_one_editor_call() {
+        let (dir, repo) = init_repo();
+
+        let mut commits: Vec<CommitInfo> = Vec::new();
+        for (name, subject) in [("base", "base"), ("t", "target"), ("a", "add a"), ("b", "add b"), ("c", "add c")] {
+            let oid = commit_file(&repo, name, name, subject);
+            let mut info = commit(&oid.to_string(), subject);
+            info.parents = repo.find_commit(oid).unwrap().parent_ids().collect();
+            commits.insert(0, info);
+        }
+        commits.truncate(4);
+
+        // newest-first: reword c, squash b and a into the target with one message
+        let entries = [
+            TodoEntry { original_idx: 0, action: RebaseAction::Reword("new c\n".into()) },
+            TodoEntry {
+                original_idx: 1,
+                action: RebaseAction::Squash { into_idx: 3, message: Some("target + a + b\n".into()) },
+            },
+            TodoEntry { original_idx: 2, action: RebaseAction::Squash { into_idx: 3, message: None } },
+            TodoEntry::pick(3),
+        ];
+        let hunks = std::collections::HashMap::new();
+        execute_craft_plan(dir.path(), &commits, &entries, &hunks, false, false).unwrap();
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.summary(), Some("new c"));
+        let squashed = head.parent(0).unwrap();
+        assert_eq!(squashed.message(), Some("target + a + b\n"));
+        assert_eq!(squashed.parent(0).unwrap().summary(), Some("base"));
+    }
+}
@@ -1,19 +1,30 @@
 pub mod actions;
 mod execute;
+mod plan;
 pub mod split;
 mod tui;
 
 use anyhow::{bail, Result};
 use colored::Colorize;
 use git2::Repository;
+use std::io::{self, Write};
 
 use crate::ai::detect_provider;
+use crate::error::RepoError;
+use crate::config::Config;
+use crate::git::{get_upstream_ref, is_dirty};
 use crate::models::CommitInfo;
 use tui::{run_craft_tui, CraftResult};
 
 pub struct CraftArgs {
     pub count: usize,
     pub last: Option<usize>,
+    pub rebase_merges: bool,
+    pub autosquash: bool,
+    /// Print the todo, messages and split script instead of rebasing
+    pub dry_run: bool,
+    /// Skip the final confirmation even if `craft_confirm` is set
+    pub yes: bool,
 }
 
 pub fn run_craft(repo: &Repository, args: CraftArgs) -> Result<()> {
@@ -25,13 +36,27 @@ pub fn run_craft(repo: &Repository, args: CraftArgs) -> Result<()> {
         return Ok(());
     }
 
+    let merges = commits.iter().filter(|c| c.parents.len() > 1).count();
+    if merges > 0 && !args.rebase_merges {
+        bail!(
+            "range contains {} merge commit(s) that a plain rebase would flatten — rerun with --rebase-merges",
+            merges
+        );
+    }
+
     let ai_provider = detect_provider();
-    let result = run_craft_tui(commits.clone(), repo, ai_provider)?;
+    let mut initial = plan::offer_restore(repo, &commits)?;
+    if initial.is_none() && args.autosquash {
+        let subjects: Vec<&str> = commits.iter().map(|c| c.message.as_str()).collect();
+        initial = Some(actions::autosquash_plan(&subjects));
+    }
+    let result = run_craft_tui(commits.clone(), repo, ai_provider, initial)?;
 
     match result {
         CraftResult::Execute(entries, hunks_cache) => {
-            let action_count = entries.iter().filter(|e| !matches!(e.action, actions::RebaseAction::Pick)).count();
-            if action_count == 0 {
+            // a reorder-only plan still rewrites history
+            if !plan::is_modified(&entries) {
+                plan::clear_plan(repo);
                 println!("{} no changes to apply", "!".yellow());
                 return Ok(());
             }
@@ -39,20 +64,48 @@ pub fn run_craft(repo: &Repository, args: CraftArgs) -> Result<()> {
             // Only warn about pushed commits at execute time, checking modified commits only
             let modified_indices: Vec<usize> = entries
                 .iter()
-                .filter(|e| !matches!(e.action, actions::RebaseAction::Pick))
-                .map(|e| e.original_idx)
+                .enumerate()
+                .filter(|(i, e)| e.original_idx != *i || !matches!(e.action, actions::RebaseAction::Pick))
+                .map(|(_, e)| e.original_idx)
                 .collect();
             warn_pushed_commits(repo, &commits, &modified_indices);
 
+            println!("{} {}", "●".cyan(), actions::plan_summary(&entries));
+            let confirm = Config::load().unwrap_or_default().craft_confirm;
+            if confirm && !args.yes && !args.dry_run && !confirm_execute()? {
+                plan::save_plan(repo, &commits, &entries)?;
+                println!("{} cancelled — plan saved, run craft again to restore", "·".dimmed());
+                return Ok(());
+            }
+
             let repo_path = repo
                 .workdir()
                 .unwrap_or_else(|| repo.path())
                 .to_path_buf();
 
-            execute::execute_craft_plan(&repo_path, &commits, &entries, &hunks_cache)?;
-            println!("{} crafted {} action(s)", "done".green(), action_count);
+            execute::execute_craft_plan(
+                &repo_path,
+                &commits,
+                &entries,
+                &hunks_cache,
+                args.rebase_merges,
+                args.dry_run,
+            )?;
+            if args.dry_run {
+                // keep the plan so the real run can restore it
+                plan::save_plan(repo, &commits, &entries)?;
+                println!("{} dry run — nothing changed, plan saved", "·".dimmed());
+                return Ok(());
+            }
+            plan::clear_plan(repo);
+            println!("{} crafted {} action(s)", "done".green(), actions::action_count(&entries));
         }
-        CraftResult::Cancel => {
+        CraftResult::Cancel(entries) => {
+            if plan::is_modified(&entries) {
+                plan::save_plan(repo, &commits, &entries)?;
+                println!("{} cancelled — plan saved, run craft again to restore", "·".dimmed());
+                return Ok(());
+            }
             println!("{}", "cancelled".dimmed());
         }
     }
@@ -60,25 +113,21 @@ pub fn run_craft(repo: &Repository, args: CraftArgs) -> Result<()> {
     Ok(())
 }
 
+fn confirm_execute() -> Result<bool> {
+    print!("{} rewrite history with this plan? [y/N] ", "?".cyan());
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(matches!(input.trim(), "y" | "Y" | "yes"))
+}
+
 fn validate_state(repo: &Repository) -> Result<()> {
     if repo.head_detached()? {
-        bail!("detached HEAD — cannot craft");
+        bail!(RepoError::DetachedHead("craft"));
     }
 
-    let statuses = repo.statuses(None)?;
-    let dirty = statuses.iter().any(|s| {
-        let st = s.status();
-        st.intersects(
-            git2::Status::INDEX_NEW
-                | git2::Status::INDEX_MODIFIED
-                | git2::Status::INDEX_DELETED
-                | git2::Status::WT_MODIFIED
-                | git2::Status::WT_NEW
-                | git2::Status::WT_DELETED,
-        )
-    });
-    if dirty {
-        bail!("dirty working tree — commit or stash changes first");
+    if is_dirty(repo)? {
+        bail!(RepoError::DirtyTree);
     }
 
     Ok(())
@@ -89,7 +138,7 @@ fn load_commits(repo: &Repository, limit: usize) -> Result<Vec<CommitInfo>> {
 
     let head = repo.head()?;
     let mut revwalk = repo.revwalk()?;
-    revwalk.set_sorting(git2::Sort::TIME)?;
+    revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)?;
     revwalk.push(head.target().unwrap())?;
 
     let mut commits = Vec::new();
@@ -120,12 +169,9 @@ fn load_commits(repo: &Repository, limit: usize) -> Result<Vec<CommitInfo>> {
 }
 
 fn warn_pushed_commits(repo: &Repository, commits: &[CommitInfo], modified: &[usize]) {
-    let head = match repo.head() {
-        Ok(h) => h,
-        Err(_) => return,
+    let Some(upstream_ref) = get_upstream_ref(repo) else {
+        return;
     };
-    let branch_name = head.shorthand().unwrap_or("");
-    let upstream_ref = format!("refs/remotes/origin/{}", branch_name);
 
     if let Ok(reference) = repo.find_reference(&upstream_ref) {
         if let Some(upstream_oid) = reference.target() {
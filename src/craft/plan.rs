@@ -0,0 +1,155 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use colored::Colorize;
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+
+use super::actions::{RebaseAction, TodoEntry};
+use crate::models::CommitInfo;
+
+const PLAN_FILE: &str = "repo-craft-plan.json";
+
+/// A craft plan saved on cancel so it can be resumed later. Only valid while
+/// HEAD and the loaded commit range are unchanged.
+#[derive(Debug, Serialize, Deserialize)]
+struct SavedPlan {
+    head: String,
+    commits: Vec<String>,
+    entries: Vec<TodoEntry>,
+}
+
+fn plan_path(git_dir: &Path) -> PathBuf {
+    git_dir.join(PLAN_FILE)
+}
+
+fn commit_ids(commits: &[CommitInfo]) -> Vec<String> {
+    commits.iter().map(|c| c.id.to_string()).collect()
+}
+
+fn head_id(repo: &Repository) -> Option<String> {
+    repo.head().ok()?.target().map(|oid| oid.to_string())
+}
+
+/// Whether the plan differs from picking every commit in order
+pub fn is_modified(entries: &[TodoEntry]) -> bool {
+    entries
+        .iter()
+        .enumerate()
+        .any(|(i, e)| e.original_idx != i || !matches!(e.action, RebaseAction::Pick))
+}
+
+fn save_to(git_dir: &Path, head: &str, commits: &[CommitInfo], entries: &[TodoEntry]) -> Result<()> {
+    let plan = SavedPlan {
+        head: head.to_string(),
+        commits: commit_ids(commits),
+        entries: entries.to_vec(),
+    };
+    let json = serde_json::to_string_pretty(&plan)?;
+    fs::write(plan_path(git_dir), json).context("Failed to save craft plan")
+}
+
+fn load_from(git_dir: &Path, head: &str, commits: &[CommitInfo]) -> Option<Vec<TodoEntry>> {
+    let content = fs::read_to_string(plan_path(git_dir)).ok()?;
+    let plan: SavedPlan = serde_json::from_str(&content).ok()?;
+    if plan.head != head || plan.commits != commit_ids(commits) {
+        return None;
+    }
+    if plan.entries.len() != commits.len()
+        || plan.entries.iter().any(|e| e.original_idx >= commits.len())
+    {
+        return None;
+    }
+    Some(plan.entries)
+}
+
+/// Save the plan to `.git/repo-craft-plan.json`
+pub fn save_plan(repo: &Repository, commits: &[CommitInfo], entries: &[TodoEntry]) -> Result<()> {
+    let head = head_id(repo).unwrap_or_default();
+    save_to(repo.path(), &head, commits, entries)
+}
+
+/// Remove any saved plan
+pub fn clear_plan(repo: &Repository) {
+    let _ = fs::remove_file(plan_path(repo.path()));
+}
+
+/// If a plan was saved for the same HEAD and commit range, ask whether to
+/// restore it. Declining discards the saved plan; a stale plan is removed.
+pub fn offer_restore(repo: &Repository, commits: &[CommitInfo]) -> Result<Option<Vec<TodoEntry>>> {
+    let path = plan_path(repo.path());
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let head = head_id(repo).unwrap_or_default();
+    let Some(entries) = load_from(repo.path(), &head, commits) else {
+        clear_plan(repo);
+        return Ok(None);
+    };
+
+    let actions = entries
+        .iter()
+        .filter(|e| !matches!(e.action, RebaseAction::Pick))
+        .count();
+    print!(
+        "{} Restore saved craft plan ({} action(s))? [Y/n] ",
+        "?".cyan(),
+        actions
+    );
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+
+    if input.trim().eq_ignore_ascii_case("n") {
+        clear_plan(repo);
+        return Ok(None);
+    }
+    Ok(Some(entries))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Local;
+    use tempfile::TempDir;
+
+    fn commit(byte: u8) -> CommitInfo {
+        CommitInfo {
+            id: git2::Oid::from_bytes(&[byte; 20]).unwrap(),
+            short_id: String::new(),
+            message: String::new(),
+            author: String::new(),
+            time: Local::now(),
+            parents: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_plan_roundtrip_and_base_check() {
+        let dir = TempDir::new().unwrap();
+        let commits = vec![commit(1), commit(2)];
+        let entries = vec![
+            TodoEntry { original_idx: 1, action: RebaseAction::Reword("new".into()) },
+            TodoEntry::pick(0),
+        ];
+        save_to(dir.path(), "abc", &commits, &entries).unwrap();
+
+        let restored = load_from(dir.path(), "abc", &commits).unwrap();
+        assert_eq!(restored.len(), 2);
+        assert_eq!(restored[0].original_idx, 1);
+        assert!(matches!(&restored[0].action, RebaseAction::Reword(m) if m == "new"));
+
+        assert!(load_from(dir.path(), "def", &commits).is_none());
+        assert!(load_from(dir.path(), "abc", &[commit(1), commit(3)]).is_none());
+    }
+
+    #[test]
+    fn test_is_modified() {
+        assert!(!is_modified(&[TodoEntry::pick(0), TodoEntry::pick(1)]));
+        assert!(is_modified(&[TodoEntry::pick(1), TodoEntry::pick(0)]));
+        assert!(is_modified(&[TodoEntry { original_idx: 0, action: RebaseAction::Drop }]));
+    }
+}
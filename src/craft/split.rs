@@ -1,5 +1,5 @@
 use anyhow::{Context, Result};
-use git2::{Oid, Repository};
+use git2::{Delta, Oid, Repository};
 
 #[derive(Debug, Clone)]
 pub struct Hunk {
@@ -8,6 +8,17 @@ pub struct Hunk {
     pub lines: Vec<DiffLine>,
     pub old_start: u32,
     pub new_start: u32,
+    pub file: FileChange,
+}
+
+/// How the hunk's file changed in the commit, for the patch's file header
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileChange {
+    pub status: Delta,
+    pub old_id: Oid,
+    pub new_id: Oid,
+    pub old_mode: u32,
+    pub new_mode: u32,
 }
 
 #[derive(Debug, Clone)]
@@ -23,6 +34,57 @@ impl Hunk {
         let removed = self.lines.iter().filter(|l| matches!(l, DiffLine::Removed(_))).count();
         format!("{} +{} -{}", self.file_path, added, removed)
     }
+
+    /// (old, new) line counts for the `@@` header
+    fn line_counts(&self) -> (i64, i64) {
+        self.lines.iter().fold((0, 0), |(old, new), l| match l {
+            DiffLine::Context(_) => (old + 1, new + 1),
+            DiffLine::Removed(_) => (old + 1, new),
+            DiffLine::Added(_) => (old, new + 1),
+        })
+    }
+
+    fn net_lines(&self) -> i64 {
+        let (old, new) = self.line_counts();
+        new - old
+    }
+}
+
+/// Indices of the hunks assigned to `group` (groups are 1-based; 0 = unassigned)
+pub fn hunks_in_group(hunk_groups: &[usize], group: usize) -> Vec<usize> {
+    hunk_groups
+        .iter()
+        .enumerate()
+        .filter(|(_, &g)| g == group)
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// One group per distinct file, numbered 1.. in order of first appearance
+pub fn group_hunks_by_file(hunks: &[Hunk]) -> Vec<usize> {
+    let mut files: Vec<&str> = Vec::new();
+    hunks
+        .iter()
+        .map(|h| match files.iter().position(|f| *f == h.file_path) {
+            Some(i) => i + 1,
+            None => {
+                files.push(&h.file_path);
+                files.len()
+            }
+        })
+        .collect()
+}
+
+/// Default split message for a file: "add <file>", "remove <file>" or "update <file>"
+pub fn file_group_message(hunk: &Hunk) -> String {
+    let action = if hunk.old_start == 0 {
+        "add"
+    } else if hunk.new_start == 0 {
+        "remove"
+    } else {
+        "update"
+    };
+    format!("{} {}", action, hunk.file_path)
 }
 
 pub fn get_commit_hunks(repo: &Repository, commit_oid: Oid) -> Result<Vec<Hunk>> {
@@ -38,20 +100,39 @@ pub fn get_commit_hunks(repo: &Repository, commit_oid: Oid) -> Result<Vec<Hunk>>
     let diff = repo
         .diff_tree_to_tree(parent_tree.as_ref(), Some(&commit_tree), None)
         .context("diff tree to tree")?;
+    hunks_from_diff(&diff)
+}
+
+/// Unstaged hunks of tracked files (working tree vs index), for `commit --patch`
+pub fn get_unstaged_hunks(repo: &Repository) -> Result<Vec<Hunk>> {
+    let diff = repo
+        .diff_index_to_workdir(None, None)
+        .context("diff index to workdir")?;
+    hunks_from_diff(&diff)
+}
 
+fn hunks_from_diff(diff: &git2::Diff) -> Result<Vec<Hunk>> {
     // Parse hunks by iterating over deltas/patches (avoids borrow issues with foreach)
     let mut hunks: Vec<Hunk> = Vec::new();
 
     for delta_idx in 0..diff.deltas().len() {
-        let patch = git2::Patch::from_diff(&diff, delta_idx)
+        let patch = git2::Patch::from_diff(diff, delta_idx)
             .context("get patch")?;
 
         if let Some(patch) = patch {
-            let file_path = diff
-                .get_delta(delta_idx)
-                .and_then(|d| d.new_file().path())
+            let delta = diff.get_delta(delta_idx).context("get delta")?;
+            let file_path = delta
+                .new_file()
+                .path()
                 .map(|p| p.to_string_lossy().to_string())
                 .unwrap_or_default();
+            let file = FileChange {
+                status: delta.status(),
+                old_id: delta.old_file().id(),
+                new_id: delta.new_file().id(),
+                old_mode: u32::from(delta.old_file().mode()),
+                new_mode: u32::from(delta.new_file().mode()),
+            };
 
             for hunk_idx in 0..patch.num_hunks() {
                 let (hunk_header, _) = patch.hunk(hunk_idx).context("get hunk")?;
@@ -81,6 +162,7 @@ pub fn get_commit_hunks(repo: &Repository, commit_oid: Oid) -> Result<Vec<Hunk>>
                     lines,
                     old_start: hunk_header.old_start(),
                     new_start: hunk_header.new_start(),
+                    file: file.clone(),
                 });
             }
         }
@@ -89,6 +171,60 @@ pub fn get_commit_hunks(repo: &Repository, commit_oid: Oid) -> Result<Vec<Hunk>>
     Ok(hunks)
 }
 
+fn short_id(oid: Oid) -> String {
+    oid.to_string()[..7].to_string()
+}
+
+fn file_header(hunk: &Hunk) -> String {
+    let path = &hunk.file_path;
+    let f = &hunk.file;
+    let mut header = format!("diff --git a/{} b/{}\n", path, path);
+    let (old, new) = match f.status {
+        Delta::Added => {
+            header.push_str(&format!("new file mode {:o}\n", f.new_mode));
+            header.push_str(&format!("index {}..{}\n", short_id(f.old_id), short_id(f.new_id)));
+            ("/dev/null".to_string(), format!("b/{}", path))
+        }
+        Delta::Deleted => {
+            header.push_str(&format!("deleted file mode {:o}\n", f.old_mode));
+            header.push_str(&format!("index {}..{}\n", short_id(f.old_id), short_id(f.new_id)));
+            (format!("a/{}", path), "/dev/null".to_string())
+        }
+        _ => {
+            header.push_str(&format!(
+                "index {}..{} {:o}\n",
+                short_id(f.old_id),
+                short_id(f.new_id),
+                f.new_mode
+            ));
+            (format!("a/{}", path), format!("b/{}", path))
+        }
+    };
+    header.push_str(&format!("--- {}\n+++ {}\n", old, new));
+    header
+}
+
+/// `@@` header with ranges recomputed for the hunk's own lines. The new-side
+/// start drops the shift from earlier hunks of the file that aren't selected.
+fn hunk_header(hunk: &Hunk, skipped_shift: i64) -> String {
+    let (old_count, new_count) = hunk.line_counts();
+    let new_start = (hunk.new_start as i64 - skipped_shift).max(0);
+    // keep the function context git prints after the closing @@
+    let context = hunk
+        .header
+        .splitn(3, "@@")
+        .nth(2)
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .map(|c| format!(" {}", c))
+        .unwrap_or_default();
+    format!(
+        "@@ -{},{} +{},{} @@{}",
+        hunk.old_start, old_count, new_start, new_count, context
+    )
+}
+
+/// Patch applying `selected` hunks (file order) on top of the commit's parent
 pub fn generate_patch_for_hunks(hunks: &[Hunk], selected: &[usize]) -> String {
     let mut patch = String::new();
     let mut current_file: Option<&str> = None;
@@ -97,14 +233,18 @@ pub fn generate_patch_for_hunks(hunks: &[Hunk], selected: &[usize]) -> String {
         let hunk = &hunks[idx];
 
         if current_file != Some(&hunk.file_path) {
-            // file header
-            patch.push_str(&format!("--- a/{}\n", hunk.file_path));
-            patch.push_str(&format!("+++ b/{}\n", hunk.file_path));
+            patch.push_str(&file_header(hunk));
             current_file = Some(&hunk.file_path);
         }
 
-        // hunk header
-        patch.push_str(&format!("{}\n", hunk.header));
+        let skipped_shift: i64 = hunks[..idx]
+            .iter()
+            .enumerate()
+            .filter(|(i, h)| h.file_path == hunk.file_path && !selected.contains(i))
+            .map(|(_, h)| h.net_lines())
+            .sum();
+        patch.push_str(&hunk_header(hunk, skipped_shift));
+        patch.push('\n');
 
         // lines
         for line in &hunk.lines {
@@ -130,3 +270,105 @@ pub fn generate_patch_for_hunks(hunks: &[Hunk], selected: &[usize]) -> String {
 
     patch
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_files, init_repo};
+    use std::fs;
+    use std::path::Path;
+    use std::process::Command;
+
+    fn hunk(file: &str, old_start: u32, new_start: u32) -> Hunk {
+        Hunk {
+            file_path: file.to_string(),
+            header: format!("@@ -{} +{} @@", old_start, new_start),
+            lines: Vec::new(),
+            old_start,
+            new_start,
+            file: FileChange {
+                status: Delta::Modified,
+                old_id: Oid::zero(),
+                new_id: Oid::zero(),
+                old_mode: 0o100644,
+                new_mode: 0o100644,
+            },
+        }
+    }
+
+    #[test]
+    fn test_group_hunks_by_file() {
+        let hunks = vec![
+            hunk("src/a.rs", 1, 1),
+            hunk("src/a.rs", 40, 42),
+            hunk("src/b.rs", 0, 1),
+            hunk("README.md", 3, 3),
+            hunk("src/b.rs", 0, 20),
+        ];
+        assert_eq!(group_hunks_by_file(&hunks), vec![1, 1, 2, 3, 2]);
+        assert!(group_hunks_by_file(&[]).is_empty());
+
+        assert_eq!(file_group_message(&hunks[0]), "update src/a.rs");
+        assert_eq!(file_group_message(&hunks[2]), "add src/b.rs");
+        assert_eq!(file_group_message(&hunk("old.rs", 1, 0)), "remove old.rs");
+    }
+
+    #[test]
+    fn test_hunks_in_group() {
+        let groups = [1, 0, 2, 1, 2, 0];
+        assert_eq!(hunks_in_group(&groups, 1), vec![0, 3]);
+        assert_eq!(hunks_in_group(&groups, 2), vec![2, 4]);
+        assert_eq!(hunks_in_group(&groups, 0), vec![1, 5]);
+        assert!(hunks_in_group(&groups, 3).is_empty());
+        assert!(hunks_in_group(&[], 1).is_empty());
+    }
+
+    fn git_apply_cached(dir: &Path, patch: &str, check: bool) -> bool {
+        let patch_path = dir.join(".git").join("split.patch");
+        fs::write(&patch_path, patch).unwrap();
+        let mut cmd = Command::new("git");
+        cmd.arg("-C").arg(dir).args(["apply", "--cached"]);
+        if check {
+            cmd.arg("--check");
+        }
+        cmd.arg(&patch_path).status().unwrap().success()
+    }
+
+    #[test]
+    fn test_regrouped_patches_apply() {
+        let (dir, repo) = init_repo();
+        let base: String = (1..=30).map(|i| format!("line {}\n", i)).collect();
+        commit_files(&repo, &[("a.txt", &base)], "base");
+
+        let changed = base
+            .replace("line 2\n", "line 2\nnew top 1\nnew top 2\n")
+            .replace("line 28\n", "line 28 changed\n");
+        let oid = commit_files(&repo, &[("a.txt", &changed), ("b.txt", "fresh\n")], "big");
+        let hunks = get_commit_hunks(&repo, oid).unwrap();
+        assert_eq!(hunks.len(), 3);
+        assert_eq!(hunks[2].file.status, Delta::Added);
+
+        // back to the parent's index, as after `git reset HEAD^`
+        let parent_tree = repo.find_commit(oid).unwrap().parent(0).unwrap().tree().unwrap();
+        let mut index = repo.index().unwrap();
+        index.read_tree(&parent_tree).unwrap();
+        index.write().unwrap();
+
+        // bottom hunk and new file first, then the top hunk
+        let first = generate_patch_for_hunks(&hunks, &[1, 2]);
+        assert!(first.contains("@@ -25,6 +25,6 @@"));
+        assert!(first.contains("new file mode 100644\n"));
+        assert!(first.contains("--- /dev/null\n+++ b/b.txt\n"));
+        assert!(git_apply_cached(dir.path(), &first, true));
+        assert!(git_apply_cached(dir.path(), &first, false));
+
+        let second = generate_patch_for_hunks(&hunks, &[0]);
+        assert!(git_apply_cached(dir.path(), &second, true));
+        assert!(git_apply_cached(dir.path(), &second, false));
+
+        let mut index = repo.index().unwrap();
+        index.read(true).unwrap();
+        let tree_id = index.write_tree().unwrap();
+        assert_eq!(tree_id, repo.find_commit(oid).unwrap().tree_id());
+    }
+}
@@ -1,22 +1,38 @@
+use std::cell::Cell;
 use std::collections::HashMap;
 use std::io::stdout;
 
 use anyhow::Result;
 use crossterm::{
-    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-    ExecutableCommand,
+    event::{Event, KeyCode, KeyEventKind, KeyModifiers},
 };
-use git2::Repository;
+use git2::{Oid, Repository};
 use ratatui::{
     prelude::*,
     widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
 };
 
-use crate::ai::{AiProvider, generate_commit_message};
-use crate::models::{format_relative_time, CommitInfo};
-use super::actions::{RebaseAction, SplitGroup, TodoEntry};
-use super::split::{get_commit_hunks, Hunk};
+use crate::ai::{exclude_ai_diff_files, AiProvider, generate_commit_message};
+use crate::config::Config;
+use crate::git::{get_commit_stat, CommitStat};
+use crate::models::{format_relative_time, truncate, CommitInfo};
+use crate::render::help::{footer_spans, render_help_overlay, KeyHelp};
+use crate::render::redraw::Redraw;
+use crate::render::scroll::{cursor_line, follow_cursor, move_lines, next_char, prev_char, PAGE_LINES};
+use crate::terminal::TuiGuard;
+use super::actions::{action_count, move_for_squash, squash_chain, RebaseAction, SplitGroup, TodoEntry};
+use super::split::{
+    file_group_message, get_commit_hunks, group_hunks_by_file, hunks_in_group, Hunk,
+};
+
+/// A commit's full message (subject and body); `CommitInfo` keeps only the
+/// subject. Falls back to it if the commit can't be read.
+fn full_message(repo: &Repository, commit: &CommitInfo) -> String {
+    repo.find_commit(commit.id)
+        .ok()
+        .and_then(|c| c.message().map(|m| m.trim_end().to_string()))
+        .unwrap_or_else(|| commit.message.clone())
+}
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum Mode {
@@ -31,7 +47,7 @@ enum Mode {
 
 pub enum CraftResult {
     Execute(Vec<TodoEntry>, HashMap<usize, Vec<Hunk>>),
-    Cancel,
+    Cancel(Vec<TodoEntry>),
 }
 
 struct App {
@@ -45,9 +61,14 @@ struct App {
     // selection state
     selected: Vec<bool>,
 
+    // `/` filter on subject/author; rows that don't match are hidden
+    filter: String,
+    filter_editing: bool,
+
     // reword state
     reword_buffer: String,
     reword_cursor: usize,
+    reword_scroll: Cell<u16>,
 
     // split state
     hunks: Vec<Hunk>,
@@ -60,6 +81,7 @@ struct App {
 
     // squash state
     squash_source: usize,
+    squash_into: Option<usize>, // set while RewordEdit holds a squash message
 
     // diff preview
     diff_text: String,
@@ -68,12 +90,18 @@ struct App {
     // hunk cache for execution
     hunks_cache: HashMap<usize, Vec<Hunk>>,
 
+    // +/- per commit, filled lazily for shown rows; None = merge or diff error
+    stats_cache: HashMap<Oid, Option<CommitStat>>,
+
     // status message
     status: String,
 
     // AI provider
     ai_provider: Option<AiProvider>,
     ai_loading: bool,
+
+    // help overlay
+    show_help: bool,
 }
 
 impl App {
@@ -88,8 +116,11 @@ impl App {
             should_quit: false,
             result: None,
             selected: vec![false; len],
+            filter: String::new(),
+            filter_editing: false,
             reword_buffer: String::new(),
             reword_cursor: 0,
+            reword_scroll: Cell::new(0),
             hunks: Vec::new(),
             hunk_cursor: 0,
             hunk_groups: Vec::new(),
@@ -98,22 +129,203 @@ impl App {
             split_editing_msg: false,
             next_group: 1,
             squash_source: 0,
+            squash_into: None,
             diff_text: String::new(),
             diff_scroll: 0,
             hunks_cache: HashMap::new(),
+            stats_cache: HashMap::new(),
             status: String::new(),
             ai_provider,
             ai_loading: false,
+            show_help: false,
+        }
+    }
+
+    /// Start from a prepared plan (saved or autosquash): reorder commits and
+    /// selections to match and reload hunks for splits
+    fn load_plan(&mut self, entries: Vec<TodoEntry>, repo: &Repository) {
+        // current row of each original commit
+        let mut row = vec![0; self.entries.len()];
+        for (i, e) in self.entries.iter().enumerate() {
+            row[e.original_idx] = i;
+        }
+        let commits: Vec<CommitInfo> =
+            entries.iter().map(|e| self.commits[row[e.original_idx]].clone()).collect();
+        self.selected = entries.iter().map(|e| self.selected[row[e.original_idx]]).collect();
+        for entry in &entries {
+            if matches!(entry.action, RebaseAction::Split { .. }) {
+                if let Ok(hunks) = get_commit_hunks(repo, self.commits[row[entry.original_idx]].id) {
+                    self.hunks_cache.insert(entry.original_idx, hunks);
+                }
+            }
+        }
+        self.commits = commits;
+        let actions = entries.iter().filter(|e| !matches!(e.action, RebaseAction::Pick)).count();
+        self.entries = entries;
+        self.status = format!("{} action(s) loaded — p=preview", actions);
+    }
+
+    /// Whether keys are typed into a text field rather than treated as commands
+    fn is_text_input(&self) -> bool {
+        self.mode == Mode::RewordEdit
+            || (self.mode == Mode::SplitView && self.split_editing_msg)
+            || self.filter_editing
+    }
+
+    /// Compute stats for shown rows that aren't cached yet
+    fn load_stats(&mut self, repo: &Repository) {
+        for i in self.visible_rows() {
+            let oid = self.commits[i].id;
+            self.stats_cache
+                .entry(oid)
+                .or_insert_with(|| get_commit_stat(repo, oid).ok().flatten());
+        }
+    }
+
+    /// Whether row `i` passes the filter
+    fn is_visible(&self, i: usize) -> bool {
+        self.filter.is_empty() || self.commits[i].matches(&self.filter)
+    }
+
+    fn visible_rows(&self) -> Vec<usize> {
+        (0..self.commits.len()).filter(|&i| self.is_visible(i)).collect()
+    }
+
+    /// Move the cursor `delta` visible rows, staying on visible rows
+    fn move_cursor(&mut self, delta: isize) {
+        let rows = self.visible_rows();
+        let Some(pos) = rows.iter().position(|&i| i == self.cursor) else {
+            self.clamp_cursor();
+            return;
+        };
+        let new_pos = (pos as isize + delta).clamp(0, rows.len() as isize - 1);
+        self.cursor = rows[new_pos as usize];
+    }
+
+    /// After the filter changes, move a hidden cursor to the nearest visible row
+    fn clamp_cursor(&mut self) {
+        let rows = self.visible_rows();
+        if rows.contains(&self.cursor) {
+            return;
+        }
+        if let Some(&next) = rows.iter().find(|&&i| i > self.cursor).or(rows.last()) {
+            self.cursor = next;
         }
     }
 
+    fn clear_filter(&mut self) {
+        self.filter.clear();
+        self.filter_editing = false;
+    }
+
+    fn handle_filter_input(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Enter => self.filter_editing = false,
+            KeyCode::Esc => {
+                self.clear_filter();
+                self.status.clear();
+                return;
+            }
+            KeyCode::Backspace => {
+                self.filter.pop();
+            }
+            KeyCode::Char(c) => self.filter.push(c),
+            _ => {}
+        }
+        self.clamp_cursor();
+        let shown = self.visible_rows().len();
+        self.status = format!("{} of {} commits match", shown, self.commits.len());
+    }
+
+    /// Keys for the current mode, shown in the footer and the `?` overlay
+    fn mode_keys(&self) -> Vec<KeyHelp> {
+        let mut keys = match self.mode {
+            Mode::CommitList if self.filter_editing => KeyHelp::list(&[
+                ("Enter", "apply filter"),
+                ("Esc", "clear filter"),
+            ]),
+            Mode::CommitList => KeyHelp::list(&[
+                ("j/k", "nav"),
+                ("/", "filter"),
+                ("Enter", "actions"),
+                ("D", "diff"),
+                ("p", "preview"),
+                ("q", "quit"),
+            ]),
+            Mode::ActionMenu => KeyHelp::list(&[
+                ("r", "reword"),
+                ("s", "split"),
+                ("q", "squash"),
+                ("f", "fixup"),
+                ("d", "drop"),
+                ("m", "reorder"),
+                ("e", "edit"),
+                ("x", "reset"),
+                ("Esc", "back"),
+            ]),
+            Mode::RewordEdit => KeyHelp::list(&[
+                ("Tab", "AI generate"),
+                ("PgUp/PgDn", "move a page"),
+                ("Esc", "save & exit"),
+            ]),
+            Mode::SplitView if self.split_editing_msg => KeyHelp::list(&[
+                ("Esc/Enter", "done"),
+            ]),
+            Mode::SplitView => KeyHelp::list(&[
+                ("j/k", "nav"),
+                ("space", "toggle"),
+                ("1-9", "assign"),
+                ("g", "new group"),
+                ("F", "by file"),
+                ("n", "name"),
+                ("Enter", "done"),
+                ("!", "done, discard unassigned"),
+            ]),
+            Mode::SquashTarget => KeyHelp::list(&[
+                ("j/k", "select"),
+                ("Enter", "confirm"),
+                ("Esc", "cancel"),
+            ]),
+            Mode::ReorderMode => KeyHelp::list(&[
+                ("J/K", "move"),
+                ("j/k", "nav"),
+                ("Esc/Enter", "done"),
+            ]),
+            Mode::Preview => KeyHelp::list(&[
+                ("y/Enter", "execute"),
+                ("j/k", "scroll"),
+                ("Esc", "back"),
+            ]),
+        };
+        if !self.is_text_input() {
+            keys.push(KeyHelp::new("?", "help"));
+        }
+        keys
+    }
+
     fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers, repo: &Repository) {
+        if self.show_help {
+            if matches!(code, KeyCode::Char('?') | KeyCode::Esc) {
+                self.show_help = false;
+            }
+            return;
+        }
+        if code == KeyCode::Char('?') && !self.is_text_input() {
+            self.show_help = true;
+            return;
+        }
+
+        if self.filter_editing {
+            self.handle_filter_input(code);
+            return;
+        }
+
         match self.mode {
             Mode::CommitList => self.handle_commit_list(code, repo),
             Mode::ActionMenu => self.handle_action_menu(code, repo),
             Mode::RewordEdit => self.handle_reword_edit(code, repo),
             Mode::SplitView => self.handle_split_view(code),
-            Mode::SquashTarget => self.handle_squash_target(code),
+            Mode::SquashTarget => self.handle_squash_target(code, repo),
             Mode::ReorderMode => self.handle_reorder(code, modifiers),
             Mode::Preview => self.handle_preview(code),
         }
@@ -121,12 +333,21 @@ impl App {
 
     // --- CommitList mode ---
     fn handle_commit_list(&mut self, code: KeyCode, repo: &Repository) {
+        // with every row filtered out there is nothing to act on
+        let nothing_visible = !self.is_visible(self.cursor);
         match code {
-            KeyCode::Char('j') | KeyCode::Down => {
-                self.cursor = (self.cursor + 1).min(self.commits.len().saturating_sub(1));
+            KeyCode::Char('j') | KeyCode::Down => self.move_cursor(1),
+            KeyCode::Char('k') | KeyCode::Up => self.move_cursor(-1),
+            KeyCode::Char('/') => {
+                self.filter_editing = true;
+                self.status = "filter: type to match subject or author".into();
             }
-            KeyCode::Char('k') | KeyCode::Up => {
-                self.cursor = self.cursor.saturating_sub(1);
+            KeyCode::Esc if !self.filter.is_empty() => {
+                self.clear_filter();
+                self.status = "filter cleared".into();
+            }
+            KeyCode::Char(' ') | KeyCode::Enter | KeyCode::Char('D') if nothing_visible => {
+                self.status = "no commits match the filter".into();
             }
             KeyCode::Char(' ') => {
                 self.selected[self.cursor] = !self.selected[self.cursor];
@@ -151,7 +372,7 @@ impl App {
                 self.load_diff_for_cursor(repo);
             }
             KeyCode::Char('q') | KeyCode::Esc => {
-                self.result = Some(CraftResult::Cancel);
+                self.result = Some(CraftResult::Cancel(self.entries.clone()));
                 self.should_quit = true;
             }
             _ => {}
@@ -193,7 +414,7 @@ impl App {
                             return;
                         }
                         let len = hunks.len();
-                        self.hunks_cache.insert(self.cursor, hunks.clone());
+                        self.hunks_cache.insert(self.entries[self.cursor].original_idx, hunks.clone());
                         self.hunks = hunks;
                         self.hunk_cursor = 0;
                         self.hunk_groups = vec![0; len];
@@ -219,40 +440,47 @@ impl App {
             KeyCode::Char('f') => {
                 // fixup (squash without message edit)
                 self.squash_source = self.cursor;
-                // For fixup, pick the commit above (cursor - 1)
-                if self.cursor > 0 {
+                // git's `fixup` melds a todo line into the one before it, the
+                // older commit below, so name that one as the target
+                if self.cursor + 1 < self.commits.len() {
                     self.entries[self.cursor] = TodoEntry {
-                        original_idx: self.cursor,
-                        action: RebaseAction::Fixup { into_idx: self.cursor - 1 },
+                        original_idx: self.entries[self.cursor].original_idx,
+                        action: RebaseAction::Fixup { into_idx: self.cursor + 1 },
                     };
                     self.status = format!(
                         "fixup {} into {}",
                         self.commits[self.cursor].short_id,
-                        self.commits[self.cursor - 1].short_id
+                        self.commits[self.cursor + 1].short_id
                     );
                 } else {
-                    self.status = "cannot fixup first commit".into();
+                    self.status = "cannot fixup oldest commit".into();
                 }
                 self.mode = Mode::CommitList;
             }
             KeyCode::Char('d') => {
                 // drop
                 self.entries[self.cursor] = TodoEntry {
-                    original_idx: self.cursor,
+                    original_idx: self.entries[self.cursor].original_idx,
                     action: RebaseAction::Drop,
                 };
                 self.status = format!("drop {}", self.commits[self.cursor].short_id);
                 self.mode = Mode::CommitList;
             }
             KeyCode::Char('m') => {
-                // reorder mode
+                // reorder mode; moves swap adjacent rows, so show them all
+                let filtered = !self.filter.is_empty();
+                self.clear_filter();
                 self.mode = Mode::ReorderMode;
-                self.status = "J/K=move commit Esc=done".into();
+                self.status = if filtered {
+                    "filter cleared — J/K=move commit Esc=done".into()
+                } else {
+                    "J/K=move commit Esc=done".into()
+                };
             }
             KeyCode::Char('e') => {
                 // edit (stop for manual editing)
                 self.entries[self.cursor] = TodoEntry {
-                    original_idx: self.cursor,
+                    original_idx: self.entries[self.cursor].original_idx,
                     action: RebaseAction::Edit,
                 };
                 self.status = format!("edit stop at {}", self.commits[self.cursor].short_id);
@@ -286,7 +514,8 @@ impl App {
                     Ok(diff) if !diff.is_empty() => {
                         self.ai_loading = true;
                         self.status = format!("generating with {}...", provider.name());
-                        match generate_commit_message(provider, &diff, None, None) {
+                        let diff = exclude_ai_diff_files(&diff, &Config::load().unwrap_or_default());
+                        match generate_commit_message(provider, &diff, None, None, None) {
                             Ok(msg) => {
                                 self.reword_buffer = msg;
                                 self.reword_cursor = self.reword_buffer.len();
@@ -307,10 +536,24 @@ impl App {
                 }
             }
             KeyCode::Esc => {
+                if let Some(into_idx) = self.squash_into.take() {
+                    let message = (!self.reword_buffer.trim().is_empty())
+                        .then(|| self.reword_buffer.clone());
+                    // git edits one message per chain; this one now covers it
+                    for i in squash_chain(&self.entries, self.cursor) {
+                        if let RebaseAction::Squash { message: m, .. } = &mut self.entries[i].action {
+                            *m = None;
+                        }
+                    }
+                    self.entries[self.cursor].action = RebaseAction::Squash { into_idx, message };
+                    self.status = format!("squash {}", self.commits[self.cursor].short_id);
+                    self.mode = Mode::CommitList;
+                    return;
+                }
                 // Save reword action
                 if !self.reword_buffer.is_empty() && self.reword_buffer != self.commits[self.cursor].message {
                     self.entries[self.cursor] = TodoEntry {
-                        original_idx: self.cursor,
+                        original_idx: self.entries[self.cursor].original_idx,
                         action: RebaseAction::Reword(self.reword_buffer.clone()),
                     };
                     self.status = format!("reword {}", self.commits[self.cursor].short_id);
@@ -323,7 +566,7 @@ impl App {
             }
             KeyCode::Backspace => {
                 if self.reword_cursor > 0 {
-                    self.reword_cursor -= 1;
+                    self.reword_cursor = prev_char(&self.reword_buffer, self.reword_cursor);
                     self.reword_buffer.remove(self.reword_cursor);
                 }
             }
@@ -333,16 +576,22 @@ impl App {
                 }
             }
             KeyCode::Left => {
-                self.reword_cursor = self.reword_cursor.saturating_sub(1);
+                self.reword_cursor = prev_char(&self.reword_buffer, self.reword_cursor);
             }
             KeyCode::Right => {
-                self.reword_cursor = (self.reword_cursor + 1).min(self.reword_buffer.len());
+                self.reword_cursor = next_char(&self.reword_buffer, self.reword_cursor);
             }
             KeyCode::Home => self.reword_cursor = 0,
             KeyCode::End => self.reword_cursor = self.reword_buffer.len(),
+            KeyCode::PageUp => {
+                self.reword_cursor = move_lines(&self.reword_buffer, self.reword_cursor, -PAGE_LINES);
+            }
+            KeyCode::PageDown => {
+                self.reword_cursor = move_lines(&self.reword_buffer, self.reword_cursor, PAGE_LINES);
+            }
             KeyCode::Char(c) => {
                 self.reword_buffer.insert(self.reword_cursor, c);
-                self.reword_cursor += 1;
+                self.reword_cursor += c.len_utf8();
             }
             _ => {}
         }
@@ -399,6 +648,21 @@ impl App {
                 self.hunk_groups[self.hunk_cursor] = self.next_group;
                 self.next_group += 1;
             }
+            KeyCode::Char('F') => {
+                // split by file: one group per file, messages pre-filled
+                self.hunk_groups = group_hunks_by_file(&self.hunks);
+                let max_group = self.hunk_groups.iter().copied().max().unwrap_or(0);
+                if self.split_messages.len() <= max_group {
+                    self.split_messages.resize(max_group + 1, String::new());
+                }
+                for g in 1..=max_group {
+                    if let Some(&first) = hunks_in_group(&self.hunk_groups, g).first() {
+                        self.split_messages[g] = file_group_message(&self.hunks[first]);
+                    }
+                }
+                self.next_group = max_group + 1;
+                self.status = format!("split into {} file group(s) — n=rename Enter=done", max_group);
+            }
             KeyCode::Char('n') => {
                 // name/edit group message
                 let group = self.hunk_groups[self.hunk_cursor];
@@ -409,9 +673,29 @@ impl App {
                 }
             }
             KeyCode::Enter => {
-                // finalize split
-                self.finalize_split();
-                self.mode = Mode::CommitList;
+                // unassigned hunks would be dropped from the commit, so make
+                // the user choose explicitly
+                let unassigned = self.unassigned_hunks();
+                if unassigned > 0 && self.hunk_groups.iter().any(|&g| g > 0) {
+                    self.status = format!(
+                        "{} hunk(s) unassigned — assign or press ! to discard",
+                        unassigned
+                    );
+                    return;
+                }
+                if self.finalize_split() {
+                    self.mode = Mode::CommitList;
+                }
+            }
+            KeyCode::Char('!') => {
+                // finalize, discarding unassigned hunks
+                let unassigned = self.unassigned_hunks();
+                if self.finalize_split() {
+                    if unassigned > 0 {
+                        self.status.push_str(&format!(", {} hunk(s) discarded", unassigned));
+                    }
+                    self.mode = Mode::CommitList;
+                }
             }
             KeyCode::Esc => {
                 self.mode = Mode::CommitList;
@@ -421,21 +705,21 @@ impl App {
         }
     }
 
-    fn finalize_split(&mut self) {
+    fn unassigned_hunks(&self) -> usize {
+        self.hunk_groups.iter().filter(|&&g| g == 0).count()
+    }
+
+    /// Store the split action; false if no hunk is assigned to a group
+    fn finalize_split(&mut self) -> bool {
         let max_group = self.hunk_groups.iter().copied().max().unwrap_or(0);
         if max_group == 0 {
             self.status = "no hunks assigned to groups".into();
-            return;
+            return false;
         }
 
         let mut groups = Vec::new();
         for g in 1..=max_group {
-            let indices: Vec<usize> = self.hunk_groups
-                .iter()
-                .enumerate()
-                .filter(|(_, &grp)| grp == g)
-                .map(|(i, _)| i)
-                .collect();
+            let indices = hunks_in_group(&self.hunk_groups, g);
             if indices.is_empty() {
                 continue;
             }
@@ -451,37 +735,59 @@ impl App {
         }
 
         self.entries[self.cursor] = TodoEntry {
-            original_idx: self.cursor,
+            original_idx: self.entries[self.cursor].original_idx,
             action: RebaseAction::Split { groups },
         };
         self.status = format!("split {} into {} parts", self.commits[self.cursor].short_id, max_group);
+        true
     }
 
     // --- SquashTarget mode ---
-    fn handle_squash_target(&mut self, code: KeyCode) {
+    fn handle_squash_target(&mut self, code: KeyCode, repo: &Repository) {
         match code {
-            KeyCode::Char('j') | KeyCode::Down => {
-                self.cursor = (self.cursor + 1).min(self.commits.len().saturating_sub(1));
-            }
-            KeyCode::Char('k') | KeyCode::Up => {
-                self.cursor = self.cursor.saturating_sub(1);
-            }
+            KeyCode::Char('j') | KeyCode::Down => self.move_cursor(1),
+            KeyCode::Char('k') | KeyCode::Up => self.move_cursor(-1),
             KeyCode::Enter => {
-                if self.cursor != self.squash_source {
-                    self.entries[self.squash_source] = TodoEntry {
-                        original_idx: self.squash_source,
-                        action: RebaseAction::Squash {
-                            into_idx: self.cursor,
-                            message: None,
-                        },
-                    };
-                    self.status = format!(
-                        "squash {} into {}",
-                        self.commits[self.squash_source].short_id,
-                        self.commits[self.cursor].short_id
-                    );
+                let source = self.squash_source;
+                let target = self.cursor;
+                if target == source {
+                    self.mode = Mode::CommitList;
+                    return;
                 }
-                self.mode = Mode::CommitList;
+                // git squashes into the preceding (older) commit, so the
+                // target must be below and the source moved right above it
+                let Some(new_source) = move_for_squash(&mut self.entries, source, target) else {
+                    self.status = "squash target must be an older commit (below)".into();
+                    return;
+                };
+                move_for_squash(&mut self.commits, source, target);
+                move_for_squash(&mut self.selected, source, target);
+
+                self.entries[new_source].action = RebaseAction::Squash {
+                    into_idx: target,
+                    message: None,
+                };
+                let moved = if new_source != source { " (moved above target)" } else { "" };
+                self.status = format!(
+                    "squash {} into {}{} — edit combined message, Esc=save",
+                    self.commits[new_source].short_id,
+                    self.commits[target].short_id,
+                    moved
+                );
+                self.cursor = new_source;
+
+                // git lists the older message first; a chain already squashing
+                // into the target carries the combined text so far
+                let older = squash_chain(&self.entries, new_source)
+                    .find_map(|i| match &self.entries[i].action {
+                        RebaseAction::Squash { message: Some(m), .. } if i != new_source => Some(m.clone()),
+                        _ => None,
+                    })
+                    .unwrap_or_else(|| full_message(repo, &self.commits[target]));
+                self.reword_buffer = format!("{}\n\n{}", older, full_message(repo, &self.commits[new_source]));
+                self.reword_cursor = self.reword_buffer.len();
+                self.squash_into = Some(target);
+                self.mode = Mode::RewordEdit;
             }
             KeyCode::Esc => {
                 self.cursor = self.squash_source;
@@ -564,21 +870,30 @@ impl App {
     }
 }
 
-pub fn run_craft_tui(commits: Vec<CommitInfo>, repo: &Repository, ai_provider: Option<AiProvider>) -> Result<CraftResult> {
-    enable_raw_mode()?;
-    stdout().execute(EnterAlternateScreen)?;
+pub fn run_craft_tui(
+    commits: Vec<CommitInfo>,
+    repo: &Repository,
+    ai_provider: Option<AiProvider>,
+    initial: Option<Vec<TodoEntry>>,
+) -> Result<CraftResult> {
+    let mut app = App::new(commits, ai_provider);
+    if let Some(entries) = initial {
+        app.load_plan(entries, repo);
+    }
 
+    let _guard = TuiGuard::enter()?;
     let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
-    let mut app = App::new(commits, ai_provider);
 
+    let mut redraw = Redraw::new();
     loop {
-        terminal.draw(|f| ui(f, &app))?;
+        if redraw.take(app.ai_loading) {
+            app.load_stats(repo);
+            terminal.draw(|f| ui(f, &app))?;
+        }
 
-        if event::poll(std::time::Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    app.handle_key(key.code, key.modifiers, repo);
-                }
+        if let Some(Event::Key(key)) = redraw.next_event(app.ai_loading)? {
+            if key.kind == KeyEventKind::Press {
+                app.handle_key(key.code, key.modifiers, repo);
             }
         }
 
@@ -587,10 +902,7 @@ pub fn run_craft_tui(commits: Vec<CommitInfo>, repo: &Repository, ai_provider: O
         }
     }
 
-    disable_raw_mode()?;
-    stdout().execute(LeaveAlternateScreen)?;
-
-    Ok(app.result.unwrap_or(CraftResult::Cancel))
+    Ok(app.result.unwrap_or_else(|| CraftResult::Cancel(app.entries.clone())))
 }
 
 // ─── Rendering ──────────────────────────────────────────────
@@ -623,14 +935,17 @@ fn ui(f: &mut Frame, app: &App) {
     }
 
     render_footer(f, app, chunks[2]);
+
+    if app.show_help {
+        render_help_overlay(f, "Craft keys", &app.mode_keys(), f.size());
+    }
 }
 
 fn render_header(f: &mut Frame, app: &App, area: Rect) {
-    let action_count = app.entries.iter().filter(|e| !matches!(e.action, RebaseAction::Pick)).count();
     let title = format!(
         " CRAFT  {} commits  {} action(s) ",
         app.commits.len(),
-        action_count,
+        action_count(&app.entries),
     );
 
     let header = Paragraph::new(title)
@@ -657,6 +972,7 @@ fn render_commit_list(f: &mut Frame, app: &App, area: Rect) {
     let items: Vec<ListItem> = app.commits
         .iter()
         .enumerate()
+        .filter(|(i, _)| app.is_visible(*i))
         .map(|(i, c)| {
             let num = format!("{:>3}", i + 1);
             let action = app.action_label(i);
@@ -667,10 +983,16 @@ fn render_commit_list(f: &mut Frame, app: &App, area: Rect) {
             };
             let time = format_relative_time(&c.time);
 
-            let text = format!(
-                " {} {} {} {}{}",
-                num, c.short_id, truncate(&c.message, 35), time, action_str,
-            );
+            let stat = match app.stats_cache.get(&c.id) {
+                Some(Some(st)) => format!(" +{}/-{} {}f", st.insertions, st.deletions, st.files),
+                _ => String::new(),
+            };
+
+            let text = Line::from(vec![
+                Span::raw(format!(" {} {} {}", num, c.short_id, truncate(&c.message, 35))),
+                Span::styled(stat, Style::default().add_modifier(Modifier::DIM)),
+                Span::raw(format!(" {}{}", time, action_str)),
+            ]);
 
             let style = if i == app.cursor {
                 match app.mode {
@@ -702,11 +1024,22 @@ fn render_commit_list(f: &mut Frame, app: &App, area: Rect) {
         _ => Style::default().fg(Color::Cyan),
     };
 
-    let title = match app.mode {
+    let mut title = match app.mode {
         Mode::ReorderMode => " Commits (REORDER) ",
         Mode::SquashTarget => " Commits (SQUASH TARGET) ",
         _ => " Commits ",
-    };
+    }
+    .to_string();
+    if app.filter_editing || !app.filter.is_empty() {
+        let caret = if app.filter_editing { "_" } else { "" };
+        title.push_str(&format!(
+            "/{}{} ({}/{}) ",
+            app.filter,
+            caret,
+            app.visible_rows().len(),
+            app.commits.len()
+        ));
+    }
 
     let list = List::new(items).block(
         Block::default()
@@ -823,8 +1156,13 @@ fn render_reword_editor(f: &mut Frame, app: &App, area: Rect) {
     let (before, after) = app.reword_buffer.split_at(app.reword_cursor.min(app.reword_buffer.len()));
     let text = format!("{}|{}", before, after);
 
+    let line = cursor_line(&app.reword_buffer, app.reword_cursor);
+    let scroll = follow_cursor(app.reword_scroll.get(), line, chunks[1].height.saturating_sub(2));
+    app.reword_scroll.set(scroll);
+
     let editor = Paragraph::new(text)
         .wrap(Wrap { trim: false })
+        .scroll((scroll, 0))
         .block(
             Block::default()
                 .borders(Borders::ALL)
@@ -878,6 +1216,31 @@ fn render_split_view(f: &mut Frame, app: &App, area: Rect) {
     // Hunk detail / group messages
     if app.split_editing_msg {
         let idx = app.split_msg_cursor;
+        let members = hunks_in_group(&app.hunk_groups, idx);
+        let right = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length((members.len() as u16 + 2).min(chunks[1].height / 2)),
+                Constraint::Min(3),
+            ])
+            .split(chunks[1]);
+
+        // Hunks in this group, so the message matches what's being committed
+        let member_items: Vec<ListItem> = members
+            .iter()
+            .map(|&i| {
+                let h = &app.hunks[i];
+                ListItem::new(format!(" {} {}", h.file_path, h.header))
+            })
+            .collect();
+        let member_list = List::new(member_items).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan))
+                .title(format!(" Group {} · {} hunk(s) ", idx, members.len())),
+        );
+        f.render_widget(member_list, right[0]);
+
         let msg = &app.split_messages[idx];
         let text = format!("Group {} message:\n\n{}_", idx, msg);
         let widget = Paragraph::new(text)
@@ -888,7 +1251,7 @@ fn render_split_view(f: &mut Frame, app: &App, area: Rect) {
                     .border_style(Style::default().fg(Color::Green))
                     .title(" Group Message "),
             );
-        f.render_widget(widget, chunks[1]);
+        f.render_widget(widget, right[1]);
     } else if !app.hunks.is_empty() {
         // Show current hunk lines
         let hunk = &app.hunks[app.hunk_cursor];
@@ -923,8 +1286,8 @@ fn render_preview(f: &mut Frame, app: &App, area: Rect) {
     lines.push(Line::styled("  Rebase Plan:", Style::default().bold()));
     lines.push(Line::from(""));
 
-    for entry in &app.entries {
-        let c = &app.commits[entry.original_idx];
+    // entries and commits are kept aligned by position through reorders
+    for (entry, c) in app.entries.iter().zip(&app.commits) {
         let action_style = match &entry.action {
             RebaseAction::Pick => Style::default().fg(Color::DarkGray),
             RebaseAction::Reword(_) => Style::default().fg(Color::Yellow),
@@ -937,8 +1300,12 @@ fn render_preview(f: &mut Frame, app: &App, area: Rect) {
 
         let detail = match &entry.action {
             RebaseAction::Reword(msg) => format!(" -> \"{}\"", truncate(msg, 40)),
-            RebaseAction::Squash { into_idx, .. } => {
-                format!(" -> into {}", app.commits[*into_idx].short_id)
+            RebaseAction::Squash { into_idx, message } => {
+                let target = &app.commits[*into_idx].short_id;
+                match message {
+                    Some(msg) => format!(" -> into {} \"{}\"", target, truncate(msg.lines().next().unwrap_or(""), 30)),
+                    None => format!(" -> into {}", target),
+                }
             }
             RebaseAction::Fixup { into_idx } => {
                 format!(" -> into {}", app.commits[*into_idx].short_id)
@@ -976,72 +1343,7 @@ fn render_preview(f: &mut Frame, app: &App, area: Rect) {
 }
 
 fn render_footer(f: &mut Frame, app: &App, area: Rect) {
-    let help = match app.mode {
-        Mode::CommitList => vec![
-            ("j/k", "nav"),
-            ("Enter", "actions"),
-            ("D", "diff"),
-            ("p", "preview"),
-            ("q", "quit"),
-        ],
-        Mode::ActionMenu => vec![
-            ("r", "reword"),
-            ("s", "split"),
-            ("q", "squash"),
-            ("f", "fixup"),
-            ("d", "drop"),
-            ("m", "reorder"),
-            ("e", "edit"),
-            ("x", "reset"),
-            ("Esc", "back"),
-        ],
-        Mode::RewordEdit => vec![
-            ("Tab", "AI generate"),
-            ("Esc", "save & exit"),
-        ],
-        Mode::SplitView if app.split_editing_msg => vec![
-            ("Esc/Enter", "done"),
-        ],
-        Mode::SplitView => vec![
-            ("j/k", "nav"),
-            ("space", "toggle"),
-            ("1-9", "assign"),
-            ("g", "new group"),
-            ("n", "name"),
-            ("Enter", "done"),
-        ],
-        Mode::SquashTarget => vec![
-            ("j/k", "select"),
-            ("Enter", "confirm"),
-            ("Esc", "cancel"),
-        ],
-        Mode::ReorderMode => vec![
-            ("J/K", "move"),
-            ("j/k", "nav"),
-            ("Esc/Enter", "done"),
-        ],
-        Mode::Preview => vec![
-            ("y/Enter", "execute"),
-            ("j/k", "scroll"),
-            ("Esc", "back"),
-        ],
-    };
-
-    let help_spans: Vec<Span> = help
-        .iter()
-        .enumerate()
-        .flat_map(|(i, (key, desc))| {
-            let mut spans = vec![
-                Span::styled(*key, Style::default().fg(Color::Cyan)),
-                Span::styled(":", Style::default().fg(Color::Gray)),
-                Span::styled(*desc, Style::default().fg(Color::White)),
-            ];
-            if i < help.len() - 1 {
-                spans.push(Span::styled("  ", Style::default()));
-            }
-            spans
-        })
-        .collect();
+    let help_spans = footer_spans(&app.mode_keys());
 
     let footer_line = if app.status.is_empty() {
         Line::from(help_spans)
@@ -1060,10 +1362,142 @@ fn render_footer(f: &mut Frame, app: &App, area: Rect) {
     f.render_widget(footer, area);
 }
 
-fn truncate(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
-        s.to_string()
-    } else {
-        format!("{}...", &s[..max_len.saturating_sub(3)])
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Local;
+    use tempfile::TempDir;
+
+    fn commit(byte: u8, message: &str) -> CommitInfo {
+        CommitInfo {
+            id: git2::Oid::from_bytes(&[byte; 20]).unwrap(),
+            short_id: format!("{:02x}", byte),
+            message: message.to_string(),
+            author: String::new(),
+            time: Local::now(),
+            parents: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_squash_edits_combined_message() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let mut app = App::new(vec![commit(1, "fix typo"), commit(2, "add parser")], None);
+
+        app.squash_source = 0;
+        app.cursor = 1;
+        app.mode = Mode::SquashTarget;
+        app.handle_squash_target(KeyCode::Enter, &repo);
+        assert_eq!(app.mode, Mode::RewordEdit);
+        assert_eq!(app.reword_buffer, "add parser\n\nfix typo");
+
+        app.reword_buffer = "add parser".into();
+        app.handle_reword_edit(KeyCode::Esc, &repo);
+        assert_eq!(app.mode, Mode::CommitList);
+        assert!(matches!(
+            &app.entries[app.cursor].action,
+            RebaseAction::Squash { into_idx: 1, message: Some(m) } if m == "add parser"
+        ));
+    }
+
+    #[test]
+    fn test_fixup_targets_older_commit() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let mut app = App::new(vec![commit(1, "fix typo"), commit(2, "add parser")], None);
+
+        app.handle_action_menu(KeyCode::Char('f'), &repo);
+        assert!(matches!(app.entries[0].action, RebaseAction::Fixup { into_idx: 1 }));
+        assert_eq!(app.status, "fixup 01 into 02");
+
+        app.cursor = 1;
+        app.handle_action_menu(KeyCode::Char('f'), &repo);
+        assert!(matches!(app.entries[1].action, RebaseAction::Pick));
+        assert_eq!(app.status, "cannot fixup oldest commit");
+    }
+
+    #[test]
+    fn test_load_plan_reorders_selection() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let mut app = App::new(vec![commit(1, "c"), commit(2, "b"), commit(3, "a")], None);
+        app.selected[0] = true;
+
+        app.load_plan(vec![TodoEntry::pick(2), TodoEntry::pick(0), TodoEntry::pick(1)], &repo);
+        let ids: Vec<&str> = app.commits.iter().map(|c| c.short_id.as_str()).collect();
+        assert_eq!(ids, vec!["03", "01", "02"]);
+        assert_eq!(app.selected, vec![false, true, false]);
+    }
+
+    #[test]
+    fn test_filter_hides_rows_and_clamps_cursor() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let mut app = App::new(
+            vec![commit(1, "fix parser"), commit(2, "add docs"), commit(3, "parser tests")],
+            None,
+        );
+        app.cursor = 1;
+        app.handle_commit_list(KeyCode::Char('/'), &repo);
+        for c in "parser".chars() {
+            app.handle_filter_input(KeyCode::Char(c));
+        }
+        assert_eq!(app.visible_rows(), vec![0, 2]);
+        assert_eq!(app.cursor, 2, "hidden cursor moves to the next match");
+
+        app.handle_filter_input(KeyCode::Enter);
+        assert!(!app.filter_editing);
+        app.move_cursor(-1);
+        assert_eq!(app.cursor, 0, "skips the hidden row");
+        app.move_cursor(-1);
+        assert_eq!(app.cursor, 0);
+
+        // no matches: actions are refused
+        app.filter = "zzz".into();
+        app.clamp_cursor();
+        app.handle_commit_list(KeyCode::Enter, &repo);
+        assert_eq!(app.mode, Mode::CommitList);
+
+        app.handle_commit_list(KeyCode::Esc, &repo);
+        assert!(app.filter.is_empty());
+        assert!(!app.should_quit);
+        assert_eq!(app.visible_rows().len(), 3);
+    }
+
+    #[test]
+    fn test_split_blocks_on_unassigned_hunks() {
+        let mut app = App::new(vec![commit(1, "big change")], None);
+        app.mode = Mode::SplitView;
+        app.hunk_groups = vec![1, 0, 2];
+
+        app.handle_split_view(KeyCode::Enter);
+        assert_eq!(app.mode, Mode::SplitView);
+        assert!(app.status.starts_with("1 hunk(s) unassigned"));
+        assert!(matches!(app.entries[0].action, RebaseAction::Pick));
+
+        app.handle_split_view(KeyCode::Char('!'));
+        assert_eq!(app.mode, Mode::CommitList);
+        let RebaseAction::Split { groups } = &app.entries[0].action else {
+            panic!("expected split");
+        };
+        assert_eq!(groups.len(), 2);
+        assert!(app.status.ends_with("1 hunk(s) discarded"));
+    }
+
+    #[test]
+    fn test_mode_keys_and_help_toggle() {
+        let mut app = App::new(Vec::new(), None);
+        assert!(app.mode_keys().contains(&KeyHelp::new("p", "preview")));
+        assert!(app.mode_keys().contains(&KeyHelp::new("?", "help")));
+
+        app.mode = Mode::ReorderMode;
+        assert!(app.mode_keys().contains(&KeyHelp::new("J/K", "move")));
+
+        // text fields keep `?` as a character
+        app.mode = Mode::SplitView;
+        app.split_editing_msg = true;
+        assert!(!app.mode_keys().iter().any(|k| k.key == "?"));
+        assert!(app.is_text_input());
     }
 }
@@ -0,0 +1,88 @@
+use thiserror::Error;
+
+/// Failures callers may want to tell apart. Functions still return
+/// `anyhow::Result`; `err.downcast_ref::<RepoError>()` recovers the variant,
+/// including through added context.
+#[derive(Debug, Error)]
+pub enum RepoError {
+    #[error("Not a git repository (or any parent)")]
+    NotARepo,
+    #[error("Nothing to commit. Working tree clean.")]
+    NothingToCommit,
+    #[error("dirty working tree — commit or stash changes first")]
+    DirtyTree,
+    #[error("bare repository — no working tree")]
+    BareRepo,
+    /// Holds the refused action, e.g. `"reword"`
+    #[error("detached HEAD — cannot {0}")]
+    DetachedHead(&'static str),
+    #[error(
+        "No AI provider available. Options:
+   · install the claude, codex, or gemini CLI
+   · pass --ai <claude|codex|gemini>
+   · set default_ai in ~/.config/repo/config.toml"
+    )]
+    AiUnavailable,
+    #[error("{0}")]
+    Network(String),
+    #[error("rebase aborted with unresolved conflicts")]
+    RebaseConflict,
+}
+
+impl RepoError {
+    /// Process exit code for this failure; see [`EXIT_CODES`]
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::NothingToCommit => 2,
+            Self::DirtyTree => 3,
+            Self::AiUnavailable => 4,
+            Self::Network(_) => 5,
+            Self::RebaseConflict => 6,
+            Self::NotARepo | Self::BareRepo | Self::DetachedHead(_) => 1,
+        }
+    }
+}
+
+/// Exit codes as listed in `repo --help`
+pub const EXIT_CODES: &str = "Exit codes:
+  0  success
+  1  any other error
+  2  nothing to commit
+  3  dirty working tree
+  4  no AI provider available
+  5  network or GitHub API error
+  6  rebase stopped on conflicts";
+
+/// The [`RepoError`] behind `err`, if any
+pub fn repo_error(err: &anyhow::Error) -> Option<&RepoError> {
+    err.downcast_ref()
+}
+
+/// Exit code for `err`: its [`RepoError`] category, else 1
+pub fn exit_code(err: &anyhow::Error) -> i32 {
+    repo_error(err).map_or(1, RepoError::exit_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_repo_error_survives_context() {
+        let dir = TempDir::new().unwrap();
+        let err = crate::git::open_repo(Some(dir.path())).err().unwrap();
+        assert!(matches!(repo_error(&err), Some(RepoError::NotARepo)));
+
+        let err = Err::<(), _>(RepoError::DetachedHead("sync"))
+            .context("sync failed")
+            .unwrap_err();
+        assert!(matches!(repo_error(&err), Some(RepoError::DetachedHead("sync"))));
+        assert_eq!(format!("{:#}", err), "sync failed: detached HEAD — cannot sync");
+        assert!(repo_error(&anyhow::anyhow!("other")).is_none());
+        assert_eq!(exit_code(&anyhow::anyhow!("other")), 1);
+        assert_eq!(exit_code(&RepoError::DirtyTree.into()), 3);
+        assert_eq!(exit_code(&anyhow::Error::new(RepoError::RebaseConflict).context("craft")), 6);
+    }
+}
@@ -1,9 +1,5 @@
 use anyhow::Result;
-use crossterm::{
-    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
 use ratatui::{
     backend::CrosstermBackend,
     layout::{Constraint, Direction, Layout, Rect},
@@ -18,6 +14,7 @@ use git2::Repository;
 
 use crate::config::Config;
 use crate::models::RepoSummary;
+use crate::terminal::TuiGuard;
 
 use super::ai::AiState;
 use super::branches::tree::build_branch_tree;
@@ -56,6 +53,7 @@ pub struct ExploreApp {
     pub status_message: String,
     pub repo_path: String,
     pub highlighter: Highlighter,
+    pub date_mode: crate::models::TimeMode,
 }
 
 impl ExploreApp {
@@ -98,6 +96,7 @@ impl ExploreApp {
             status_message: String::new(),
             repo_path,
             highlighter: Highlighter::new(),
+            date_mode: config.date_format,
         })
     }
 }
@@ -114,16 +113,9 @@ pub fn run_explore_tui(
     // Pre-load diff for first commit
     load_commit_data(&mut app, &repo);
 
-    enable_raw_mode()?;
-    execute!(stdout(), EnterAlternateScreen)?;
-
+    let _guard = TuiGuard::enter()?;
     let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
-    let result = run_event_loop(&mut terminal, &mut app, &repo);
-
-    disable_raw_mode()?;
-    execute!(stdout(), LeaveAlternateScreen)?;
-
-    result
+    run_event_loop(&mut terminal, &mut app, &repo)
 }
 
 fn run_event_loop(
@@ -1102,7 +1094,10 @@ fn render_summary_tab(f: &mut Frame, app: &ExploreApp, area: Rect) {
         ]),
         Line::from(vec![
             Span::styled("  commits  ", label_style),
-            Span::styled(format!("{}", s.total_commits), value_style),
+            Span::styled(
+                format!("{}{}", s.total_commits, if s.commits_capped { "+" } else { "" }),
+                value_style,
+            ),
         ]),
         Line::from(vec![
             Span::styled("  branches ", label_style),
@@ -1202,7 +1197,7 @@ fn render_commit_list(f: &mut Frame, app: &ExploreApp, area: Rect) {
             } else {
                 String::new()
             };
-            let time = crate::models::format_relative_time(&commit.time);
+            let time = crate::models::format_time(&commit.time, app.date_mode);
             let overhead = hash.len() + time.len() + 5;
             let msg_width = (area.width as usize).saturating_sub(overhead);
             let msg = super::layout::truncate(&commit.message, msg_width);
@@ -1767,7 +1762,7 @@ fn build_branch_info(app: &ExploreApp) -> String {
 
         let activity = node
             .last_activity
-            .map(|t| crate::models::format_relative_time(&t))
+            .map(|t| crate::models::format_time(&t, app.date_mode))
             .unwrap_or_else(|| "unknown".to_string());
 
         let status = if node.branch.is_head {
@@ -1,7 +1,9 @@
-use anyhow::{Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use chrono::{Local, TimeZone};
 use git2::{BranchType, Repository};
 
+use super::status::is_dirty;
+use crate::error::RepoError;
 use crate::models::{BranchInfo, RemoteBranchInfo, UpstreamInfo};
 
 pub fn get_current_branch(repo: &Repository) -> Result<BranchInfo> {
@@ -45,6 +47,36 @@ fn get_upstream_info(repo: &Repository, branch_name: &str) -> Option<UpstreamInf
     })
 }
 
+/// Full ref name of HEAD's upstream (`branch.upstream()`), falling back to
+/// `refs/remotes/origin/<branch>` only when no upstream is configured
+pub fn get_upstream_ref(repo: &Repository) -> Option<String> {
+    let head = repo.head().ok()?;
+    if !head.is_branch() {
+        return None;
+    }
+    let branch_name = head.shorthand()?;
+    let branch = repo.find_branch(branch_name, BranchType::Local).ok()?;
+
+    if let Ok(upstream) = branch.upstream() {
+        return upstream.get().name().map(|n| n.to_string());
+    }
+
+    let fallback = format!("refs/remotes/origin/{}", branch_name);
+    repo.find_reference(&fallback).ok().map(|_| fallback)
+}
+
+/// Whether HEAD's upstream already contains `oid`, i.e. rewriting it would
+/// need a force-push
+pub fn is_pushed(repo: &Repository, oid: git2::Oid) -> bool {
+    let Some(upstream) = get_upstream_ref(repo)
+        .and_then(|r| repo.find_reference(&r).ok())
+        .and_then(|r| r.target())
+    else {
+        return false;
+    };
+    upstream == oid || repo.graph_descendant_of(upstream, oid).unwrap_or(false)
+}
+
 pub fn get_local_branches(repo: &Repository) -> Result<Vec<BranchInfo>> {
     let mut branches = Vec::new();
     let head_ref = repo.head().ok();
@@ -129,3 +161,221 @@ pub fn get_remote_branches(repo: &Repository) -> Result<Vec<RemoteBranchInfo>> {
 
     Ok(branches)
 }
+
+/// Create `name` at HEAD without switching to it
+pub fn create_branch(repo: &Repository, name: &str) -> Result<()> {
+    let head = repo.head()?.peel_to_commit().context("No commits yet")?;
+    repo.branch(name, &head, false)
+        .map_err(|e| anyhow!("Failed to create branch '{}': {}", name, e.message()))?;
+    Ok(())
+}
+
+/// Commits on `name` that its upstream (or HEAD, if it has none) lacks,
+/// with the ref they were compared against
+pub fn unmerged_commits(repo: &Repository, name: &str) -> Result<(usize, String)> {
+    let branch = repo
+        .find_branch(name, BranchType::Local)
+        .ok()
+        .with_context(|| format!("No branch named '{}'", name))?;
+    let tip = branch.get().target().context("Branch has no target")?;
+    let (base, base_name) = match branch.upstream() {
+        Ok(upstream) => (
+            upstream.get().target().context("Upstream has no target")?,
+            upstream.name()?.unwrap_or("upstream").to_string(),
+        ),
+        Err(_) => (
+            repo.head()?.target().context("HEAD has no target")?,
+            repo.head()?.shorthand().unwrap_or("HEAD").to_string(),
+        ),
+    };
+    let (ahead, _) = repo.graph_ahead_behind(tip, base)?;
+    Ok((ahead, base_name))
+}
+
+/// Delete a local branch. Refuses the current branch, and without `force`,
+/// a branch with commits not yet in its upstream or HEAD.
+pub fn delete_branch(repo: &Repository, name: &str, force: bool) -> Result<()> {
+    let mut branch = repo
+        .find_branch(name, BranchType::Local)
+        .ok()
+        .with_context(|| format!("No branch named '{}'", name))?;
+    if branch.is_head() {
+        bail!("'{}' is the current branch; switch away before deleting it", name);
+    }
+    if !force {
+        let (ahead, base) = unmerged_commits(repo, name)?;
+        if ahead > 0 {
+            bail!(
+                "'{}' has {} commit(s) not in {} (use --force to delete anyway)",
+                name,
+                ahead,
+                base
+            );
+        }
+    }
+    branch
+        .delete()
+        .map_err(|e| anyhow!("Failed to delete '{}': {}", name, e.message()))?;
+    Ok(())
+}
+
+/// Rename a local branch; `force` overwrites an existing `new`
+pub fn rename_branch(repo: &Repository, old: &str, new: &str, force: bool) -> Result<()> {
+    let mut branch = repo
+        .find_branch(old, BranchType::Local)
+        .ok()
+        .with_context(|| format!("No branch named '{}'", old))?;
+    branch
+        .rename(new, force)
+        .map_err(|e| anyhow!("Failed to rename '{}' to '{}': {}", old, new, e.message()))?;
+    Ok(())
+}
+
+/// Check out `name` and point HEAD at it, creating it from HEAD first with
+/// `create`. Refuses a dirty tree (untracked files included) unless `force`,
+/// which discards changes to tracked files. Untracked files stay, except
+/// where the target branch has a file at the same path.
+pub fn switch_branch(repo: &Repository, name: &str, create: bool, force: bool) -> Result<()> {
+    if !force && is_dirty(repo)? {
+        bail!(RepoError::DirtyTree);
+    }
+    if create {
+        create_branch(repo, name)?;
+    }
+
+    let branch = repo
+        .find_branch(name, BranchType::Local)
+        .ok()
+        .with_context(|| format!("No branch named '{}'", name))?;
+    let refname = branch.get().name().context("Branch name is not valid UTF-8")?.to_string();
+    let commit = branch.get().peel_to_commit()?;
+
+    let mut checkout = git2::build::CheckoutBuilder::new();
+    if force {
+        checkout.force();
+    } else {
+        checkout.safe();
+    }
+    repo.checkout_tree(commit.as_object(), Some(&mut checkout))
+        .map_err(|e| anyhow!("Failed to check out '{}': {}", name, e.message()))?;
+    repo.set_head(&refname)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn init_repo_with_commit() -> (TempDir, Repository) {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        {
+            let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+            let tree_id = repo.index().unwrap().write_tree().unwrap();
+            let tree = repo.find_tree(tree_id).unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, "init", &tree, &[]).unwrap();
+        }
+        (dir, repo)
+    }
+
+    fn add_remote_branch(repo: &Repository, remote: &str, branch: &str) {
+        repo.remote(remote, "https://example.com/x.git").unwrap();
+        let head = repo.head().unwrap().target().unwrap();
+        let name = format!("refs/remotes/{}/{}", remote, branch);
+        repo.reference(&name, head, true, "test").unwrap();
+    }
+
+    #[test]
+    fn test_upstream_ref_prefers_configured_upstream() {
+        let (_dir, repo) = init_repo_with_commit();
+        let branch = repo.head().unwrap().shorthand().unwrap().to_string();
+        add_remote_branch(&repo, "origin", &branch);
+        add_remote_branch(&repo, "fork", &branch);
+
+        let mut local = repo.find_branch(&branch, BranchType::Local).unwrap();
+        local.set_upstream(Some(&format!("fork/{}", branch))).unwrap();
+
+        assert_eq!(
+            get_upstream_ref(&repo),
+            Some(format!("refs/remotes/fork/{}", branch))
+        );
+    }
+
+    #[test]
+    fn test_upstream_ref_falls_back_to_origin() {
+        let (_dir, repo) = init_repo_with_commit();
+        assert_eq!(get_upstream_ref(&repo), None);
+
+        let branch = repo.head().unwrap().shorthand().unwrap().to_string();
+        add_remote_branch(&repo, "origin", &branch);
+        assert_eq!(
+            get_upstream_ref(&repo),
+            Some(format!("refs/remotes/origin/{}", branch))
+        );
+    }
+
+    fn commit_on(repo: &Repository, refname: &str, msg: &str) -> git2::Oid {
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        let parent = repo.find_reference(refname).unwrap().peel_to_commit().unwrap();
+        let tree = parent.tree().unwrap();
+        repo.commit(Some(refname), &sig, &sig, msg, &tree, &[&parent]).unwrap()
+    }
+
+    #[test]
+    fn test_is_pushed() {
+        let (_dir, repo) = init_repo_with_commit();
+        let branch = repo.head().unwrap().shorthand().unwrap().to_string();
+        let base = repo.head().unwrap().target().unwrap();
+        assert!(!is_pushed(&repo, base));
+
+        add_remote_branch(&repo, "origin", &branch);
+        assert!(is_pushed(&repo, base));
+        let local = commit_on(&repo, &format!("refs/heads/{}", branch), "local only");
+        assert!(!is_pushed(&repo, local));
+        assert!(is_pushed(&repo, base));
+    }
+
+    #[test]
+    fn test_branch_create_rename_delete() {
+        let (_dir, repo) = init_repo_with_commit();
+        let current = repo.head().unwrap().shorthand().unwrap().to_string();
+
+        create_branch(&repo, "feature").unwrap();
+        assert!(create_branch(&repo, "feature").is_err());
+        rename_branch(&repo, "feature", "topic", false).unwrap();
+        assert!(repo.find_branch("feature", BranchType::Local).is_err());
+
+        assert!(delete_branch(&repo, &current, true).is_err());
+
+        commit_on(&repo, "refs/heads/topic", "unmerged work");
+        assert_eq!(unmerged_commits(&repo, "topic").unwrap(), (1, current.clone()));
+        let err = delete_branch(&repo, "topic", false).unwrap_err().to_string();
+        assert!(err.contains("1 commit(s) not in"), "{}", err);
+        delete_branch(&repo, "topic", true).unwrap();
+
+        create_branch(&repo, "merged").unwrap();
+        delete_branch(&repo, "merged", false).unwrap();
+        assert!(repo.find_branch("merged", BranchType::Local).is_err());
+    }
+
+    #[test]
+    fn test_switch_branch() {
+        let (dir, repo) = init_repo_with_commit();
+        let start = repo.head().unwrap().shorthand().unwrap().to_string();
+
+        switch_branch(&repo, "feature", true, false).unwrap();
+        assert_eq!(repo.head().unwrap().shorthand(), Some("feature"));
+        assert!(switch_branch(&repo, "missing", false, false).is_err());
+
+        std::fs::write(dir.path().join("wip.txt"), "wip").unwrap();
+        let err = switch_branch(&repo, &start, false, false).unwrap_err().to_string();
+        assert!(err.contains("dirty working tree"), "{}", err);
+        assert_eq!(repo.head().unwrap().shorthand(), Some("feature"));
+
+        switch_branch(&repo, &start, false, true).unwrap();
+        assert_eq!(repo.head().unwrap().shorthand(), Some(start.as_str()));
+        // --force leaves untracked files alone
+        assert!(dir.path().join("wip.txt").exists());
+    }
+}
@@ -1,5 +1,10 @@
 use anyhow::{bail, Context, Result};
-use git2::Repository;
+use git2::{Repository, RepositoryState, ResetType};
+
+use super::commits::commit_info;
+use super::diff::has_staged_changes;
+use crate::error::RepoError;
+use crate::models::CommitInfo;
 
 /// Create a commit with the given message
 pub fn create_commit(repo: &Repository, message: &str) -> Result<git2::Oid> {
@@ -68,3 +73,196 @@ pub fn get_author_info(repo: &Repository) -> Result<(String, String)> {
     let email = sig.email().unwrap_or("").to_string();
     Ok((name, email))
 }
+
+const SCISSORS: &str = " ------------------------ >8 ------------------------";
+
+/// `commit.cleanup` modes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CleanupMode {
+    /// `strip` when the message was edited, `whitespace` otherwise (git's default)
+    Default,
+    Strip,
+    Whitespace,
+    Verbatim,
+    Scissors,
+}
+
+impl CleanupMode {
+    fn parse(value: &str) -> Self {
+        match value {
+            "strip" => Self::Strip,
+            "whitespace" => Self::Whitespace,
+            "verbatim" => Self::Verbatim,
+            "scissors" => Self::Scissors,
+            _ => Self::Default,
+        }
+    }
+}
+
+/// Read `commit.cleanup` and `core.commentChar` from git config
+pub fn get_cleanup_config(repo: &Repository) -> (CleanupMode, char) {
+    let config = repo.config().ok();
+    let mode = config
+        .as_ref()
+        .and_then(|c| c.get_string("commit.cleanup").ok())
+        .map(|v| CleanupMode::parse(&v))
+        .unwrap_or(CleanupMode::Default);
+    let comment = config
+        .as_ref()
+        .and_then(|c| c.get_string("core.commentChar").ok())
+        .and_then(|v| v.chars().next())
+        .filter(|c| *c != 'a') // `auto` picks per message; keep git's default
+        .unwrap_or('#');
+    (mode, comment)
+}
+
+/// Normalize a message the way `git commit` does for the given cleanup mode.
+/// `edited` is whether it came through an editor, which makes the default
+/// mode strip comments.
+pub fn cleanup_message(message: &str, mode: CleanupMode, comment: char, edited: bool) -> String {
+    let scissors = format!("{}{}", comment, SCISSORS);
+    match mode {
+        CleanupMode::Verbatim => message.to_string(),
+        CleanupMode::Whitespace => stripspace(message, None),
+        CleanupMode::Default if !edited => stripspace(message, None),
+        CleanupMode::Default | CleanupMode::Strip => stripspace(message, Some(comment)),
+        CleanupMode::Scissors => {
+            let kept = match message.lines().position(|l| l == scissors) {
+                Some(idx) => message.lines().take(idx).collect::<Vec<_>>().join("\n"),
+                None => message.to_string(),
+            };
+            stripspace(&kept, None)
+        }
+    }
+}
+
+/// Trim trailing whitespace, collapse blank lines and drop leading/trailing
+/// ones; with `comment`, also drop lines starting with it
+fn stripspace(message: &str, comment: Option<char>) -> String {
+    let mut lines: Vec<&str> = Vec::new();
+    for line in message.lines() {
+        if comment.is_some_and(|c| line.starts_with(c)) {
+            continue;
+        }
+        let line = line.trim_end();
+        if line.is_empty() && lines.last().is_none_or(|l| l.is_empty()) {
+            continue;
+        }
+        lines.push(line);
+    }
+    while lines.last() == Some(&"") {
+        lines.pop();
+    }
+    lines.join("\n")
+}
+
+/// `git reset --soft HEAD~count`: move the branch back, keeping the changes
+/// staged. Returns the removed commits, newest first.
+pub fn uncommit(repo: &Repository, count: usize) -> Result<Vec<CommitInfo>> {
+    if count == 0 {
+        bail!("nothing to uncommit (count is 0)");
+    }
+    if repo.head_detached()? {
+        bail!(RepoError::DetachedHead("uncommit"));
+    }
+    if repo.state() != RepositoryState::Clean {
+        bail!("a merge, rebase or similar is in progress — finish or abort it first");
+    }
+    // unstaged edits are untouched by a soft reset, but staged ones would blend in
+    if has_staged_changes(repo)? {
+        bail!("staged changes would mix with the uncommitted ones — commit or stash first");
+    }
+
+    let mut commit = repo.head()?.peel_to_commit().context("no commits yet")?;
+    let mut removed = Vec::new();
+    for _ in 0..count {
+        removed.push(commit_info(repo, commit.id())?);
+        commit = match commit.parent(0) {
+            Ok(parent) => parent,
+            Err(_) => bail!(
+                "only {} commit(s) on this branch — can't uncommit {}",
+                removed.len(),
+                count
+            ),
+        };
+    }
+
+    repo.reset(commit.as_object(), ResetType::Soft, None)?;
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, init_repo};
+    use std::fs;
+    use std::path::Path;
+
+    #[test]
+    fn test_uncommit_keeps_changes_staged() {
+        let (dir, repo) = init_repo();
+        let first = commit_file(&repo, "a.txt", "first", "first");
+        commit_file(&repo, "b.txt", "second", "second");
+        commit_file(&repo, "c.txt", "third", "third");
+
+        assert!(uncommit(&repo, 3).is_err());
+        assert!(uncommit(&repo, 0).is_err());
+
+        fs::write(dir.path().join("c.txt"), "edited").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("c.txt")).unwrap();
+        index.write().unwrap();
+        assert!(uncommit(&repo, 1).is_err());
+        fs::write(dir.path().join("c.txt"), "third").unwrap();
+        index.add_path(Path::new("c.txt")).unwrap();
+        index.write().unwrap();
+
+        let removed = uncommit(&repo, 2).unwrap();
+        let subjects: Vec<&str> = removed.iter().map(|c| c.message.as_str()).collect();
+        assert_eq!(subjects, vec!["third", "second"]);
+        assert_eq!(repo.head().unwrap().target(), Some(first));
+
+        let staged = crate::git::get_staged_files(&repo).unwrap();
+        assert_eq!(staged, vec!["b.txt", "c.txt"]);
+    }
+
+    const MSG: &str = "\n\nfeat: add x  \n\n\n# a comment\nbody line\t\n\n";
+
+    #[test]
+    fn test_cleanup_modes() {
+        assert_eq!(cleanup_message(MSG, CleanupMode::Verbatim, '#', true), MSG);
+        assert_eq!(
+            cleanup_message(MSG, CleanupMode::Whitespace, '#', true),
+            "feat: add x\n\n# a comment\nbody line"
+        );
+        assert_eq!(
+            cleanup_message(MSG, CleanupMode::Strip, '#', false),
+            "feat: add x\n\nbody line"
+        );
+        assert_eq!(
+            cleanup_message("feat: x\n; note", CleanupMode::Strip, ';', false),
+            "feat: x"
+        );
+    }
+
+    #[test]
+    fn test_cleanup_default_depends_on_editor() {
+        assert_eq!(
+            cleanup_message(MSG, CleanupMode::Default, '#', true),
+            "feat: add x\n\nbody line"
+        );
+        assert_eq!(
+            cleanup_message(MSG, CleanupMode::Default, '#', false),
+            "feat: add x\n\n# a comment\nbody line"
+        );
+    }
+
+    #[test]
+    fn test_cleanup_scissors() {
+        let msg = format!("fix: y\n\n# kept\n#{}\ndiff --git a/x b/x\n", SCISSORS);
+        assert_eq!(
+            cleanup_message(&msg, CleanupMode::Scissors, '#', true),
+            "fix: y\n\n# kept"
+        );
+    }
+}
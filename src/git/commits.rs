@@ -1,9 +1,9 @@
-use anyhow::Result;
-use chrono::{Local, TimeZone};
-use git2::{BranchType, Repository};
-use std::collections::HashSet;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, TimeZone};
+use git2::{BranchType, DescribeFormatOptions, DescribeOptions, Oid, Repository};
+use std::collections::HashMap;
 
-use crate::models::{BranchCommitCount, CommitInfo};
+use crate::models::{BranchCommitCount, CommitCounts, CommitInfo};
 
 pub fn get_recent_commits(repo: &Repository, limit: usize) -> Result<Vec<CommitInfo>> {
     let mut commits = Vec::new();
@@ -35,63 +35,144 @@ pub fn get_recent_commits(repo: &Repository, limit: usize) -> Result<Vec<CommitI
         }
 
         let oid = oid_result?;
-        let commit = repo.find_commit(oid)?;
-
-        let time = commit.time();
-        let dt = Local
-            .timestamp_opt(time.seconds(), 0)
-            .single()
-            .unwrap_or_else(Local::now);
-
-        let parents: Vec<_> = commit.parent_ids().collect();
-
-        commits.push(CommitInfo {
-            id: oid,
-            short_id: oid.to_string()[..7].to_string(),
-            message: commit.summary().unwrap_or("").to_string(),
-            author: commit.author().name().unwrap_or("").to_string(),
-            time: dt,
-            parents,
-        });
+        commits.push(commit_info(repo, oid)?);
     }
 
     Ok(commits)
 }
 
+pub(super) fn commit_info(repo: &Repository, oid: Oid) -> Result<CommitInfo> {
+    let commit = repo.find_commit(oid)?;
+
+    let time = commit.time();
+    let dt = Local
+        .timestamp_opt(time.seconds(), 0)
+        .single()
+        .unwrap_or_else(Local::now);
+
+    let parents: Vec<_> = commit.parent_ids().collect();
+    let author = commit.author().name().unwrap_or("").to_string();
+
+    Ok(CommitInfo {
+        id: oid,
+        short_id: oid.to_string()[..7].to_string(),
+        message: commit.summary().unwrap_or("").to_string(),
+        author,
+        time: dt,
+        parents,
+    })
+}
+
+/// Most recent tag reachable from HEAD (like `git describe --tags --abbrev=0`)
+pub fn get_nearest_tag(repo: &Repository) -> Result<Option<String>> {
+    let mut opts = DescribeOptions::new();
+    opts.describe_tags();
+    let describe = match repo.describe(&opts) {
+        Ok(d) => d,
+        // No tags reachable from HEAD
+        Err(e) if e.class() == git2::ErrorClass::Describe => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let mut format = DescribeFormatOptions::new();
+    format.abbreviated_size(0);
+    Ok(Some(describe.format(Some(&format))?))
+}
+
+/// Commits in `from..HEAD`, newest first (`from = None` walks all of HEAD)
+pub fn get_commits_since(
+    repo: &Repository,
+    from: Option<&str>,
+    limit: Option<usize>,
+) -> Result<Vec<CommitInfo>> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)?;
+    revwalk.push_head()?;
+    if let Some(rev) = from {
+        let base = repo
+            .revparse_single(rev)
+            .with_context(|| format!("unknown revision: {}", rev))?
+            .peel_to_commit()?;
+        revwalk.hide(base.id())?;
+    }
+
+    revwalk
+        .take(limit.unwrap_or(usize::MAX))
+        .map(|oid| commit_info(repo, oid?))
+        .collect()
+}
+
 pub fn get_total_commit_count(repo: &Repository) -> Result<usize> {
-    let mut seen = HashSet::new();
+    Ok(count_commits(repo, None, None)?.total)
+}
+
+/// Count unique commits reachable from local and remote branches, stopping
+/// after `cap` (the walk is O(all commits)). With `email`, also count the
+/// ones authored by it.
+pub fn count_commits(repo: &Repository, cap: Option<usize>, email: Option<&str>) -> Result<CommitCounts> {
     let mut revwalk = repo.revwalk()?;
-    revwalk.set_sorting(git2::Sort::TIME)?;
 
-    // Push all local branches
-    for branch in repo.branches(Some(BranchType::Local))? {
-        if let Ok((branch, _)) = branch {
+    for kind in [BranchType::Local, BranchType::Remote] {
+        for (branch, _) in repo.branches(Some(kind))?.flatten() {
             if let Some(oid) = branch.get().target() {
                 let _ = revwalk.push(oid);
             }
         }
     }
 
-    // Push all remote branches
-    for branch in repo.branches(Some(BranchType::Remote))? {
-        if let Ok((branch, _)) = branch {
-            if let Some(oid) = branch.get().target() {
-                let _ = revwalk.push(oid);
+    let mut counts = CommitCounts::default();
+    for oid in revwalk.flatten() {
+        if cap.is_some_and(|c| counts.total >= c) {
+            counts.capped = true;
+            break;
+        }
+        counts.total += 1;
+        if let Some(email) = email {
+            let mine = repo
+                .find_commit(oid)
+                .ok()
+                .and_then(|c| c.author().email().map(|e| e.eq_ignore_ascii_case(email)))
+                .unwrap_or(false);
+            if mine {
+                counts.mine += 1;
             }
         }
     }
 
-    // Count unique commits
-    for oid_result in revwalk {
-        if let Ok(oid) = oid_result {
-            seen.insert(oid);
+    Ok(counts)
+}
+
+/// Commits per author (`Name <email>`) reachable from HEAD, most first, like
+/// `git shortlog -sn`. `since` skips older commits.
+pub fn get_shortlog(repo: &Repository, since: Option<DateTime<Local>>) -> Result<Vec<(String, usize)>> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push_head().context("no commits yet")?;
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        if let Some(cutoff) = since {
+            let time = Local.timestamp_opt(commit.time().seconds(), 0).single();
+            if time.is_some_and(|t| t < cutoff) {
+                continue;
+            }
         }
+        let author = commit.author();
+        let key = format!(
+            "{} <{}>",
+            author.name().unwrap_or("unknown"),
+            author.email().unwrap_or("")
+        );
+        *counts.entry(key).or_default() += 1;
     }
 
-    Ok(seen.len())
+    let mut authors: Vec<(String, usize)> = counts.into_iter().collect();
+    authors.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    Ok(authors)
 }
 
-pub fn get_branch_commit_counts(repo: &Repository) -> Result<Vec<BranchCommitCount>> {
+/// Commits reachable from each local branch, most first. Each walk stops
+/// after `cap` commits, like [`count_commits`].
+pub fn get_branch_commit_counts(repo: &Repository, cap: Option<usize>) -> Result<Vec<BranchCommitCount>> {
     let mut branch_counts = Vec::new();
 
     // Get local branches only
@@ -99,10 +180,11 @@ pub fn get_branch_commit_counts(repo: &Repository) -> Result<Vec<BranchCommitCou
         if let Ok((branch, _)) = branch_result {
             if let Some(name) = branch.name()? {
                 if let Some(oid) = branch.get().target() {
-                    let count = count_commits_from(repo, oid)?;
+                    let (count, capped) = count_commits_from(repo, oid, cap)?;
                     branch_counts.push(BranchCommitCount {
                         name: name.to_string(),
                         count,
+                        capped,
                     });
                 }
             }
@@ -117,16 +199,96 @@ pub fn get_branch_commit_counts(repo: &Repository) -> Result<Vec<BranchCommitCou
     Ok(branch_counts)
 }
 
-fn count_commits_from(repo: &Repository, oid: git2::Oid) -> Result<usize> {
-    let mut seen = HashSet::new();
+/// Commits reachable from `oid`, and whether the walk stopped at `cap`.
+/// The revwalk never yields a commit twice, so no seen-set is needed.
+fn count_commits_from(repo: &Repository, oid: git2::Oid, cap: Option<usize>) -> Result<(usize, bool)> {
     let mut revwalk = repo.revwalk()?;
     revwalk.push(oid)?;
 
-    for oid_result in revwalk {
-        if let Ok(oid) = oid_result {
-            seen.insert(oid);
+    let limit = cap.unwrap_or(usize::MAX);
+    let count = revwalk.flatten().take(limit.saturating_add(1)).count();
+    Ok((count.min(limit), count > limit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, init_repo};
+
+    #[test]
+    fn test_count_commits_mine_and_cap() {
+        let (_dir, repo) = init_repo();
+        for name in ["a", "b", "c"] {
+            commit_file(&repo, name, name, name);
         }
+
+        let counts = count_commits(&repo, None, Some("TEST@example.com")).unwrap();
+        assert_eq!(counts, CommitCounts { total: 3, mine: 3, capped: false });
+        assert_eq!(count_commits(&repo, None, Some("other@example.com")).unwrap().mine, 0);
+
+        let capped = count_commits(&repo, Some(2), None).unwrap();
+        assert_eq!(capped, CommitCounts { total: 2, mine: 0, capped: true });
+        assert!(!count_commits(&repo, Some(3), None).unwrap().capped);
+
+        let branches = get_branch_commit_counts(&repo, Some(2)).unwrap();
+        assert_eq!((branches[0].count, branches[0].capped), (2, true));
+        let branches = get_branch_commit_counts(&repo, None).unwrap();
+        assert_eq!((branches[0].count, branches[0].capped), (3, false));
     }
 
-    Ok(seen.len())
+    #[test]
+    fn test_shortlog_counts_and_since() {
+        let (_dir, repo) = init_repo();
+        commit_file(&repo, "a", "a", "a");
+        commit_file(&repo, "b", "b", "b");
+
+        // an older commit by someone else
+        let mut index = repo.index().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let old = git2::Signature::new("Old", "old@example.com", &git2::Time::new(1_000_000_000, 0)).unwrap();
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.commit(Some("HEAD"), &old, &old, "old", &tree, &[&head]).unwrap();
+
+        assert_eq!(
+            get_shortlog(&repo, None).unwrap(),
+            vec![
+                ("Test <test@example.com>".to_string(), 2),
+                ("Old <old@example.com>".to_string(), 1),
+            ]
+        );
+        let recent = get_shortlog(&repo, Some(Local::now() - chrono::Duration::days(1))).unwrap();
+        assert_eq!(recent, vec![("Test <test@example.com>".to_string(), 2)]);
+    }
+
+    fn tag(repo: &Repository, name: &str, oid: Oid) {
+        let obj = repo.find_object(oid, None).unwrap();
+        repo.tag_lightweight(name, &obj, false).unwrap();
+    }
+
+    #[test]
+    fn test_nearest_tag_and_range() {
+        let (_dir, repo) = init_repo();
+
+        let first = commit_file(&repo, "a.txt", "feat: first", "feat: first");
+        assert_eq!(get_nearest_tag(&repo).unwrap(), None);
+
+        tag(&repo, "v0.1.0", first);
+        let second = commit_file(&repo, "b.txt", "fix: second", "fix: second");
+        tag(&repo, "v0.2.0", second);
+        commit_file(&repo, "c.txt", "feat: third", "feat: third");
+        commit_file(&repo, "d.txt", "docs: fourth", "docs: fourth");
+
+        assert_eq!(get_nearest_tag(&repo).unwrap().as_deref(), Some("v0.2.0"));
+
+        let since_latest = get_commits_since(&repo, Some("v0.2.0"), None).unwrap();
+        let msgs: Vec<&str> = since_latest.iter().map(|c| c.message.as_str()).collect();
+        assert_eq!(msgs, vec!["docs: fourth", "feat: third"]);
+
+        let since_pinned = get_commits_since(&repo, Some("v0.1.0"), None).unwrap();
+        assert_eq!(since_pinned.len(), 3);
+
+        assert_eq!(get_commits_since(&repo, None, None).unwrap().len(), 4);
+        assert_eq!(get_commits_since(&repo, None, Some(2)).unwrap().len(), 2);
+        assert!(get_commits_since(&repo, Some("v9.9.9"), None).is_err());
+    }
 }
@@ -0,0 +1,145 @@
+use std::path::Path;
+
+use anyhow::Result;
+use git2::Repository;
+
+use super::rebase::get_conflicted_files;
+
+/// One `<<<<<<<` … `>>>>>>>` block in a conflicted file
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConflictRegion {
+    /// 1-based line of the `<<<<<<<` marker
+    pub line: usize,
+    pub ours_label: String,
+    pub theirs_label: String,
+    pub ours: Vec<String>,
+    pub theirs: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ConflictedFile {
+    pub path: String,
+    pub regions: Vec<ConflictRegion>,
+}
+
+enum Section {
+    Outside,
+    Ours,
+    Base,
+    Theirs,
+}
+
+/// The label after a conflict `marker` (`<<<<<<< HEAD` → `HEAD`), if `line`
+/// is one: the marker alone or followed by a space, not a longer run
+pub fn marker_label<'a>(line: &'a str, marker: &str) -> Option<&'a str> {
+    line.strip_prefix(marker)
+        .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+        .map(str::trim)
+}
+
+/// Extract conflict regions. diff3 base sections (`|||||||`) are skipped.
+pub fn parse_conflict_regions(content: &str) -> Vec<ConflictRegion> {
+    let mut regions = Vec::new();
+    let mut current: Option<ConflictRegion> = None;
+    let mut section = Section::Outside;
+
+    for (i, line) in content.lines().enumerate() {
+        if let Some(label) = marker_label(line, "<<<<<<<") {
+            current = Some(ConflictRegion {
+                line: i + 1,
+                ours_label: label.to_string(),
+                theirs_label: String::new(),
+                ours: Vec::new(),
+                theirs: Vec::new(),
+            });
+            section = Section::Ours;
+            continue;
+        }
+        let Some(region) = current.as_mut() else {
+            continue;
+        };
+        match section {
+            Section::Ours | Section::Base if line.starts_with("=======") => {
+                section = Section::Theirs;
+            }
+            Section::Ours if line.starts_with("|||||||") => section = Section::Base,
+            Section::Theirs if line.starts_with(">>>>>>>") => {
+                region.theirs_label = marker_label(line, ">>>>>>>").unwrap_or("").to_string();
+                regions.extend(current.take());
+                section = Section::Outside;
+            }
+            Section::Ours => region.ours.push(line.to_string()),
+            Section::Theirs => region.theirs.push(line.to_string()),
+            Section::Base | Section::Outside => {}
+        }
+    }
+
+    regions
+}
+
+/// Conflicted files in the index with the marker regions left in the worktree
+pub fn get_conflicts(repo: &Repository) -> Result<Vec<ConflictedFile>> {
+    let workdir = repo.workdir().unwrap_or_else(|| Path::new("."));
+    Ok(get_conflicted_files(repo)?
+        .into_iter()
+        .map(|path| {
+            let content = std::fs::read_to_string(workdir.join(&path)).unwrap_or_default();
+            ConflictedFile {
+                regions: parse_conflict_regions(&content),
+                path,
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, init_repo};
+
+    #[test]
+    fn test_parse_conflict_regions() {
+        let content = "top\n<<<<<<< HEAD\nours\n||||||| base\nold\n=======\ntheirs 1\ntheirs 2\n>>>>>>> feature\nend\n";
+        let regions = parse_conflict_regions(content);
+        assert_eq!(
+            regions,
+            vec![ConflictRegion {
+                line: 2,
+                ours_label: "HEAD".into(),
+                theirs_label: "feature".into(),
+                ours: vec!["ours".into()],
+                theirs: vec!["theirs 1".into(), "theirs 2".into()],
+            }]
+        );
+        assert!(parse_conflict_regions("no markers\n").is_empty());
+        assert_eq!(marker_label("<<<<<<<", "<<<<<<<"), Some(""));
+        assert_eq!(marker_label("<<<<<<<< longer run", "<<<<<<<"), None);
+    }
+
+    #[test]
+    fn test_get_conflicts_from_merge() {
+        let (_dir, repo) = init_repo();
+        let base = commit_file(&repo, "a.txt", "a\nb\nc\n", "base");
+        let head_ref = repo.head().unwrap().name().unwrap().to_string();
+        repo.branch("other", &repo.find_commit(base).unwrap(), false).unwrap();
+        commit_file(&repo, "a.txt", "a\nours\nc\n", "ours");
+
+        repo.set_head("refs/heads/other").unwrap();
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+            .unwrap();
+        let theirs = commit_file(&repo, "a.txt", "a\ntheirs\nc\n", "theirs");
+        repo.set_head(&head_ref).unwrap();
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+            .unwrap();
+
+        let annotated = repo.find_annotated_commit(theirs).unwrap();
+        repo.merge(&[&annotated], None, None).unwrap();
+
+        let conflicts = get_conflicts(&repo).unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].path, "a.txt");
+        assert_eq!(conflicts[0].regions.len(), 1);
+        assert_eq!(conflicts[0].regions[0].ours, vec!["ours"]);
+        assert_eq!(conflicts[0].regions[0].theirs, vec!["theirs"]);
+    }
+}
@@ -1,7 +1,34 @@
-use anyhow::{Context, Result};
+use std::collections::BTreeSet;
+
+use anyhow::{bail, Context, Result};
 use git2::{DiffOptions, IndexAddOption, Repository};
 use globset::GlobSet;
 
+/// Placeholder written in place of libgit2's "Binary files ... differ" line,
+/// so binary changes still show up by name in the diff text
+pub const BINARY_MARKER: &str = "Binary file ";
+
+/// Append one `diff.print` line to `out` with its `+`/`-`/` ` prefix. Binary
+/// deltas become a single `Binary file <path> changed` line.
+fn push_diff_line(out: &mut String, delta: &git2::DiffDelta, line: &git2::DiffLine) {
+    let prefix = match line.origin() {
+        '+' => "+",
+        '-' => "-",
+        ' ' => " ",
+        'B' => {
+            let path = delta.new_file().path().or_else(|| delta.old_file().path());
+            let path = path.map(|p| p.to_string_lossy()).unwrap_or_default();
+            out.push_str(&format!("{}{} changed\n", BINARY_MARKER, path));
+            return;
+        }
+        _ => "", // file and hunk headers
+    };
+    out.push_str(prefix);
+    if let Ok(content) = std::str::from_utf8(line.content()) {
+        out.push_str(content);
+    }
+}
+
 /// Get the staged diff as a string for AI consumption
 pub fn get_staged_diff(repo: &Repository) -> Result<String> {
     let head = repo.head().ok().and_then(|h| h.peel_to_tree().ok());
@@ -21,24 +48,8 @@ pub fn get_staged_diff(repo: &Repository) -> Result<String> {
 
     let mut diff_text = String::new();
 
-    diff.print(git2::DiffFormat::Patch, |_delta, _hunk, line| {
-        let prefix = match line.origin() {
-            '+' => "+",
-            '-' => "-",
-            ' ' => " ",
-            'H' => "", // file header
-            'F' => "", // file header
-            'B' => "", // binary
-            _ => "",
-        };
-
-        if !prefix.is_empty() {
-            diff_text.push_str(prefix);
-        }
-
-        if let Ok(content) = std::str::from_utf8(line.content()) {
-            diff_text.push_str(content);
-        }
+    diff.print(git2::DiffFormat::Patch, |delta, _hunk, line| {
+        push_diff_line(&mut diff_text, &delta, &line);
 
         true
     })
@@ -109,17 +120,22 @@ pub fn stage_all(repo: &Repository, ignore: Option<&GlobSet>) -> Result<()> {
     Ok(())
 }
 
-/// Stage specific files by path
-pub fn stage_files(repo: &Repository, paths: &[String]) -> Result<()> {
+/// Stage specific files (workdir-relative paths), staging the removal of
+/// any that were deleted — `git add` at file granularity
+pub fn stage_paths(repo: &Repository, paths: &[String]) -> Result<()> {
+    let workdir = repo
+        .workdir()
+        .context("Bare repos not supported")?
+        .to_path_buf();
     let mut index = repo.index().context("Failed to get index")?;
     for path in paths {
         let p = std::path::Path::new(path);
-        if p.exists() {
+        // resolve against the workdir, not the cwd (`--path`, subdirectories)
+        if workdir.join(p).symlink_metadata().is_ok() {
             index
                 .add_path(p)
                 .with_context(|| format!("Failed to stage: {}", path))?;
         } else {
-            // Deleted file: stage the removal
             index
                 .remove_path(p)
                 .with_context(|| format!("Failed to stage removal: {}", path))?;
@@ -129,6 +145,43 @@ pub fn stage_files(repo: &Repository, paths: &[String]) -> Result<()> {
     Ok(())
 }
 
+/// Stage every changed, new or deleted file matching the pathspecs (git
+/// pathspec syntax, relative to the repo root) and return the staged paths.
+/// Fails without touching the index if any pathspec matches nothing.
+pub fn stage_pathspec(repo: &Repository, pathspecs: &[String]) -> Result<Vec<String>> {
+    let workdir = repo
+        .workdir()
+        .context("Bare repos not supported")?
+        .to_path_buf();
+    let mut index = repo.index().context("Failed to get index")?;
+    let mut staged = BTreeSet::new();
+    for spec in pathspecs {
+        let mut matched = BTreeSet::new();
+        let mut collect = |path: &std::path::Path, _spec: &[u8]| {
+            if is_inside_worktree(&workdir, path) {
+                return 1;
+            }
+            matched.insert(path.to_string_lossy().into_owned());
+            0
+        };
+        index
+            .add_all([spec].iter(), IndexAddOption::DEFAULT, Some(&mut collect))
+            .with_context(|| format!("Failed to stage: {}", spec))?;
+        // add_all skips deletions; update_all stages those
+        index
+            .update_all([spec].iter(), Some(&mut collect))
+            .with_context(|| format!("Failed to stage: {}", spec))?;
+        if matched.is_empty() {
+            // the repo shares this in-memory index; drop what was added
+            index.read(true).context("Failed to reload index")?;
+            bail!("pathspec '{}' did not match any changed files", spec);
+        }
+        staged.extend(matched);
+    }
+    write_index(&mut index)?;
+    Ok(staged.into_iter().collect())
+}
+
 fn write_index(index: &mut git2::Index) -> Result<()> {
     index.write().map_err(|e| {
         if e.code() == git2::ErrorCode::Locked {
@@ -207,24 +260,8 @@ pub fn get_amend_diff(repo: &Repository) -> Result<String> {
 
     let mut diff_text = String::new();
 
-    diff.print(git2::DiffFormat::Patch, |_delta, _hunk, line| {
-        let prefix = match line.origin() {
-            '+' => "+",
-            '-' => "-",
-            ' ' => " ",
-            'H' => "",
-            'F' => "",
-            'B' => "",
-            _ => "",
-        };
-
-        if !prefix.is_empty() {
-            diff_text.push_str(prefix);
-        }
-
-        if let Ok(content) = std::str::from_utf8(line.content()) {
-            diff_text.push_str(content);
-        }
+    diff.print(git2::DiffFormat::Patch, |delta, _hunk, line| {
+        push_diff_line(&mut diff_text, &delta, &line);
 
         true
     })
@@ -250,24 +287,8 @@ pub fn get_commit_diff(repo: &Repository, oid: git2::Oid) -> Result<String> {
 
     let mut diff_text = String::new();
 
-    diff.print(git2::DiffFormat::Patch, |_delta, _hunk, line| {
-        let prefix = match line.origin() {
-            '+' => "+",
-            '-' => "-",
-            ' ' => " ",
-            'H' => "",
-            'F' => "",
-            'B' => "",
-            _ => "",
-        };
-
-        if !prefix.is_empty() {
-            diff_text.push_str(prefix);
-        }
-
-        if let Ok(content) = std::str::from_utf8(line.content()) {
-            diff_text.push_str(content);
-        }
+    diff.print(git2::DiffFormat::Patch, |delta, _hunk, line| {
+        push_diff_line(&mut diff_text, &delta, &line);
 
         true
     })
@@ -276,6 +297,36 @@ pub fn get_commit_diff(repo: &Repository, oid: git2::Oid) -> Result<String> {
     Ok(diff_text)
 }
 
+/// Size of a commit against its first parent
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CommitStat {
+    pub files: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+/// Stats for a commit's diff against its parent; None for merges, whose
+/// first-parent diff would overstate them
+pub fn get_commit_stat(repo: &Repository, oid: git2::Oid) -> Result<Option<CommitStat>> {
+    let commit = repo.find_commit(oid).context("find commit")?;
+    if commit.parent_count() > 1 {
+        return Ok(None);
+    }
+    let parent_tree = match commit.parent_count() {
+        0 => None,
+        _ => Some(commit.parent(0)?.tree()?),
+    };
+    let diff = repo
+        .diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)
+        .context("diff tree to tree")?;
+    let stats = diff.stats().context("Failed to get diff stats")?;
+    Ok(Some(CommitStat {
+        files: stats.files_changed(),
+        insertions: stats.insertions(),
+        deletions: stats.deletions(),
+    }))
+}
+
 /// Check if a path lives inside (or *is*) a git worktree checkout.
 /// Worktrees contain a `.git` *file* (not directory) pointing to the main repo.
 /// We walk from the resolved absolute path upward toward the repo root; if any
@@ -315,24 +366,8 @@ pub fn get_unstaged_diff(repo: &Repository) -> Result<String> {
 
     let mut diff_text = String::new();
 
-    diff.print(git2::DiffFormat::Patch, |_delta, _hunk, line| {
-        let prefix = match line.origin() {
-            '+' => "+",
-            '-' => "-",
-            ' ' => " ",
-            'H' => "",
-            'F' => "",
-            'B' => "",
-            _ => "",
-        };
-
-        if !prefix.is_empty() {
-            diff_text.push_str(prefix);
-        }
-
-        if let Ok(content) = std::str::from_utf8(line.content()) {
-            diff_text.push_str(content);
-        }
+    diff.print(git2::DiffFormat::Patch, |delta, _hunk, line| {
+        push_diff_line(&mut diff_text, &delta, &line);
 
         true
     })
@@ -393,23 +428,7 @@ pub fn get_unstaged_diff_for_paths(
     let stats_ref = std::cell::RefCell::new(&mut stats);
 
     diff.print(git2::DiffFormat::Patch, |delta, _hunk, line| {
-        let prefix = match line.origin() {
-            '+' => "+",
-            '-' => "-",
-            ' ' => " ",
-            'H' => "",
-            'F' => "",
-            'B' => "",
-            _ => "",
-        };
-
-        if !prefix.is_empty() {
-            diff_text.push_str(prefix);
-        }
-
-        if let Ok(content) = std::str::from_utf8(line.content()) {
-            diff_text.push_str(content);
-        }
+        push_diff_line(&mut diff_text, &delta, &line);
 
         if matches!(line.origin(), '+' | '-') {
             let delta_path = delta
@@ -458,6 +477,91 @@ mod tests {
         repo
     }
 
+    #[test]
+    fn test_stage_paths_modified_new_and_deleted() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let repo = init_test_repo(dir.path());
+        fs::write(dir.path().join("init.txt"), "changed").unwrap();
+        fs::write(dir.path().join("new.txt"), "new").unwrap();
+        fs::write(dir.path().join("skip.txt"), "skip").unwrap();
+
+        stage_paths(&repo, &["init.txt".to_string(), "new.txt".to_string()]).unwrap();
+        let staged = get_staged_files(&repo).unwrap();
+        assert!(staged.iter().any(|f| f.contains("init.txt")));
+        assert!(staged.iter().any(|f| f.contains("new.txt")));
+        assert!(!staged.iter().any(|f| f.contains("skip.txt")));
+
+        fs::remove_file(dir.path().join("new.txt")).unwrap();
+        stage_paths(&repo, &["new.txt".to_string()]).unwrap();
+        let index = repo.index().unwrap();
+        assert!(index.get_path(Path::new("new.txt"), 0).is_none());
+        assert!(index.get_path(Path::new("init.txt"), 0).is_some());
+    }
+
+    #[test]
+    fn test_binary_placeholder() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let repo = init_test_repo(dir.path());
+        fs::write(dir.path().join("logo.png"), [0x89, b'P', b'N', b'G', 0, 0, 1]).unwrap();
+        stage_paths(&repo, &["logo.png".to_string()]).unwrap();
+
+        let diff = get_staged_diff(&repo).unwrap();
+        assert!(diff.contains("Binary file logo.png changed\n"), "{}", diff);
+        assert!(!diff.contains("differ"));
+    }
+
+    #[test]
+    fn test_stage_pathspec() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let repo = init_test_repo(dir.path());
+        fs::create_dir(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src/a.rs"), "a").unwrap();
+        fs::write(dir.path().join("src/b.rs"), "b").unwrap();
+        fs::write(dir.path().join("notes.txt"), "n").unwrap();
+        fs::remove_file(dir.path().join("init.txt")).unwrap();
+
+        let staged = stage_pathspec(&repo, &["src".to_string(), "init.txt".to_string()]).unwrap();
+        assert_eq!(staged, ["init.txt", "src/a.rs", "src/b.rs"]);
+        let files = get_staged_files(&repo).unwrap();
+        assert!(!files.iter().any(|f| f.contains("notes.txt")));
+        assert!(repo.index().unwrap().get_path(Path::new("init.txt"), 0).is_none());
+
+        let err = stage_pathspec(&repo, &["notes.txt".to_string(), "docs".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("'docs'"), "{}", err);
+        assert!(!get_staged_files(&repo).unwrap().iter().any(|f| f.contains("notes.txt")));
+    }
+
+    #[test]
+    fn test_get_commit_stat() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let repo = init_test_repo(dir.path());
+        let root = repo.head().unwrap().target().unwrap();
+        assert_eq!(
+            get_commit_stat(&repo, root).unwrap(),
+            Some(CommitStat { files: 1, insertions: 1, deletions: 0 })
+        );
+
+        fs::write(dir.path().join("init.txt"), "changed\nmore\n").unwrap();
+        fs::write(dir.path().join("b.txt"), "b\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("init.txt")).unwrap();
+        index.add_path(Path::new("b.txt")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = git2::Signature::now("Test", "test@test.com").unwrap();
+        let parent = repo.find_commit(root).unwrap();
+        let oid = repo.commit(Some("HEAD"), &sig, &sig, "two", &tree, &[&parent]).unwrap();
+        assert_eq!(
+            get_commit_stat(&repo, oid).unwrap(),
+            Some(CommitStat { files: 2, insertions: 3, deletions: 1 })
+        );
+
+        let merge = repo
+            .commit(None, &sig, &sig, "merge", &tree, &[&parent, &repo.find_commit(oid).unwrap()])
+            .unwrap();
+        assert_eq!(get_commit_stat(&repo, merge).unwrap(), None);
+    }
+
     /// Create a fake worktree marker: a `.git` *file* (not directory) inside a subdir.
     /// Real worktrees have `gitdir: /path/to/main/.git/worktrees/<name>` in this file.
     fn make_worktree_marker(dir: &Path) {
@@ -1,42 +1,45 @@
+use anyhow::{Context, Result};
 use colored::Colorize;
-use std::path::Path;
-use std::process::Command;
+use git2::{Cred, CredentialType, ErrorClass, FetchOptions, FetchPrune, Oid, RemoteCallbacks, Repository};
+use indicatif::{ProgressBar, ProgressStyle};
+use std::collections::BTreeMap;
+use std::io::{IsTerminal, Read, Write};
+use std::process::{Command, Stdio};
 
-/// Fetch from all remotes using git CLI, returning any errors as warnings
-pub fn fetch_all_remotes(repo_path: &Path) -> Vec<String> {
-    let output = Command::new("git")
-        .args(["-C", &repo_path.display().to_string(), "remote"])
-        .output();
+use super::branches::get_upstream_ref;
+use crate::error::RepoError;
+use crate::terminal::is_quiet;
 
-    let remotes = match output {
-        Ok(o) if o.status.success() => {
-            String::from_utf8_lossy(&o.stdout)
-                .lines()
-                .map(String::from)
-                .collect::<Vec<_>>()
-        }
-        _ => return vec!["failed to list remotes".to_string()],
+/// Fetch every remote, collecting failures as warnings instead of stopping
+pub fn fetch_all_remotes(repo: &Repository) -> (Vec<FetchReport>, Vec<String>) {
+    let remotes = match repo.remotes() {
+        Ok(names) => names.iter().flatten().map(String::from).collect::<Vec<_>>(),
+        Err(_) => return (Vec::new(), vec!["failed to list remotes".to_string()]),
     };
 
+    let mut reports = Vec::new();
     let mut warnings = Vec::new();
-
     for remote in remotes {
-        let fetch = Command::new("git")
-            .args(["-C", &repo_path.display().to_string(), "fetch", &remote, "--quiet"])
-            .output();
-
-        match fetch {
-            Ok(o) if !o.status.success() => {
-                let err = String::from_utf8_lossy(&o.stderr);
-                let msg = err.lines().next().unwrap_or("fetch failed");
-                warnings.push(format!("{}: {}", remote, msg.trim()));
-            }
-            Err(e) => warnings.push(format!("{}: {}", remote, e)),
-            _ => {}
+        match fetch_remote(repo, &remote, false) {
+            Ok(report) => reports.push(report),
+            Err(e) => warnings.push(format!("{}: {}", remote, e.root_cause())),
         }
     }
+    (reports, warnings)
+}
 
-    warnings
+/// Commits a fetch brought to HEAD's upstream, as (`origin/main`, count).
+/// None when the upstream didn't move or was fetched for the first time.
+pub fn new_upstream_commits(repo: &Repository, reports: &[FetchReport]) -> Option<(String, usize)> {
+    let upstream = get_upstream_ref(repo)?;
+    let name = upstream.strip_prefix("refs/remotes/")?;
+    let update = reports.iter().flat_map(|r| &r.updated).find(|u| u.name == name)?;
+
+    let mut walk = repo.revwalk().ok()?;
+    walk.push(update.new).ok()?;
+    walk.hide(update.old?).ok()?;
+    let count = walk.count();
+    (count > 0).then(|| (name.to_string(), count))
 }
 
 /// Print fetch warnings to stderr
@@ -45,3 +48,341 @@ pub fn print_fetch_warnings(warnings: &[String]) {
         eprintln!("{} fetch: {}", "⚠".yellow(), warning);
     }
 }
+
+/// Credentials in the order git would try them: ssh-agent, the configured
+/// credential helper, then whatever the transport offers by default. Gives
+/// up after a few rounds instead of letting libgit2 retry forever. Only a
+/// libgit2 with https/ssh asks for them; the vendored build hands those
+/// remotes to git, which uses the same helpers.
+pub(super) fn remote_callbacks<'a>(repo: &Repository) -> Result<RemoteCallbacks<'a>> {
+    let config = repo.config()?;
+    let mut attempts = 0;
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(move |url, username, allowed| {
+        attempts += 1;
+        if attempts > 3 {
+            return Err(git2::Error::from_str("authentication failed"));
+        }
+        if allowed.contains(CredentialType::SSH_KEY) {
+            if let Some(user) = username {
+                return Cred::ssh_key_from_agent(user);
+            }
+        }
+        if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+            return Cred::credential_helper(&config, url, username);
+        }
+        Cred::default()
+    });
+    Ok(callbacks)
+}
+
+/// libgit2 is built without the https/ssh transports, so those remotes go
+/// through the git binary (see [`git_fetch_cli`]), which brings git's own
+/// credential helpers and progress display
+pub(super) fn unsupported_transport(e: &git2::Error) -> bool {
+    e.class() == ErrorClass::Net && e.message().contains("unsupported URL protocol")
+}
+
+/// Transport failures become [`RepoError::Network`]; anything else is kept
+pub(super) fn network_error(e: git2::Error) -> anyhow::Error {
+    match e.class() {
+        ErrorClass::Net | ErrorClass::Http | ErrorClass::Ssh | ErrorClass::Ssl => {
+            RepoError::Network(e.message().to_string()).into()
+        }
+        // socket errors, e.g. a refused git:// connection
+        ErrorClass::Os
+            if e.message().starts_with("failed to connect")
+                || e.message().starts_with("failed to resolve") =>
+        {
+            RepoError::Network(e.message().to_string()).into()
+        }
+        _ => e.into(),
+    }
+}
+
+/// Phrases in git's stderr that mean the remote couldn't be reached or
+/// refused the credentials, rather than the command itself failing
+const TRANSPORT_FAILURES: &[&str] = &[
+    "could not resolve host",
+    "unable to access",
+    "could not read from remote repository",
+    "connection refused",
+    "connection timed out",
+    "operation timed out",
+    "network is unreachable",
+    "authentication failed",
+    "permission denied (publickey",
+];
+
+/// A failed git command's stderr as an error: a refused ref becomes the same
+/// "push rejected (...)" the libgit2 path reports, transport failures become
+/// [`RepoError::Network`], and anything else keeps git's message
+pub(super) fn cli_error(stderr: &str) -> anyhow::Error {
+    // progress output redraws with \r; only the final text of a line counts
+    let lines: Vec<&str> = stderr
+        .lines()
+        .filter_map(|l| l.rsplit('\r').next())
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+
+    if let Some(line) = lines.iter().find(|l| l.starts_with("! [")) {
+        let rest = line.split_once(']').map_or(*line, |(_, rest)| rest.trim());
+        let reason = match rest.rsplit_once(" (") {
+            Some((refs, why)) => format!("{}: {}", refs.trim(), why.trim_end_matches(')')),
+            None => rest.to_string(),
+        };
+        return anyhow::anyhow!("push rejected ({})", reason);
+    }
+
+    let message = lines
+        .iter()
+        .find(|l| l.starts_with("fatal:"))
+        .or_else(|| lines.iter().find(|l| l.starts_with("error:")))
+        .or(lines.first())
+        .map_or("git failed".to_string(), |l| l.to_string());
+    let lower = stderr.to_lowercase();
+    if TRANSPORT_FAILURES.iter().any(|p| lower.contains(p)) {
+        RepoError::Network(message).into()
+    } else {
+        anyhow::anyhow!(message)
+    }
+}
+
+pub(super) fn git_cli(repo: &Repository, args: &[&str]) -> Result<()> {
+    let workdir = repo.workdir().unwrap_or_else(|| repo.path());
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(workdir)
+        .args(args)
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        return Err(cli_error(&String::from_utf8_lossy(&output.stderr)));
+    }
+    Ok(())
+}
+
+/// Object-count bar for a libgit2 fetch from `remote`; hidden with `--quiet`
+/// or when stderr isn't a tty
+pub(super) fn transfer_bar(remote: &str) -> ProgressBar {
+    if is_quiet() {
+        return ProgressBar::hidden();
+    }
+    let pb = ProgressBar::new(0);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template("{spinner:.green} {prefix} [{bar:30.cyan/blue}] {pos}/{len} objects")
+            .unwrap()
+            .progress_chars("#>-"),
+    );
+    pb.set_prefix(format!("fetching {}", remote));
+    pb
+}
+
+pub(super) fn track_transfer(callbacks: &mut RemoteCallbacks<'_>, pb: &ProgressBar) {
+    let pb = pb.clone();
+    callbacks.transfer_progress(move |stats| {
+        pb.set_length(stats.total_objects() as u64);
+        pb.set_position(stats.received_objects() as u64);
+        true
+    });
+}
+
+/// `git fetch <args>` through the git binary. On a terminal git's progress
+/// is passed through to stderr as it arrives; with `--quiet` or no tty the
+/// fetch is silent. Either way a failure is classified by [`cli_error`].
+pub(super) fn git_fetch_cli(repo: &Repository, args: &[&str]) -> Result<()> {
+    if is_quiet() || !std::io::stderr().is_terminal() {
+        let mut quiet = vec!["fetch", "--quiet"];
+        quiet.extend_from_slice(args);
+        return git_cli(repo, &quiet);
+    }
+    let workdir = repo.workdir().unwrap_or_else(|| repo.path());
+    let mut child = Command::new("git")
+        .arg("-C")
+        .arg(workdir)
+        .args(["fetch", "--progress"])
+        .args(args)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run git")?;
+
+    // tee: show git's progress live, keep a copy to classify a failure
+    let mut captured = Vec::new();
+    if let Some(mut pipe) = child.stderr.take() {
+        let mut stderr = std::io::stderr();
+        let mut buf = [0u8; 4096];
+        loop {
+            let n = pipe.read(&mut buf)?;
+            if n == 0 {
+                break;
+            }
+            let _ = stderr.write_all(&buf[..n]);
+            captured.extend_from_slice(&buf[..n]);
+        }
+    }
+    if !child.wait()?.success() {
+        return Err(cli_error(&String::from_utf8_lossy(&captured)));
+    }
+    Ok(())
+}
+
+/// A tracking ref a fetch created (`old` is None) or moved
+#[derive(Debug, Clone, PartialEq)]
+pub struct RefUpdate {
+    pub name: String,
+    pub old: Option<Oid>,
+    pub new: Oid,
+}
+
+/// Refs one remote's fetch changed
+#[derive(Debug, Clone, PartialEq)]
+pub struct FetchReport {
+    pub remote: String,
+    /// Tracking refs that are new or moved
+    pub updated: Vec<RefUpdate>,
+    /// Tracking refs removed by `--prune`
+    pub pruned: Vec<String>,
+}
+
+pub(super) fn tracking_tips(repo: &Repository, remote: &str) -> Result<BTreeMap<String, Oid>> {
+    let refs = repo.references_glob(&format!("refs/remotes/{}/*", remote))?;
+    Ok(refs
+        .flatten()
+        .filter_map(|r| Some((r.shorthand()?.to_string(), r.target()?)))
+        .collect())
+}
+
+/// Fetch `name` with its configured refspecs, comparing tracking refs before
+/// and after to report what changed
+pub fn fetch_remote(repo: &Repository, name: &str, prune: bool) -> Result<FetchReport> {
+    let mut remote = repo
+        .find_remote(name)
+        .ok()
+        .with_context(|| format!("no remote named '{}'", name))?;
+    let before = tracking_tips(repo, name)?;
+
+    let pb = transfer_bar(name);
+    let mut callbacks = remote_callbacks(repo)?;
+    track_transfer(&mut callbacks, &pb);
+    let mut opts = FetchOptions::new();
+    opts.remote_callbacks(callbacks);
+    if prune {
+        opts.prune(FetchPrune::On);
+    }
+
+    let result = remote.fetch::<&str>(&[], Some(&mut opts), None);
+    pb.finish_and_clear();
+    match result {
+        Ok(()) => {}
+        Err(e) if unsupported_transport(&e) => {
+            let mut args = Vec::new();
+            if prune {
+                args.push("--prune");
+            }
+            args.push(name);
+            git_fetch_cli(repo, &args).with_context(|| format!("fetch from {} failed", name))?;
+        }
+        Err(e) => return Err(network_error(e)).with_context(|| format!("fetch from {} failed", name)),
+    }
+
+    let after = tracking_tips(repo, name)?;
+    Ok(FetchReport {
+        remote: name.to_string(),
+        updated: after
+            .iter()
+            .filter(|(r, oid)| before.get(*r) != Some(oid))
+            .map(|(r, oid)| RefUpdate { name: r.clone(), old: before.get(r).copied(), new: *oid })
+            .collect(),
+        pruned: before.into_keys().filter(|r| !after.contains_key(r)).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_cli_error_classifies_failures() {
+        let rejected = "To example.com:me/repo.git\n ! [rejected]        main -> main (non-fast-forward)\n\
+                        error: failed to push some refs to 'example.com:me/repo.git'\n";
+        let err = cli_error(rejected);
+        assert_eq!(err.to_string(), "push rejected (main -> main: non-fast-forward)");
+        assert!(crate::error::repo_error(&err).is_none());
+
+        let hook = " ! [remote rejected] main -> main (pre-receive hook declined)\n";
+        assert_eq!(cli_error(hook).to_string(), "push rejected (main -> main: pre-receive hook declined)");
+
+        let offline = "fatal: unable to access 'https://example.com/r.git/': Could not resolve host: example.com\n";
+        let err = cli_error(offline);
+        assert!(matches!(crate::error::repo_error(&err), Some(RepoError::Network(_))));
+        assert!(err.to_string().starts_with("fatal: unable to access"));
+
+        let missing = "Receiving objects: 10%\rReceiving objects: 100%\nfatal: couldn't find remote ref nope\n";
+        let err = cli_error(missing);
+        assert!(crate::error::repo_error(&err).is_none());
+        assert_eq!(err.to_string(), "fatal: couldn't find remote ref nope");
+    }
+
+    #[test]
+    fn test_fetch_remote_reports_updated_and_pruned() {
+        let upstream_dir = TempDir::new().unwrap();
+        let upstream = Repository::init(upstream_dir.path()).unwrap();
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        let tree = upstream
+            .find_tree(upstream.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        let oid = upstream.commit(Some("HEAD"), &sig, &sig, "init", &tree, &[]).unwrap();
+        let clone_dir = TempDir::new().unwrap();
+        let clone = Repository::clone(upstream_dir.path().to_str().unwrap(), clone_dir.path()).unwrap();
+
+        let report = fetch_remote(&clone, "origin", false).unwrap();
+        assert!(report.updated.is_empty() && report.pruned.is_empty());
+
+        let head = upstream.find_commit(oid).unwrap();
+        upstream.branch("feature", &head, false).unwrap();
+        let report = fetch_remote(&clone, "origin", false).unwrap();
+        assert_eq!(report.updated, vec![RefUpdate { name: "origin/feature".into(), old: None, new: oid }]);
+
+        upstream
+            .find_branch("feature", git2::BranchType::Local)
+            .unwrap()
+            .delete()
+            .unwrap();
+        assert!(fetch_remote(&clone, "origin", false).unwrap().pruned.is_empty());
+        let report = fetch_remote(&clone, "origin", true).unwrap();
+        assert_eq!((report.updated.len(), report.pruned), (0, vec!["origin/feature".to_string()]));
+        assert!(fetch_remote(&clone, "nope", false).is_err());
+    }
+
+    #[test]
+    fn test_new_upstream_commits() {
+        let upstream_dir = TempDir::new().unwrap();
+        let upstream = Repository::init(upstream_dir.path()).unwrap();
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        let commit = |msg: &str| {
+            let tree = upstream
+                .find_tree(upstream.index().unwrap().write_tree().unwrap())
+                .unwrap();
+            let parent = upstream.head().ok().and_then(|h| h.peel_to_commit().ok());
+            let parents: Vec<&git2::Commit> = parent.iter().collect();
+            upstream.commit(Some("HEAD"), &sig, &sig, msg, &tree, &parents).unwrap()
+        };
+        commit("init");
+        let clone_dir = TempDir::new().unwrap();
+        let clone = Repository::clone(upstream_dir.path().to_str().unwrap(), clone_dir.path()).unwrap();
+        let branch = clone.head().unwrap().shorthand().unwrap().to_string();
+
+        let (reports, warnings) = fetch_all_remotes(&clone);
+        assert!(warnings.is_empty());
+        assert_eq!(new_upstream_commits(&clone, &reports), None);
+
+        commit("second");
+        commit("third");
+        let (reports, _) = fetch_all_remotes(&clone);
+        assert_eq!(new_upstream_commits(&clone, &reports), Some((format!("origin/{}", branch), 2)));
+    }
+}
@@ -1,9 +1,18 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use git2::Repository;
-use serde::Deserialize;
-use std::time::Duration;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::config::Config;
 
 const TIMEOUT: Duration = Duration::from_secs(3);
+const DEFAULT_HOST: &str = "github.com";
+/// GitHub's maximum page size
+const PER_PAGE: usize = 100;
 
 #[derive(Deserialize)]
 struct GithubRepo {
@@ -40,64 +49,249 @@ pub struct Fork {
     pub stars: u32,
 }
 
+/// A paged listing; `stopped` says why paging ended before `limit` (e.g. a
+/// rate limit on a later page), in which case `items` is what came back
+#[derive(Debug, Clone)]
+pub struct Paged<T> {
+    pub items: Vec<T>,
+    pub stopped: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct GithubStats {
     pub stars: u32,
     pub forks: u32,
 }
 
+/// A GitHub (or GitHub Enterprise) repository parsed from a remote URL
+#[derive(Debug, Clone, PartialEq)]
+pub struct GithubRemote {
+    pub host: String,
+    pub owner: String,
+    pub name: String,
+}
+
+impl GithubRemote {
+    /// REST API root: api.github.com for github.com, `/api/v3` for enterprise
+    pub fn api_base(&self) -> String {
+        if self.host == DEFAULT_HOST {
+            "https://api.github.com".to_string()
+        } else {
+            format!("https://{}/api/v3", self.host)
+        }
+    }
+
+    /// Repository page on the web host
+    pub fn web_url(&self) -> String {
+        format!("https://{}/{}/{}", self.host, self.owner, self.name)
+    }
+
+    pub fn issue_url(&self, number: u64) -> String {
+        format!("{}/issues/{}", self.web_url(), number)
+    }
+
+    /// Compare view for `base...head` (`base..head` is accepted too)
+    pub fn compare_url(&self, base: &str, head: &str) -> String {
+        format!("{}/compare/{}...{}", self.web_url(), base, head)
+    }
+
+    fn cache_key(&self) -> String {
+        if self.host == DEFAULT_HOST {
+            format!("{}/{}", self.owner, self.name)
+        } else {
+            format!("{}/{}/{}", self.host, self.owner, self.name)
+        }
+    }
+}
+
+/// Issue number in a branch name: the first all-digit segment between
+/// `/`, `-`, `_` or `#`, e.g. `fix/123-crash` or `gh-42`
+pub fn ticket_from_branch(branch: &str) -> Option<u64> {
+    branch
+        .split(['/', '-', '_', '#'])
+        .find(|seg| !seg.is_empty() && seg.chars().all(|c| c.is_ascii_digit()))
+        .and_then(|seg| seg.parse().ok())
+}
+
+/// Split `base...head` / `base..head`; a bare ref compares against `current`
+pub fn parse_compare_spec<'a>(spec: &'a str, current: &'a str) -> (&'a str, &'a str) {
+    spec.split_once("...")
+        .or_else(|| spec.split_once(".."))
+        .unwrap_or((spec, current))
+}
+
 pub fn get_github_stats(repo: &Repository) -> Option<GithubStats> {
-    let (owner, name) = parse_github_remote(repo)?;
-    fetch_repo_stats(&owner, &name).ok()
+    let remote = parse_github_remote(repo)?;
+    fetch_repo_stats(&remote).ok()
+}
+
+/// Like `get_github_stats`, but served from the disk cache while fresh.
+/// `refresh` forces a fetch; the cache is only updated on success.
+pub fn get_github_stats_cached(
+    repo: &Repository,
+    ttl_secs: u64,
+    refresh: bool,
+) -> Option<GithubStats> {
+    let remote = parse_github_remote(repo)?;
+    let key = remote.cache_key();
+    let path = stats_cache_path();
+    let mut cache = load_stats_cache(&path);
+    let now = unix_now();
+
+    if !refresh {
+        if let Some(stats) = cache.fresh(&key, now, ttl_secs) {
+            return Some(stats);
+        }
+    }
+
+    match fetch_repo_stats(&remote) {
+        Ok(stats) => {
+            cache.insert(key, &stats, now);
+            let _ = save_stats_cache(&path, &cache);
+            Some(stats)
+        }
+        // Offline or rate-limited: a stale value beats nothing
+        Err(_) => cache.entries.get(&key).map(|e| e.stats()),
+    }
 }
 
-pub fn get_stargazers(repo: &Repository) -> Result<Vec<Stargazer>> {
-    let (owner, name) = parse_github_remote(repo).context("Not a GitHub repository")?;
-    fetch_stargazers(&owner, &name)
+/// Up to `limit` stargazers, fetched page by page
+pub fn get_stargazers(repo: &Repository, limit: usize) -> Result<Paged<Stargazer>> {
+    let remote = parse_github_remote(repo).context("Not a GitHub repository")?;
+    fetch_stargazers(&remote, limit)
 }
 
-pub fn get_forks(repo: &Repository) -> Result<Vec<Fork>> {
-    let (owner, name) = parse_github_remote(repo).context("Not a GitHub repository")?;
-    fetch_forks(&owner, &name)
+/// Up to `limit` forks, most starred first
+pub fn get_forks(repo: &Repository, limit: usize) -> Result<Paged<Fork>> {
+    let remote = parse_github_remote(repo).context("Not a GitHub repository")?;
+    fetch_forks(&remote, limit)
 }
 
-pub fn parse_github_remote(repo: &Repository) -> Option<(String, String)> {
+pub fn parse_github_remote(repo: &Repository) -> Option<GithubRemote> {
     let remote = repo.find_remote("origin").ok()?;
     let url = remote.url()?;
-    parse_github_url(url)
+    let config = Config::load().unwrap_or_default();
+    parse_github_url(url, &config.github_host)
 }
 
-fn parse_github_url(url: &str) -> Option<(String, String)> {
-    // Handle SSH: git@github.com:owner/repo.git
-    if url.starts_with("git@github.com:") {
-        let path = url.strip_prefix("git@github.com:")?;
-        let path = path.strip_suffix(".git").unwrap_or(path);
-        let mut parts = path.splitn(2, '/');
-        let owner = parts.next()?;
-        let name = parts.next()?;
-        return Some((owner.to_string(), name.to_string()));
+/// Parse scp-style (`git@host:o/r`), `ssh://`, `git://` and `http(s)://` remotes.
+/// Accepts github.com plus the configured `github_host`.
+fn parse_github_url(url: &str, github_host: &str) -> Option<GithubRemote> {
+    let (authority, path) = if let Some((_, rest)) = url.split_once("://") {
+        rest.split_once('/')?
+    } else {
+        // scp-like syntax: [user@]host:path
+        url.split_once(':')?
+    };
+
+    // Drop `user@` and `:port`
+    let host = authority.rsplit('@').next()?;
+    let host = host.split(':').next()?.to_lowercase();
+    if host != DEFAULT_HOST && host != github_host.to_lowercase() {
+        return None;
+    }
+
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    let (owner, name) = path.split_once('/')?;
+    if owner.is_empty() || name.is_empty() || name.contains('/') {
+        return None;
+    }
+
+    Some(GithubRemote {
+        host,
+        owner: owner.to_string(),
+        name: name.to_string(),
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct CachedStats {
+    stars: u32,
+    forks: u32,
+    fetched_at: u64,
+}
+
+impl CachedStats {
+    fn stats(&self) -> GithubStats {
+        GithubStats {
+            stars: self.stars,
+            forks: self.forks,
+        }
     }
+}
 
-    // Handle HTTPS: https://github.com/owner/repo.git
-    if url.contains("github.com") {
-        let url = url.strip_prefix("https://").or_else(|| url.strip_prefix("http://"))?;
-        let url = url.strip_prefix("github.com/")?;
-        let url = url.strip_suffix(".git").unwrap_or(url);
-        let mut parts = url.splitn(2, '/');
-        let owner = parts.next()?;
-        let name = parts.next()?;
-        return Some((owner.to_string(), name.to_string()));
+/// On-disk GitHub stats, keyed by `owner/name`
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct StatsCache {
+    #[serde(flatten)]
+    entries: HashMap<String, CachedStats>,
+}
+
+impl StatsCache {
+    fn fresh(&self, key: &str, now: u64, ttl_secs: u64) -> Option<GithubStats> {
+        let entry = self.entries.get(key)?;
+        if now.saturating_sub(entry.fetched_at) < ttl_secs {
+            Some(entry.stats())
+        } else {
+            None
+        }
     }
 
-    None
+    fn insert(&mut self, key: String, stats: &GithubStats, now: u64) {
+        self.entries.insert(
+            key,
+            CachedStats {
+                stars: stats.stars,
+                forks: stats.forks,
+                fetched_at: now,
+            },
+        );
+    }
+}
+
+fn stats_cache_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".cache")
+        .join("repo")
+        .join("github-stats.json")
+}
+
+/// Missing or corrupt cache files are treated as empty
+fn load_stats_cache(path: &Path) -> StatsCache {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save_stats_cache(path: &Path, cache: &StatsCache) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(cache)?)?;
+    Ok(())
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
 }
 
 fn client() -> reqwest::blocking::Client {
     reqwest::blocking::Client::new()
 }
 
-fn fetch_repo_stats(owner: &str, name: &str) -> Result<GithubStats> {
-    let url = format!("https://api.github.com/repos/{}/{}", owner, name);
+fn fetch_repo_stats(remote: &GithubRemote) -> Result<GithubStats> {
+    let url = format!(
+        "{}/repos/{}/{}",
+        remote.api_base(),
+        remote.owner,
+        remote.name
+    );
     let resp: GithubRepo = client()
         .get(&url)
         .header("User-Agent", "repo-cli")
@@ -110,32 +304,80 @@ fn fetch_repo_stats(owner: &str, name: &str) -> Result<GithubStats> {
     })
 }
 
-fn fetch_stargazers(owner: &str, name: &str) -> Result<Vec<Stargazer>> {
+/// GET `url` page by page until `limit` items or a short page
+fn fetch_paged<T: DeserializeOwned>(url: &str, limit: usize) -> Result<Paged<T>> {
+    // one page size throughout so page offsets line up
+    let per_page = limit.clamp(1, PER_PAGE);
+    let sep = if url.contains('?') { '&' } else { '?' };
+    collect_pages(limit, per_page, |page| {
+        let resp = client()
+            .get(format!("{}{}per_page={}&page={}", url, sep, per_page, page))
+            .header("User-Agent", "repo-cli")
+            .timeout(TIMEOUT)
+            .send()?;
+        let status = resp.status();
+        if !status.is_success() {
+            #[derive(Deserialize)]
+            struct ApiError {
+                message: String,
+            }
+            let message = resp.json::<ApiError>().map(|e| e.message).unwrap_or_default();
+            bail!("GitHub API {}: {}", status, message);
+        }
+        Ok(resp.json()?)
+    })
+}
+
+/// Pull pages from `get_page` (1-based) until `limit` items or a short page.
+/// A failed first page is an error; a later one keeps what came back.
+fn collect_pages<T>(
+    limit: usize,
+    per_page: usize,
+    mut get_page: impl FnMut(usize) -> Result<Vec<T>>,
+) -> Result<Paged<T>> {
+    let mut items = Vec::new();
+    let mut stopped = None;
+    let mut page = 1;
+    while items.len() < limit {
+        let resp = match get_page(page) {
+            Ok(resp) => resp,
+            Err(e) if page == 1 => return Err(e),
+            Err(e) => {
+                stopped = Some(format!("{:#}", e));
+                break;
+            }
+        };
+        let last = resp.len() < per_page;
+        items.extend(resp);
+        if last {
+            break;
+        }
+        page += 1;
+    }
+    items.truncate(limit);
+    Ok(Paged { items, stopped })
+}
+
+fn fetch_stargazers(remote: &GithubRemote, limit: usize) -> Result<Paged<Stargazer>> {
     let url = format!(
-        "https://api.github.com/repos/{}/{}/stargazers?per_page=100",
-        owner, name
+        "{}/repos/{}/{}/stargazers",
+        remote.api_base(),
+        remote.owner,
+        remote.name
     );
-    let resp: Vec<Stargazer> = client()
-        .get(&url)
-        .header("User-Agent", "repo-cli")
-        .timeout(TIMEOUT)
-        .send()?
-        .json()?;
-    Ok(resp)
+    fetch_paged(&url, limit)
 }
 
-fn fetch_forks(owner: &str, name: &str) -> Result<Vec<Fork>> {
+fn fetch_forks(remote: &GithubRemote, limit: usize) -> Result<Paged<Fork>> {
     let url = format!(
-        "https://api.github.com/repos/{}/{}/forks?sort=stargazers&per_page=100",
-        owner, name
+        "{}/repos/{}/{}/forks?sort=stargazers",
+        remote.api_base(),
+        remote.owner,
+        remote.name
     );
-    let resp: Vec<ForkResponse> = client()
-        .get(&url)
-        .header("User-Agent", "repo-cli")
-        .timeout(TIMEOUT)
-        .send()?
-        .json()?;
-    Ok(resp
+    let resp: Paged<ForkResponse> = fetch_paged(&url, limit)?;
+    let items = resp
+        .items
         .into_iter()
         .map(|f| Fork {
             repo_name: f.full_name,
@@ -144,28 +386,172 @@ fn fetch_forks(owner: &str, name: &str) -> Result<Vec<Fork>> {
             owner_url: f.owner.html_url,
             stars: f.stargazers_count,
         })
-        .collect())
+        .collect();
+    Ok(Paged { items, stopped: resp.stopped })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn remote(host: &str, owner: &str, name: &str) -> Option<GithubRemote> {
+        Some(GithubRemote {
+            host: host.to_string(),
+            owner: owner.to_string(),
+            name: name.to_string(),
+        })
+    }
+
+    #[test]
+    fn test_collect_pages_keeps_partial_results() {
+        let pages = |fail_on: usize| {
+            move |page: usize| -> Result<Vec<usize>> {
+                if page == fail_on {
+                    bail!("GitHub API 403 Forbidden: API rate limit exceeded");
+                }
+                Ok(vec![page; 2])
+            }
+        };
+        let paged = collect_pages(5, 2, pages(0)).unwrap();
+        assert_eq!(paged.items, vec![1, 1, 2, 2, 3]);
+        assert!(paged.stopped.is_none());
+
+        let paged = collect_pages(5, 2, pages(2)).unwrap();
+        assert_eq!(paged.items, vec![1, 1]);
+        assert!(paged.stopped.unwrap().contains("rate limit"));
+
+        assert!(collect_pages(5, 2, pages(1)).is_err());
+    }
+
+    #[test]
+    fn test_web_urls() {
+        let gh = remote("github.com", "K-NRS", "repo-cli").unwrap();
+        assert_eq!(gh.web_url(), "https://github.com/K-NRS/repo-cli");
+        assert_eq!(gh.issue_url(42), "https://github.com/K-NRS/repo-cli/issues/42");
+        assert_eq!(
+            gh.compare_url("main", "feat/x"),
+            "https://github.com/K-NRS/repo-cli/compare/main...feat/x"
+        );
+
+        let ghe = remote("git.corp.example", "team", "svc").unwrap();
+        assert_eq!(ghe.issue_url(7), "https://git.corp.example/team/svc/issues/7");
+        assert_eq!(
+            ghe.compare_url("v1.0", "v1.1"),
+            "https://git.corp.example/team/svc/compare/v1.0...v1.1"
+        );
+    }
+
+    #[test]
+    fn test_ticket_from_branch() {
+        assert_eq!(ticket_from_branch("fix/123-crash"), Some(123));
+        assert_eq!(ticket_from_branch("gh-42"), Some(42));
+        assert_eq!(ticket_from_branch("feature/PROJ-77_login"), Some(77));
+        assert_eq!(ticket_from_branch("issue#9"), Some(9));
+        assert_eq!(ticket_from_branch("v2-cleanup"), None);
+        assert_eq!(ticket_from_branch("main"), None);
+    }
+
+    #[test]
+    fn test_parse_compare_spec() {
+        assert_eq!(parse_compare_spec("main...feat", "cur"), ("main", "feat"));
+        assert_eq!(parse_compare_spec("v1..v2", "cur"), ("v1", "v2"));
+        assert_eq!(parse_compare_spec("main", "cur"), ("main", "cur"));
+    }
+
     #[test]
     fn test_parse_ssh_url() {
-        let result = parse_github_url("git@github.com:K-NRS/repo-cli.git");
-        assert_eq!(result, Some(("K-NRS".to_string(), "repo-cli".to_string())));
+        let result = parse_github_url("git@github.com:K-NRS/repo-cli.git", DEFAULT_HOST);
+        assert_eq!(result, remote("github.com", "K-NRS", "repo-cli"));
     }
 
     #[test]
     fn test_parse_https_url() {
-        let result = parse_github_url("https://github.com/K-NRS/repo-cli.git");
-        assert_eq!(result, Some(("K-NRS".to_string(), "repo-cli".to_string())));
+        let result = parse_github_url("https://github.com/K-NRS/repo-cli.git", DEFAULT_HOST);
+        assert_eq!(result, remote("github.com", "K-NRS", "repo-cli"));
     }
 
     #[test]
     fn test_parse_https_no_git_suffix() {
-        let result = parse_github_url("https://github.com/K-NRS/repo-cli");
-        assert_eq!(result, Some(("K-NRS".to_string(), "repo-cli".to_string())));
+        let result = parse_github_url("https://github.com/K-NRS/repo-cli", DEFAULT_HOST);
+        assert_eq!(result, remote("github.com", "K-NRS", "repo-cli"));
+    }
+
+    #[test]
+    fn test_parse_ssh_scheme_url() {
+        let expected = remote("github.com", "K-NRS", "repo-cli");
+        assert_eq!(
+            parse_github_url("ssh://git@github.com/K-NRS/repo-cli.git", DEFAULT_HOST),
+            expected
+        );
+        assert_eq!(
+            parse_github_url("ssh://git@github.com:22/K-NRS/repo-cli", DEFAULT_HOST),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_parse_trailing_slash() {
+        let expected = remote("github.com", "K-NRS", "repo-cli");
+        assert_eq!(
+            parse_github_url("https://github.com/K-NRS/repo-cli/", DEFAULT_HOST),
+            expected
+        );
+        assert_eq!(
+            parse_github_url("https://github.com/K-NRS/repo-cli.git/", DEFAULT_HOST),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_parse_enterprise_host() {
+        let host = "git.corp.example";
+        let expected = remote(host, "team", "service");
+        assert_eq!(parse_github_url("git@git.corp.example:team/service.git", host), expected);
+        assert_eq!(parse_github_url("https://git.corp.example/team/service", host), expected);
+        assert_eq!(
+            parse_github_url("ssh://git@git.corp.example/team/service.git", host),
+            expected
+        );
+        // Unconfigured hosts are rejected
+        assert_eq!(parse_github_url("https://git.corp.example/team/service", DEFAULT_HOST), None);
+        assert_eq!(parse_github_url("https://gitlab.com/team/service", host), None);
+    }
+
+    #[test]
+    fn test_api_base() {
+        let public = remote("github.com", "o", "r").unwrap();
+        assert_eq!(public.api_base(), "https://api.github.com");
+        let enterprise = remote("git.corp.example", "o", "r").unwrap();
+        assert_eq!(enterprise.api_base(), "https://git.corp.example/api/v3");
+    }
+
+    #[test]
+    fn test_stats_cache_ttl() {
+        let mut cache = StatsCache::default();
+        let stats = GithubStats { stars: 42, forks: 7 };
+        cache.insert("K-NRS/repo-cli".to_string(), &stats, 1_000);
+
+        let hit = cache.fresh("K-NRS/repo-cli", 1_000 + 3599, 3600).unwrap();
+        assert_eq!((hit.stars, hit.forks), (42, 7));
+        assert!(cache.fresh("K-NRS/repo-cli", 1_000 + 3600, 3600).is_none());
+        assert!(cache.fresh("other/repo", 1_000, 3600).is_none());
+    }
+
+    #[test]
+    fn test_stats_cache_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("github-stats.json");
+        assert!(load_stats_cache(&path).entries.is_empty());
+
+        let mut cache = StatsCache::default();
+        cache.insert("a/b".to_string(), &GithubStats { stars: 1, forks: 2 }, 10);
+        save_stats_cache(&path, &cache).unwrap();
+
+        let loaded = load_stats_cache(&path);
+        assert_eq!(loaded.entries["a/b"].stars, 1);
+        assert_eq!(loaded.entries["a/b"].fetched_at, 10);
+
+        fs::write(&path, "not json").unwrap();
+        assert!(load_stats_cache(&path).entries.is_empty());
     }
 }
@@ -7,46 +7,133 @@ mod diff;
 mod commit_ops;
 mod github;
 mod fetch;
+mod rebase;
+mod conflicts;
+mod undo;
+mod sync;
 
-pub use repo::open_repo;
-pub use branches::{get_current_branch, get_local_branches, get_remote_branches};
-pub use commits::{get_recent_commits, get_total_commit_count, get_branch_commit_counts};
-pub use status::get_working_tree_status;
-pub use stash::get_stashes;
+pub use repo::{open_repo, open_repo_exact};
+pub use branches::{
+    create_branch, delete_branch, get_current_branch, get_local_branches, get_remote_branches,
+    get_upstream_ref, is_pushed, rename_branch, switch_branch, unmerged_commits,
+};
+pub use commits::{
+    count_commits, get_branch_commit_counts, get_commits_since, get_shortlog, get_nearest_tag, get_recent_commits,
+    get_total_commit_count,
+};
+pub use status::{get_working_tree_status, is_dirty, require_workdir};
+pub use stash::{get_stash_diff, get_stashes};
 pub use diff::{
-    get_amend_diff, get_commit_diff, get_staged_diff, get_staged_files, get_unstaged_diff,
-    get_unstaged_diff_for_paths, get_unstaged_files, has_staged_changes, stage_all, stage_files,
-    FileStat,
+    get_amend_diff, get_commit_diff, get_commit_stat, get_staged_diff, get_staged_files, get_unstaged_diff,
+    get_unstaged_diff_for_paths, get_unstaged_files, has_staged_changes, stage_all, stage_pathspec,
+    stage_paths, CommitStat, FileStat, BINARY_MARKER,
+};
+pub use commit_ops::{
+    amend_commit, cleanup_message, create_commit, get_author_info, get_cleanup_config,
+    get_last_commit_message, uncommit, CleanupMode,
+};
+pub use github::{
+    get_forks, get_github_stats, get_github_stats_cached, get_stargazers, parse_compare_spec,
+    parse_github_remote, ticket_from_branch, Fork, GithubRemote, GithubStats, Paged, Stargazer,
+};
+pub use fetch::{
+    fetch_all_remotes, fetch_remote, new_upstream_commits, print_fetch_warnings,
+    FetchReport, RefUpdate,
+};
+pub use conflicts::{
+    get_conflicts, marker_label, parse_conflict_regions, ConflictRegion, ConflictedFile,
+};
+pub use sync::{
+    fetch_for_sync, has_uncommitted_changes, integrate_fetched, plan_sync, push_for_sync,
+    PullOutcome, SyncPlan,
 };
-pub use commit_ops::{amend_commit, create_commit, get_author_info, get_last_commit_message};
-pub use github::{get_github_stats, get_stargazers, get_forks, GithubStats, Stargazer, Fork};
-pub use fetch::{fetch_all_remotes, print_fetch_warnings};
+pub use undo::{check_undo_safe, find_undo_target, undo_to, UndoTarget};
+pub use rebase::{
+    get_conflicted_files, is_rebase_conflict, resolve_rebase_conflicts, write_commit_editor,
+    write_todo_editor, ScratchDir,
+};
+
+use std::path::Path;
 
 use anyhow::Result;
 use git2::Repository;
 
 use crate::config::Config;
-use crate::models::RepoSummary;
+use crate::models::{RepoSummary, WorkingTreeStatus};
+use crate::terminal::repo_display_name;
+
+/// Commits counted for the summary before giving up, unless `full_stats`
+pub const COMMIT_COUNT_CAP: usize = 10_000;
+
+/// What [`summary`] gathers
+#[derive(Debug, Clone)]
+pub struct SummaryOptions {
+    /// Recent commits to include
+    pub commit_limit: usize,
+    /// Fetch every remote first; fetch failures are ignored
+    pub fetch: bool,
+    /// Bypass the GitHub stats cache
+    pub refresh_stats: bool,
+    /// Count every commit instead of stopping at [`COMMIT_COUNT_CAP`]
+    pub full_stats: bool,
+}
+
+impl Default for SummaryOptions {
+    fn default() -> Self {
+        Self { commit_limit: 5, fetch: false, refresh_stats: false, full_stats: false }
+    }
+}
+
+/// Open the repository at `path` and gather its summary. Nothing is
+/// rendered, but stderr isn't silent: with `fetch`, fetch progress shows on
+/// a terminal unless [`crate::terminal::set_quiet`] is on, and config file
+/// warnings print once per process. Render the result with `render_static` or
+/// `run_tui`, or use it as is.
+pub fn summary(path: &Path, opts: SummaryOptions) -> Result<RepoSummary> {
+    let mut repo = open_repo(Some(path))?;
+    if opts.fetch {
+        fetch_all_remotes(&repo);
+    }
+    gather_summary(&mut repo, opts.commit_limit, opts.refresh_stats, opts.full_stats)
+}
 
-pub fn gather_summary(repo: &mut Repository, commit_limit: usize) -> Result<RepoSummary> {
+/// `refresh_stats` bypasses the GitHub stats cache; `full_stats` counts every
+/// commit instead of stopping at [`COMMIT_COUNT_CAP`]
+pub fn gather_summary(
+    repo: &mut Repository,
+    commit_limit: usize,
+    refresh_stats: bool,
+    full_stats: bool,
+) -> Result<RepoSummary> {
     let current_branch = get_current_branch(repo)?;
-    let status = get_working_tree_status(repo)?;
+    let bare = repo.is_bare();
+    let status = if bare { WorkingTreeStatus::default() } else { get_working_tree_status(repo)? };
     let recent_commits = get_recent_commits(repo, commit_limit)?;
     let local_branches = get_local_branches(repo)?;
     let remote_branches = get_remote_branches(repo)?;
-    let stashes = get_stashes(repo)?;
-    let total_commits = get_total_commit_count(repo)?;
-    let popular_branches = get_branch_commit_counts(repo)?;
+    let stashes = if bare { Vec::new() } else { get_stashes(repo)? };
+    let email = repo.signature().ok().and_then(|s| s.email().map(str::to_string));
+    let cap = (!full_stats).then_some(COMMIT_COUNT_CAP);
+    let counts = count_commits(repo, cap, email.as_deref())?;
+    let popular_branches = get_branch_commit_counts(repo, cap)?;
 
     let config = Config::load().unwrap_or_default();
     let github_stats = if config.show_github_stats {
-        get_github_stats(repo)
+        get_github_stats_cached(repo, config.github_stats_ttl_secs, refresh_stats)
     } else {
         None
     };
 
+    let repo_name = match parse_github_remote(repo) {
+        Some(remote) => format!("{}/{}", remote.owner, remote.name),
+        None => repo_display_name(repo),
+    };
+
     Ok(RepoSummary {
+        repo_name,
         current_branch,
+        bare,
+        state: repo.state(),
         status,
         recent_commits,
         local_branches,
@@ -55,7 +142,54 @@ pub fn gather_summary(repo: &mut Repository, commit_limit: usize) -> Result<Repo
         graph: None,
         github_stars: github_stats.as_ref().map(|s| s.stars),
         github_forks: github_stats.as_ref().map(|s| s.forks),
-        total_commits,
+        total_commits: counts.total,
+        my_commits: counts.mine,
+        commits_capped: counts.capped,
         popular_branches,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{repo_error, RepoError};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_summary_opens_path() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        let tree = repo.find_tree(repo.index().unwrap().write_tree().unwrap()).unwrap();
+        for msg in ["first", "second"] {
+            let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+            let parents: Vec<&git2::Commit> = parent.iter().collect();
+            repo.commit(Some("HEAD"), &sig, &sig, msg, &tree, &parents).unwrap();
+        }
+
+        let opts = SummaryOptions { commit_limit: 1, ..Default::default() };
+        let gathered = summary(dir.path(), opts).unwrap();
+        assert_eq!(gathered.recent_commits.len(), 1);
+        assert_eq!(gathered.recent_commits[0].message, "second");
+        assert_eq!(gathered.total_commits, 2);
+
+        assert!(summary(&dir.path().join("missing"), SummaryOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_summary_of_bare_repo() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init_bare(dir.path()).unwrap();
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        let tree = repo.find_tree(repo.treebuilder(None).unwrap().write().unwrap()).unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "init", &tree, &[]).unwrap();
+
+        let gathered = summary(dir.path(), SummaryOptions::default()).unwrap();
+        assert!(gathered.bare);
+        assert!(gathered.status.is_clean());
+        assert_eq!(gathered.total_commits, 1);
+
+        let err = is_dirty(&repo).unwrap_err();
+        assert!(matches!(repo_error(&err), Some(RepoError::BareRepo)));
+    }
+}
@@ -0,0 +1,353 @@
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use git2::{Repository, RepositoryState};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use super::conflicts::{marker_label, parse_conflict_regions};
+use crate::error::RepoError;
+
+/// Whether a failed `git rebase` stopped because of a merge conflict
+pub fn is_rebase_conflict(output: &Output) -> bool {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    [stderr, stdout]
+        .iter()
+        .any(|s| s.contains("CONFLICT") || s.contains("could not apply"))
+}
+
+/// Paths with unresolved conflicts in the index
+pub fn get_conflicted_files(repo: &Repository) -> Result<Vec<String>> {
+    let statuses = repo.statuses(None)?;
+    Ok(statuses
+        .iter()
+        .filter(|e| e.status().is_conflicted())
+        .filter_map(|e| e.path().map(|p| p.to_string()))
+        .collect())
+}
+
+/// Temp dir holding rebase editor scripts and message files. Removed on drop,
+/// so it outlives every step that reads it — git itself, conflict resolution
+/// and craft's split automation — including early returns on error.
+pub struct ScratchDir {
+    dir: tempfile::TempDir,
+}
+
+impl ScratchDir {
+    /// A fresh `<tmp>/<prefix>-XXXXXX`, unique per run so concurrent rebases
+    /// never share (or clear) each other's scripts
+    pub fn new(prefix: &str) -> Result<Self> {
+        let dir = tempfile::Builder::new()
+            .prefix(&format!("{}-", prefix))
+            .tempdir()
+            .with_context(|| format!("Failed to create a {} temp dir", prefix))?;
+        Ok(Self { dir })
+    }
+
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+}
+
+/// Write the complete rebase todo to `tmp_dir` plus a sequence editor script
+/// that copies it over the todo git hands it (`$1`). Keeping the todo logic in
+/// Rust avoids depending on sed/awk flavours.
+pub fn write_todo_editor(tmp_dir: &Path, todo: &str) -> Result<PathBuf> {
+    let todo_path = tmp_dir.join("todo");
+    let script_path = tmp_dir.join("seq-editor.sh");
+    std::fs::write(&todo_path, todo)?;
+
+    let todo_str = todo_path.to_string_lossy().replace('\'', "'\\''");
+    std::fs::write(&script_path, format!("#!/bin/sh\ncp '{}' \"$1\"\n", todo_str))?;
+    make_executable(&script_path)?;
+
+    Ok(script_path)
+}
+
+/// Write `msg_<sha>` files plus a GIT_EDITOR script that serves the message
+/// for the todo line git just ran, i.e. the last line of `rebase-merge/done`.
+/// Keying by SHA rather than by call count keeps every message on its commit
+/// through reorders, squash chains (one editor call at the chain's last line)
+/// and the extra editor calls of `rebase --continue` after a conflict. Steps
+/// without a message keep the text git proposes.
+pub fn write_commit_editor(tmp_dir: &Path, messages: &[(String, String)]) -> Result<PathBuf> {
+    let script_path = tmp_dir.join("msg-editor.sh");
+    for (sha, msg) in messages {
+        std::fs::write(tmp_dir.join(format!("msg_{}", sha)), msg)?;
+    }
+
+    let tmp_str = tmp_dir.to_string_lossy().replace('\'', "'\\''");
+    let script = format!(
+        r#"#!/bin/sh
+LAST=$(tail -n 1 "$(git rev-parse --git-path rebase-merge/done)" 2>/dev/null)
+SHA=${{LAST#* }}
+SHA=${{SHA%% *}}
+MSG_FILE='{tmp_str}'/msg_$SHA
+if [ -n "$LAST" ] && [ -f "$MSG_FILE" ]; then
+    cp "$MSG_FILE" "$1"
+fi
+"#
+    );
+    std::fs::write(&script_path, script)?;
+    make_executable(&script_path)?;
+
+    Ok(script_path)
+}
+
+fn make_executable(path: &Path) -> Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perms = std::fs::metadata(path)?.permissions();
+        perms.set_mode(0o755);
+        std::fs::set_permissions(path, perms)?;
+    }
+    Ok(())
+}
+
+fn rebase_in_progress(repo: &Repository) -> bool {
+    matches!(
+        repo.state(),
+        RepositoryState::Rebase | RepositoryState::RebaseInteractive | RepositoryState::RebaseMerge
+    )
+}
+
+/// Whole conflict regions, or a stray `<<<<<<<` / `>>>>>>>` left by a
+/// half-finished resolution
+fn has_conflict_markers(content: &str) -> bool {
+    let stray = |l: &str| marker_label(l, "<<<<<<<").is_some() || marker_label(l, ">>>>>>>").is_some();
+    !parse_conflict_regions(content).is_empty() || content.lines().any(stray)
+}
+
+/// `git rebase --continue` with the editor scripts in `env`. Stopping on the
+/// next conflict is not an error; the caller checks the repository state.
+fn continue_rebase(repo_path: &Path, env: &[(&str, &Path)]) -> Result<()> {
+    let mut cmd = Command::new("git");
+    cmd.arg("-C").arg(repo_path).args(["rebase", "--continue"]);
+    for (key, value) in env {
+        cmd.env(key, value);
+    }
+    let output = cmd.output()?;
+    if !output.status.success() && !is_rebase_conflict(&output) {
+        bail!(
+            "rebase --continue failed:\n{}\n{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    Ok(())
+}
+
+/// Pause on a rebase conflict: list conflicted files, let the user fix them in
+/// $EDITOR or their mergetool, then `git rebase --continue` until the rebase
+/// finishes, stops without a conflict (an `edit`), or the user aborts. `env` is passed to `--continue` so the
+/// sequence/commit editor scripts keep serving later steps; the commit editor
+/// must be the SHA-keyed one from [`write_commit_editor`], since git opens it
+/// again for the commit that conflicted.
+pub fn resolve_rebase_conflicts(repo_path: &Path, env: &[(&str, &Path)]) -> Result<()> {
+    loop {
+        let repo = Repository::open(repo_path).context("Failed to open repository")?;
+        if !rebase_in_progress(&repo) {
+            return Ok(());
+        }
+
+        let conflicted = get_conflicted_files(&repo)?;
+        println!("\n{} rebase stopped on a conflict", "⚠".yellow());
+        for path in &conflicted {
+            println!("   {} {}", "✗".red(), path);
+        }
+
+        print!(
+            "{} e=edit m=mergetool c=continue a=abort: ",
+            "?".cyan()
+        );
+        io::stdout().flush()?;
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+
+        match input.trim() {
+            "e" => {
+                let editor = std::env::var("EDITOR").unwrap_or_else(|_| "vi".to_string());
+                Command::new(&editor)
+                    .args(&conflicted)
+                    .current_dir(repo_path)
+                    .status()
+                    .with_context(|| format!("Failed to run {}", editor))?;
+            }
+            "m" => {
+                Command::new("git")
+                    .arg("-C")
+                    .arg(repo_path)
+                    .arg("mergetool")
+                    .status()
+                    .context("Failed to run git mergetool")?;
+            }
+            "c" => {
+                let unresolved: Vec<&String> = conflicted
+                    .iter()
+                    .filter(|p| {
+                        std::fs::read_to_string(repo_path.join(p))
+                            .map(|c| has_conflict_markers(&c))
+                            .unwrap_or(false)
+                    })
+                    .collect();
+                if !unresolved.is_empty() {
+                    println!(
+                        "{} conflict markers remain in {} file(s)",
+                        "!".yellow(),
+                        unresolved.len()
+                    );
+                    continue;
+                }
+
+                if !conflicted.is_empty() {
+                    let status = Command::new("git")
+                        .arg("-C")
+                        .arg(repo_path)
+                        .arg("add")
+                        .arg("--")
+                        .args(&conflicted)
+                        .status()?;
+                    if !status.success() {
+                        bail!("failed to stage resolved files");
+                    }
+                }
+
+                continue_rebase(repo_path, env)?;
+                // stopped again without a conflict, e.g. at an `edit`
+                let repo = Repository::open(repo_path).context("Failed to open repository")?;
+                if rebase_in_progress(&repo) && get_conflicted_files(&repo)?.is_empty() {
+                    return Ok(());
+                }
+            }
+            "a" => {
+                Command::new("git")
+                    .arg("-C")
+                    .arg(repo_path)
+                    .args(["rebase", "--abort"])
+                    .status()?;
+                bail!(RepoError::RebaseConflict);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, init_repo};
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_conflicted_files() {
+        let (_dir, repo) = init_repo();
+        let base = commit_file(&repo, "a.txt", "base\n", "base");
+        let base_commit = repo.find_commit(base).unwrap();
+        repo.branch("other", &base_commit, false).unwrap();
+        commit_file(&repo, "a.txt", "ours\n", "ours");
+
+        repo.set_head("refs/heads/other").unwrap();
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+            .unwrap();
+        let theirs = commit_file(&repo, "a.txt", "theirs\n", "theirs");
+        let main = repo
+            .find_branch("master", git2::BranchType::Local)
+            .or_else(|_| repo.find_branch("main", git2::BranchType::Local))
+            .unwrap();
+        repo.set_head(main.get().name().unwrap()).unwrap();
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+            .unwrap();
+
+        assert!(get_conflicted_files(&repo).unwrap().is_empty());
+        let annotated = repo.find_annotated_commit(theirs).unwrap();
+        repo.merge(&[&annotated], None, None).unwrap();
+        assert_eq!(get_conflicted_files(&repo).unwrap(), vec!["a.txt"]);
+    }
+
+    #[test]
+    fn test_reword_across_a_conflict() {
+        let (dir, repo) = init_repo();
+        commit_file(&repo, "f.txt", "1\n", "base");
+        let a = commit_file(&repo, "a.txt", "a\n", "add a");
+        let f2 = commit_file(&repo, "f.txt", "2\n", "f to 2");
+        let f3 = commit_file(&repo, "f.txt", "3\n", "f to 3");
+        let c = commit_file(&repo, "c.txt", "c\n", "add c");
+
+        // f3 before f2 conflicts, between the two rewords
+        let todo = format!(
+            "reword {a} add a\npick {f3} f to 3\npick {f2} f to 2\nreword {c} add c\n"
+        );
+        let scratch = ScratchDir::new("repo-test-reword-conflict").unwrap();
+        let seq = write_todo_editor(scratch.path(), &todo).unwrap();
+        let messages = [(a.to_string(), "new a\n".to_string()), (c.to_string(), "new c\n".to_string())];
+        let msg = write_commit_editor(scratch.path(), &messages).unwrap();
+        let env = [("GIT_SEQUENCE_EDITOR", seq.as_path()), ("GIT_EDITOR", msg.as_path())];
+
+        let base = repo.revparse_single("HEAD~4").unwrap().id().to_string();
+        let mut cmd = Command::new("git");
+        cmd.arg("-C").arg(dir.path()).args(["rebase", "-i", &base]);
+        for (key, value) in env {
+            cmd.env(key, value);
+        }
+        assert!(is_rebase_conflict(&cmd.output().unwrap()));
+
+        // resolve each stop by taking the commit's own content
+        let repo_path = dir.path();
+        while rebase_in_progress(&Repository::open(repo_path).unwrap()) {
+            let stopped = fs::read_to_string(repo_path.join(".git/rebase-merge/stopped-sha")).unwrap();
+            let content = if f3.to_string().starts_with(stopped.trim()) { "3\n" } else { "2\n" };
+            fs::write(repo_path.join("f.txt"), content).unwrap();
+            Command::new("git").arg("-C").arg(repo_path).args(["add", "f.txt"]).status().unwrap();
+            continue_rebase(repo_path, &env).unwrap();
+        }
+
+        let repo = Repository::open(repo_path).unwrap();
+        let mut walk = repo.revwalk().unwrap();
+        walk.push_head().unwrap();
+        let subjects: Vec<String> = walk
+            .map(|oid| repo.find_commit(oid.unwrap()).unwrap().summary().unwrap().to_string())
+            .collect();
+        assert_eq!(subjects, ["new c", "f to 2", "f to 3", "new a", "base"]);
+    }
+
+    #[test]
+    fn test_write_todo_editor() {
+        let dir = TempDir::new().unwrap();
+        let todo = "pick aaaa first\nreword bbbb second\n";
+        let script = write_todo_editor(dir.path(), todo).unwrap();
+
+        let target = dir.path().join("git-rebase-todo");
+        fs::write(&target, "pick bbbb second\npick aaaa first\n# comments\n").unwrap();
+        let status = Command::new("sh").arg(&script).arg(&target).status().unwrap();
+        assert!(status.success());
+        assert_eq!(fs::read_to_string(&target).unwrap(), todo);
+    }
+
+    #[test]
+    fn test_scratch_dir_outlives_failing_steps() {
+        fn run(scratch: &ScratchDir) -> Result<()> {
+            fs::write(scratch.path().join("msg_0"), "message")?;
+            bail!("rebase failed");
+        }
+
+        let scratch = ScratchDir::new("repo-test-scratch").unwrap();
+        let path = scratch.path().to_path_buf();
+        assert!(run(&scratch).is_err());
+        // still there for whatever runs after the failed step
+        assert!(path.join("msg_0").exists());
+        drop(scratch);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_has_conflict_markers() {
+        assert!(has_conflict_markers("a\n<<<<<<< HEAD\nx\n=======\ny\n>>>>>>> abc\n"));
+        assert!(!has_conflict_markers("resolved\n"));
+        assert!(has_conflict_markers("a\n<<<<<<<\nx\n=======\ny\n>>>>>>>\n"));
+        assert!(has_conflict_markers("kept ours\n>>>>>>> abc\n"));
+        assert!(!has_conflict_markers("<<<<<<<< not a marker\n"));
+    }
+}
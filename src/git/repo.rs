@@ -1,12 +1,65 @@
-use anyhow::{Context, Result};
-use git2::Repository;
+use anyhow::Result;
+use git2::{ErrorCode, Repository, RepositoryOpenFlags};
+use std::env;
+use std::ffi::OsString;
 use std::path::Path;
 
+use crate::error::RepoError;
+
+/// Open the repository containing `path` (default: the current directory),
+/// searching parent directories the way git does. `GIT_CEILING_DIRECTORIES`
+/// always applies; `GIT_DIR` and `GIT_WORK_TREE` only without an explicit
+/// path, so `--path` opens the repo it names.
 pub fn open_repo(path: Option<&Path>) -> Result<Repository> {
-    match path {
-        Some(p) => Repository::open(p).context("Failed to open repository"),
-        None => Repository::open_from_env().or_else(|_| {
-            Repository::discover(".").context("Not a git repository (or any parent)")
-        }),
+    let opened = match path {
+        Some(p) => Repository::open_ext(p, RepositoryOpenFlags::empty(), ceiling_dirs()),
+        // GIT_DIR is only consulted when libgit2 picks the start path itself
+        None if env::var_os("GIT_DIR").is_some() => Repository::open_from_env(),
+        None => Repository::open_ext(".", RepositoryOpenFlags::FROM_ENV, ceiling_dirs()),
+    };
+    opened.map_err(open_error)
+}
+
+/// Open the repository at exactly `path` (its worktree root or git dir),
+/// without searching parents. Workspace scans use this so a plain directory
+/// inside another repo isn't reported as that repo.
+pub fn open_repo_exact(path: &Path) -> Result<Repository> {
+    Repository::open_ext(path, RepositoryOpenFlags::NO_SEARCH, ceiling_dirs()).map_err(open_error)
+}
+
+fn open_error(e: git2::Error) -> anyhow::Error {
+    match e.code() {
+        ErrorCode::NotFound => anyhow::Error::new(RepoError::NotARepo),
+        _ => anyhow::Error::new(e).context("Failed to open repository"),
+    }
+}
+
+/// `GIT_CEILING_DIRECTORIES`, split. libgit2 skips the variable whenever a
+/// ceiling list is passed explicitly, and `open_ext` always passes one.
+fn ceiling_dirs() -> Vec<OsString> {
+    env::var_os("GIT_CEILING_DIRECTORIES")
+        .map(|v| env::split_paths(&v).map(OsString::from).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_open_repo_from_nested_dir() {
+        let dir = TempDir::new().unwrap();
+        Repository::init(dir.path()).unwrap();
+        let nested = dir.path().join("src/deep");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        let repo = open_repo(Some(&nested)).unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        assert_eq!(repo.workdir().unwrap().canonicalize().unwrap(), root);
+
+        let err = open_repo_exact(&nested).err().unwrap();
+        assert!(matches!(crate::error::repo_error(&err), Some(RepoError::NotARepo)));
+        assert!(open_repo_exact(dir.path()).is_ok());
     }
 }
@@ -1,18 +1,84 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
+use chrono::{Local, TimeZone};
 use git2::Repository;
 
+use super::diff::get_commit_diff;
 use crate::models::StashInfo;
 
 pub fn get_stashes(repo: &mut Repository) -> Result<Vec<StashInfo>> {
-    let mut stashes = Vec::new();
+    let mut entries = Vec::new();
+    repo.stash_foreach(|index, message, oid| {
+        entries.push((index, message.to_string(), *oid));
+        true // continue iteration
+    })?;
 
-    repo.stash_foreach(|index, message, _oid| {
+    let mut stashes = Vec::new();
+    for (index, message, oid) in entries {
+        let seconds = repo.find_commit(oid)?.time().seconds();
         stashes.push(StashInfo {
             index,
-            message: message.to_string(),
+            branch: stash_branch(&message),
+            time: Local.timestamp_opt(seconds, 0).single().unwrap_or_else(Local::now),
+            message,
         });
-        true // continue iteration
-    })?;
+    }
 
     Ok(stashes)
 }
+
+/// `WIP on main: 1a2b3c4 subject` / `On main: note` → `main`
+fn stash_branch(message: &str) -> Option<String> {
+    let rest = message.strip_prefix("WIP on ").or_else(|| message.strip_prefix("On "))?;
+    let (branch, _) = rest.split_once(':')?;
+    Some(branch.to_string())
+}
+
+/// Diff of stash entry `index` (`stash@{index}`) against the commit it was
+/// made on. Untracked files stashed with `-u` aren't included.
+pub fn get_stash_diff(repo: &mut Repository, index: usize) -> Result<String> {
+    let mut found = None;
+    repo.stash_foreach(|i, _message, oid| {
+        if i == index {
+            found = Some(*oid);
+        }
+        found.is_none()
+    })?;
+
+    let Some(oid) = found else {
+        bail!("no stash entry stash@{{{}}}", index);
+    };
+    get_commit_diff(repo, oid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, init_repo};
+    use std::fs;
+
+    #[test]
+    fn test_stash_branch() {
+        assert_eq!(stash_branch("WIP on main: 1a2b3c4 init"), Some("main".into()));
+        assert_eq!(stash_branch("On feature/x: try this"), Some("feature/x".into()));
+        assert_eq!(stash_branch("custom message"), None);
+    }
+
+    #[test]
+    fn test_stash_details_and_diff() {
+        let (dir, mut repo) = init_repo();
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        commit_file(&repo, "a.txt", "one\n", "init");
+
+        fs::write(dir.path().join("a.txt"), "two\n").unwrap();
+        repo.stash_save(&sig, "try this", None).unwrap();
+
+        let stashes = get_stashes(&mut repo).unwrap();
+        assert_eq!(stashes.len(), 1);
+        let branch = repo.head().unwrap().shorthand().unwrap().to_string();
+        assert_eq!(stashes[0].branch.as_deref(), Some(branch.as_str()));
+
+        let diff = get_stash_diff(&mut repo, 0).unwrap();
+        assert!(diff.contains("-one\n+two\n"), "{}", diff);
+        assert!(get_stash_diff(&mut repo, 1).is_err());
+    }
+}
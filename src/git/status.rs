@@ -1,9 +1,35 @@
+use std::path::Path;
+
 use anyhow::Result;
 use git2::{Repository, StatusOptions};
 
+use crate::error::RepoError;
 use crate::models::WorkingTreeStatus;
 
+/// The working directory, or [`RepoError::BareRepo`] for a bare repository
+pub fn require_workdir(repo: &Repository) -> Result<&Path> {
+    repo.workdir().ok_or_else(|| RepoError::BareRepo.into())
+}
+
+/// Staged, modified, deleted or untracked files that a history rewrite or
+/// checkout could clobber
+pub fn is_dirty(repo: &Repository) -> Result<bool> {
+    require_workdir(repo)?;
+    let statuses = repo.statuses(None)?;
+    Ok(statuses.iter().any(|s| {
+        s.status().intersects(
+            git2::Status::INDEX_NEW
+                | git2::Status::INDEX_MODIFIED
+                | git2::Status::INDEX_DELETED
+                | git2::Status::WT_MODIFIED
+                | git2::Status::WT_NEW
+                | git2::Status::WT_DELETED,
+        )
+    }))
+}
+
 pub fn get_working_tree_status(repo: &Repository) -> Result<WorkingTreeStatus> {
+    require_workdir(repo)?;
     let mut opts = StatusOptions::new();
     opts.include_untracked(true)
         .recurse_untracked_dirs(true)
@@ -15,12 +41,14 @@ pub fn get_working_tree_status(repo: &Repository) -> Result<WorkingTreeStatus> {
     let mut modified = 0;
     let mut untracked = 0;
     let mut conflicted = 0;
+    let mut conflicted_files = Vec::new();
 
     for entry in statuses.iter() {
         let status = entry.status();
 
         if status.is_conflicted() {
             conflicted += 1;
+            conflicted_files.extend(entry.path().map(str::to_string));
         } else if status.is_wt_new() {
             untracked += 1;
         } else {
@@ -50,5 +78,37 @@ pub fn get_working_tree_status(repo: &Repository) -> Result<WorkingTreeStatus> {
         modified,
         untracked,
         conflicted,
+        conflicted_files,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, init_repo};
+    use git2::build::CheckoutBuilder;
+
+    #[test]
+    fn test_status_lists_conflicted_files() {
+        let (_dir, repo) = init_repo();
+        let base = commit_file(&repo, "a.txt", "base\n", "base\n");
+        commit_file(&repo, "b.txt", "clean\n", "clean\n");
+        let head_ref = repo.head().unwrap().name().unwrap().to_string();
+
+        repo.branch("other", &repo.find_commit(base).unwrap(), false).unwrap();
+        repo.set_head("refs/heads/other").unwrap();
+        repo.checkout_head(Some(CheckoutBuilder::new().force())).unwrap();
+        let theirs = commit_file(&repo, "a.txt", "theirs\n", "theirs\n");
+        repo.set_head(&head_ref).unwrap();
+        repo.checkout_head(Some(CheckoutBuilder::new().force())).unwrap();
+        commit_file(&repo, "a.txt", "ours\n", "ours\n");
+
+        assert!(get_working_tree_status(&repo).unwrap().conflicted_files.is_empty());
+        let annotated = repo.find_annotated_commit(theirs).unwrap();
+        repo.merge(&[&annotated], None, None).unwrap();
+
+        let status = get_working_tree_status(&repo).unwrap();
+        assert_eq!(status.conflicted, 1);
+        assert_eq!(status.conflicted_files, vec!["a.txt"]);
+    }
+}
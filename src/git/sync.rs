@@ -0,0 +1,418 @@
+use std::cell::RefCell;
+
+use anyhow::{bail, Context, Result};
+use git2::{
+    build::CheckoutBuilder, AnnotatedCommit, ErrorCode, FetchOptions, FetchPrune, PushOptions,
+    Repository, StatusOptions,
+};
+
+use super::fetch::{
+    git_cli, git_fetch_cli, network_error, remote_callbacks, track_transfer, tracking_tips,
+    transfer_bar, unsupported_transport,
+};
+use super::rebase::get_conflicted_files;
+use crate::error::RepoError;
+
+/// Where `repo sync` pulls from and pushes to for the current branch
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncPlan {
+    pub branch: String,
+    pub pull_remote: String,
+    pub pull_branch: String,
+    pub push_remote: String,
+    pub push_branch: String,
+    /// No upstream was configured, so `origin/<branch>` is used instead
+    pub fallback: bool,
+}
+
+impl SyncPlan {
+    pub fn pull_spec(&self) -> String {
+        format!("{}/{}", self.pull_remote, self.pull_branch)
+    }
+
+    pub fn push_spec(&self) -> String {
+        format!("{}/{}", self.push_remote, self.push_branch)
+    }
+}
+
+/// Upstream remote and the branch part of `branch.<name>.merge`, read from
+/// config rather than split out of `<remote>/<branch>`, since remote names
+/// can contain `/`
+fn configured_upstream(repo: &Repository, branch: &str) -> Option<(String, String)> {
+    let remote = repo.branch_upstream_remote(&format!("refs/heads/{}", branch)).ok()?;
+    let remote = remote.as_str()?.to_string();
+    let merge = repo.config().ok()?.get_string(&format!("branch.{}.merge", branch)).ok()?;
+    let merge = merge.strip_prefix("refs/heads/").unwrap_or(&merge).to_string();
+    // `remote = .` tracks a local branch; nothing to pull or push
+    (remote != ".").then_some((remote, merge))
+}
+
+fn ensure_remote(repo: &Repository, name: &str) -> Result<()> {
+    if repo.find_remote(name).is_err() {
+        bail!("no remote named '{}'", name);
+    }
+    Ok(())
+}
+
+/// Resolve the pull/push targets for HEAD's branch. `remote` overrides where
+/// to pull from, `push_remote` where to push; otherwise both default to the
+/// branch's upstream, then to `origin/<branch>` if it exists.
+pub fn plan_sync(repo: &Repository, remote: Option<&str>, push_remote: Option<&str>) -> Result<SyncPlan> {
+    let head = repo.head().context("No commits yet")?;
+    if !head.is_branch() {
+        bail!(RepoError::DetachedHead("sync"));
+    }
+    let branch = head.shorthand().context("Branch name is not valid UTF-8")?.to_string();
+
+    let upstream = configured_upstream(repo, &branch);
+    let fallback = upstream.is_none() && remote.is_none();
+    let (default_remote, default_branch) = match upstream {
+        Some(upstream) => upstream,
+        None => {
+            if fallback && repo.find_reference(&format!("refs/remotes/origin/{}", branch)).is_err() {
+                bail!(
+                    "no upstream configured for '{}' (set one with `git push -u origin {}` or pass --remote)",
+                    branch,
+                    branch
+                );
+            }
+            ("origin".to_string(), branch.clone())
+        }
+    };
+
+    let (pull_remote, pull_branch) = match remote {
+        Some(r) => (r.to_string(), branch.clone()),
+        None => (default_remote.clone(), default_branch.clone()),
+    };
+    let (push_remote, push_branch) = match push_remote {
+        Some(r) if r != default_remote => (r.to_string(), branch.clone()),
+        _ => (default_remote, default_branch),
+    };
+    ensure_remote(repo, &pull_remote)?;
+    ensure_remote(repo, &push_remote)?;
+
+    Ok(SyncPlan { branch, pull_remote, pull_branch, push_remote, push_branch, fallback })
+}
+
+/// What pulling did to the current branch
+#[derive(Debug, Clone, PartialEq)]
+pub enum PullOutcome {
+    UpToDate,
+    FastForward,
+    Merged,
+    Rebased(usize),
+    /// The merge stopped with these files conflicted; the merge is left in
+    /// progress for the user to resolve
+    MergeConflicts(Vec<String>),
+    /// A `--rebase` pull hit conflicts and was aborted; nothing changed
+    RebaseConflicts(Vec<String>),
+}
+
+/// Tracked or untracked changes that a pull could clobber
+pub fn has_uncommitted_changes(repo: &Repository) -> Result<bool> {
+    super::status::require_workdir(repo)?;
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(true).include_ignored(false);
+    Ok(!repo.statuses(Some(&mut opts))?.is_empty())
+}
+
+fn tracking_ref(remote: &str, branch: &str) -> String {
+    format!("refs/remotes/{}/{}", remote, branch)
+}
+
+/// Fetch `plan`'s pull branch into its remote-tracking ref. With `prune` the
+/// same fetch drops tracking refs whose branch is gone; returns those refs.
+pub fn fetch_for_sync(repo: &Repository, plan: &SyncPlan, prune: bool) -> Result<Vec<String>> {
+    let mut refspecs = vec![format!(
+        "+refs/heads/{}:{}",
+        plan.pull_branch,
+        tracking_ref(&plan.pull_remote, &plan.pull_branch)
+    )];
+    let mut remote = repo
+        .find_remote(&plan.pull_remote)
+        .ok()
+        .with_context(|| format!("no remote named '{}'", plan.pull_remote))?;
+    // pruning only considers refs the fetched refspecs map to, so take the
+    // configured ones along
+    if prune {
+        refspecs.extend(remote.fetch_refspecs()?.iter().flatten().map(String::from));
+    }
+    let before = tracking_tips(repo, &plan.pull_remote)?;
+
+    let pb = transfer_bar(&plan.pull_remote);
+    let mut callbacks = remote_callbacks(repo)?;
+    track_transfer(&mut callbacks, &pb);
+    let mut opts = FetchOptions::new();
+    opts.remote_callbacks(callbacks);
+    if prune {
+        opts.prune(FetchPrune::On);
+    }
+
+    let result = remote.fetch(&refspecs, Some(&mut opts), None);
+    pb.finish_and_clear();
+    match result {
+        Ok(()) => Ok(()),
+        Err(e) if unsupported_transport(&e) => {
+            let mut args = if prune { vec!["--prune"] } else { Vec::new() };
+            args.push(&plan.pull_remote);
+            args.extend(refspecs.iter().map(String::as_str));
+            git_fetch_cli(repo, &args)
+        }
+        Err(e) => Err(network_error(e)),
+    }
+    .with_context(|| format!("fetch from {} failed", plan.pull_remote))?;
+
+    let after = tracking_tips(repo, &plan.pull_remote)?;
+    Ok(before.into_keys().filter(|r| !after.contains_key(r)).collect())
+}
+
+fn fast_forward(repo: &Repository, branch: &str, target: &AnnotatedCommit) -> Result<()> {
+    let commit = repo.find_commit(target.id())?;
+    repo.checkout_tree(commit.as_object(), Some(CheckoutBuilder::new().safe()))?;
+    repo.find_reference(&format!("refs/heads/{}", branch))?
+        .set_target(target.id(), "sync: fast-forward")?;
+    Ok(())
+}
+
+fn merge(repo: &Repository, plan: &SyncPlan, theirs: &AnnotatedCommit) -> Result<PullOutcome> {
+    repo.merge(&[theirs], None, None)?;
+    let mut index = repo.index()?;
+    if index.has_conflicts() {
+        return Ok(PullOutcome::MergeConflicts(get_conflicted_files(repo)?));
+    }
+
+    let tree = repo.find_tree(index.write_tree()?)?;
+    let sig = repo.signature().context("Set user.name and user.email to merge")?;
+    let head = repo.head()?.peel_to_commit()?;
+    let theirs = repo.find_commit(theirs.id())?;
+    let url = repo
+        .find_remote(&plan.pull_remote)?
+        .url()
+        .unwrap_or(&plan.pull_remote)
+        .to_string();
+    let message = format!("Merge branch '{}' of {}", plan.pull_branch, url);
+    repo.commit(Some("HEAD"), &sig, &sig, &message, &tree, &[&head, &theirs])?;
+    repo.cleanup_state()?;
+    Ok(PullOutcome::Merged)
+}
+
+fn rebase(repo: &Repository, upstream: &AnnotatedCommit) -> Result<PullOutcome> {
+    let local = repo.reference_to_annotated_commit(&repo.head()?)?;
+    let sig = repo.signature().context("Set user.name and user.email to rebase")?;
+    let mut rebase = repo.rebase(Some(&local), Some(upstream), None, None)?;
+
+    let mut applied = 0;
+    while let Some(op) = rebase.next() {
+        if let Err(e) = op {
+            rebase.abort()?;
+            return Err(e.into());
+        }
+        if repo.index()?.has_conflicts() {
+            let files = get_conflicted_files(repo)?;
+            rebase.abort()?;
+            return Ok(PullOutcome::RebaseConflicts(files));
+        }
+        match rebase.commit(None, &sig, None) {
+            Ok(_) => applied += 1,
+            // already upstream; git drops these too
+            Err(e) if e.code() == ErrorCode::Applied => {}
+            Err(e) => {
+                rebase.abort()?;
+                return Err(e.into());
+            }
+        }
+    }
+    rebase.finish(Some(&sig))?;
+    Ok(PullOutcome::Rebased(applied))
+}
+
+/// Bring the fetched pull branch into HEAD: fast-forward when possible,
+/// otherwise merge, or rebase local commits onto it with `rebase`
+pub fn integrate_fetched(repo: &Repository, plan: &SyncPlan, rebase_local: bool) -> Result<PullOutcome> {
+    let fetched = repo
+        .find_reference(&tracking_ref(&plan.pull_remote, &plan.pull_branch))
+        .with_context(|| format!("{} was not fetched", plan.pull_spec()))?;
+    let theirs = repo.reference_to_annotated_commit(&fetched)?;
+    let (analysis, _) = repo.merge_analysis(&[&theirs])?;
+
+    if analysis.is_up_to_date() {
+        Ok(PullOutcome::UpToDate)
+    } else if analysis.is_fast_forward() {
+        fast_forward(repo, &plan.branch, &theirs)?;
+        Ok(PullOutcome::FastForward)
+    } else if rebase_local {
+        rebase(repo, &theirs)
+    } else {
+        merge(repo, plan, &theirs)
+    }
+}
+
+/// Push the local branch to `plan`'s push target
+pub fn push_for_sync(repo: &Repository, plan: &SyncPlan) -> Result<()> {
+    let refspec = format!("refs/heads/{}:refs/heads/{}", plan.branch, plan.push_branch);
+    let mut remote = repo
+        .find_remote(&plan.push_remote)
+        .ok()
+        .with_context(|| format!("no remote named '{}'", plan.push_remote))?;
+
+    let rejected = RefCell::new(None);
+    let mut callbacks = remote_callbacks(repo)?;
+    callbacks.push_update_reference(|name, status| {
+        if let Some(reason) = status {
+            *rejected.borrow_mut() = Some(format!("{}: {}", name, reason));
+        }
+        Ok(())
+    });
+    let mut opts = PushOptions::new();
+    opts.remote_callbacks(callbacks);
+
+    match remote.push(&[&refspec], Some(&mut opts)) {
+        Ok(()) => {}
+        Err(e) if unsupported_transport(&e) => {
+            // a rejection is "push rejected (...)" here too, not a network error
+            return git_cli(repo, &["push", &plan.push_remote, &refspec]);
+        }
+        Err(e) => return Err(network_error(e)).with_context(|| format!("push to {} failed", plan.push_remote)),
+    }
+    drop(opts);
+    if let Some(reason) = rejected.into_inner() {
+        bail!("push rejected ({})", reason);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, init_repo};
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    fn repo_on_main() -> (TempDir, Repository) {
+        let (dir, repo) = init_repo();
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        let tree = repo.find_tree(repo.index().unwrap().write_tree().unwrap()).unwrap();
+        let oid = repo.commit(Some("refs/heads/main"), &sig, &sig, "init", &tree, &[]).unwrap();
+        repo.set_head("refs/heads/main").unwrap();
+        repo.remote("origin", "https://example.com/me/fork.git").unwrap();
+        repo.remote("upstream", "https://example.com/them/project.git").unwrap();
+        repo.reference("refs/remotes/origin/main", oid, true, "test").unwrap();
+        drop(tree);
+        (dir, repo)
+    }
+
+    #[test]
+    fn test_plan_uses_configured_upstream() {
+        let (_dir, repo) = repo_on_main();
+        let mut config = repo.config().unwrap();
+        config.set_str("branch.main.remote", "origin").unwrap();
+        config.set_str("branch.main.merge", "refs/heads/trunk").unwrap();
+
+        let plan = plan_sync(&repo, None, None).unwrap();
+        assert_eq!((plan.pull_spec(), plan.push_spec()), ("origin/trunk".into(), "origin/trunk".into()));
+        assert!(!plan.fallback);
+
+        // fork workflow: pull from upstream, keep pushing to origin
+        let plan = plan_sync(&repo, Some("upstream"), None).unwrap();
+        assert_eq!((plan.pull_spec(), plan.push_spec()), ("upstream/main".into(), "origin/trunk".into()));
+
+        // remote names may contain `/`
+        repo.remote("team/fork", "https://example.com/team/fork.git").unwrap();
+        config.set_str("branch.main.remote", "team/fork").unwrap();
+        let plan = plan_sync(&repo, None, None).unwrap();
+        assert_eq!((plan.pull_remote.as_str(), plan.pull_branch.as_str()), ("team/fork", "trunk"));
+    }
+
+    #[test]
+    fn test_plan_falls_back_to_origin() {
+        let (_dir, repo) = repo_on_main();
+        let plan = plan_sync(&repo, None, Some("upstream")).unwrap();
+        assert!(plan.fallback);
+        assert_eq!((plan.pull_spec(), plan.push_spec()), ("origin/main".into(), "upstream/main".into()));
+
+        assert!(plan_sync(&repo, Some("nope"), None).is_err());
+        repo.find_reference("refs/remotes/origin/main").unwrap().delete().unwrap();
+        let err = plan_sync(&repo, None, None).unwrap_err().to_string();
+        assert!(err.contains("no upstream configured for 'main'"), "{}", err);
+    }
+
+    fn clone_with_identity(bare: &Path, dir: &Path) -> Repository {
+        let repo = Repository::clone(bare.to_str().unwrap(), dir).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        repo
+    }
+
+    #[test]
+    fn test_sync_fast_forward_push_and_rebase() {
+        let dir = TempDir::new().unwrap();
+        let bare = dir.path().join("origin.git");
+        let seed = Repository::init_bare(&bare).unwrap();
+        let (a, b) = (dir.path().join("a"), dir.path().join("b"));
+        let repo_a = clone_with_identity(&bare, &a);
+        commit_file(&repo_a, "base", "base", "base");
+        let head = repo_a.head().unwrap().shorthand().unwrap().to_string();
+        let plan = SyncPlan {
+            branch: head.clone(),
+            pull_remote: "origin".into(),
+            pull_branch: head.clone(),
+            push_remote: "origin".into(),
+            push_branch: head.clone(),
+            fallback: false,
+        };
+        push_for_sync(&repo_a, &plan).unwrap();
+        assert!(seed.find_reference(&format!("refs/heads/{}", head)).is_ok());
+
+        let repo_b = clone_with_identity(&bare, &b);
+        let tip = commit_file(&repo_a, "from-a", "from-a", "from-a");
+        push_for_sync(&repo_a, &plan).unwrap();
+        assert!(fetch_for_sync(&repo_b, &plan, false).unwrap().is_empty());
+        assert_eq!(integrate_fetched(&repo_b, &plan, false).unwrap(), PullOutcome::FastForward);
+        assert_eq!(repo_b.head().unwrap().target(), Some(tip));
+        assert!(!has_uncommitted_changes(&repo_b).unwrap());
+
+        // diverged: b's commit is replayed on top of a's
+        let upstream_tip = commit_file(&repo_a, "more-from-a", "more-from-a", "more-from-a");
+        push_for_sync(&repo_a, &plan).unwrap();
+        commit_file(&repo_b, "from-b", "from-b", "from-b");
+        fetch_for_sync(&repo_b, &plan, false).unwrap();
+        assert_eq!(integrate_fetched(&repo_b, &plan, true).unwrap(), PullOutcome::Rebased(1));
+        let rebased = repo_b.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(rebased.message(), Some("from-b"));
+        assert_eq!(rebased.parent_id(0).unwrap(), upstream_tip);
+        assert!(b.join("more-from-a").exists());
+    }
+
+    #[test]
+    fn test_sync_fetch_prunes_stale_tracking_ref() {
+        let dir = TempDir::new().unwrap();
+        let bare = dir.path().join("origin.git");
+        let seed = Repository::init_bare(&bare).unwrap();
+        let repo_a = clone_with_identity(&bare, &dir.path().join("a"));
+        let tip = commit_file(&repo_a, "base", "base", "base");
+        let head = repo_a.head().unwrap().shorthand().unwrap().to_string();
+        let plan = SyncPlan {
+            branch: head.clone(),
+            pull_remote: "origin".into(),
+            pull_branch: head.clone(),
+            push_remote: "origin".into(),
+            push_branch: head.clone(),
+            fallback: false,
+        };
+        push_for_sync(&repo_a, &plan).unwrap();
+        seed.reference("refs/heads/feature", tip, false, "test").unwrap();
+
+        let repo_b = clone_with_identity(&bare, &dir.path().join("b"));
+        assert!(repo_b.find_reference("refs/remotes/origin/feature").is_ok());
+        seed.find_reference("refs/heads/feature").unwrap().delete().unwrap();
+
+        // without prune the stale ref stays
+        assert!(fetch_for_sync(&repo_b, &plan, false).unwrap().is_empty());
+        assert!(repo_b.find_reference("refs/remotes/origin/feature").is_ok());
+
+        assert_eq!(fetch_for_sync(&repo_b, &plan, true).unwrap(), vec!["origin/feature"]);
+        assert!(repo_b.find_reference("refs/remotes/origin/feature").is_err());
+        assert!(repo_b.find_reference(&format!("refs/remotes/origin/{}", head)).is_ok());
+    }
+}
@@ -0,0 +1,177 @@
+use anyhow::{bail, Result};
+use git2::{Oid, Repository, RepositoryState, ResetType};
+
+use super::commits::commit_info;
+use super::status::get_working_tree_status;
+use crate::error::RepoError;
+use crate::models::CommitInfo;
+
+/// Where `repo undo` would take HEAD: the commit it pointed at just before
+/// the most recent rebase
+#[derive(Debug, Clone)]
+pub struct UndoTarget {
+    pub commit: CommitInfo,
+    /// Reflog message of the entry that last moved HEAD to `commit`
+    pub reflog_message: String,
+    /// Reflog selector for that entry, e.g. `HEAD@{4}`
+    pub selector: String,
+    /// Where the rebase left HEAD; undo refuses once HEAD has moved on
+    pub rebased_head: Oid,
+}
+
+fn is_rebase_entry(message: &str) -> bool {
+    message.starts_with("rebase")
+}
+
+/// `rebase (start): checkout main`, or `rebase -i (start): ...` from older git
+fn is_rebase_start(message: &str) -> bool {
+    is_rebase_entry(message) && message.contains("(start)")
+}
+
+/// Scan HEAD's reflog (newest first) for the most recent rebase and return
+/// the HEAD it started from. Back-to-back rebases are separate: the scan
+/// stops at the newest `(start)` entry.
+pub fn find_undo_target(repo: &Repository) -> Result<Option<UndoTarget>> {
+    let reflog = repo.reflog("HEAD")?;
+    let messages: Vec<String> = reflog
+        .iter()
+        .map(|e| e.message().unwrap_or("").to_string())
+        .collect();
+
+    let Some(first) = messages.iter().position(|m| is_rebase_entry(m)) else {
+        return Ok(None);
+    };
+    let run_end = messages[first..]
+        .iter()
+        .position(|m| !is_rebase_entry(m))
+        .map_or(messages.len(), |n| first + n);
+    let start = (first..run_end)
+        .find(|&i| is_rebase_start(&messages[i]))
+        .unwrap_or(run_end - 1);
+
+    let rebased_head = reflog.get(first).map(|e| e.id_new()).unwrap_or_else(Oid::zero);
+    let oid = reflog.get(start).map(|e| e.id_old()).unwrap_or_else(Oid::zero);
+    if oid.is_zero() {
+        return Ok(None);
+    }
+
+    // the entry before the rebase started is the one that produced `oid`
+    let (idx, reflog_message) = match messages.get(start + 1) {
+        Some(m) => (start + 1, m.clone()),
+        None => (start, messages[start].clone()),
+    };
+
+    Ok(Some(UndoTarget {
+        commit: commit_info(repo, oid)?,
+        reflog_message,
+        selector: format!("HEAD@{{{}}}", idx),
+        rebased_head,
+    }))
+}
+
+/// Refuse to undo over local changes, an unfinished operation, or commits
+/// made after the rebase finished (a hard reset would drop them)
+pub fn check_undo_safe(repo: &Repository, target: &UndoTarget) -> Result<()> {
+    if repo.state() != RepositoryState::Clean {
+        bail!("a merge, rebase or similar is in progress — finish or abort it first");
+    }
+    if repo.head()?.target() != Some(target.rebased_head) {
+        bail!(
+            "HEAD has moved since the rebase finished; reset by hand with `git reset --hard {}` if you mean it",
+            target.commit.short_id
+        );
+    }
+    if get_working_tree_status(repo)?.total_changes() > 0 {
+        bail!(RepoError::DirtyTree);
+    }
+    Ok(())
+}
+
+/// `git reset --hard` to the undo target
+pub fn undo_to(repo: &Repository, target: &UndoTarget) -> Result<()> {
+    check_undo_safe(repo, target)?;
+    let object = repo.find_object(target.commit.id, None)?;
+    repo.reset(&object, ResetType::Hard, None)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, init_repo};
+    use std::fs;
+    use std::path::Path;
+    use std::process::Command;
+
+    #[test]
+    fn test_undo_restores_pre_rebase_head() {
+        let (dir, repo) = init_repo();
+
+        let a = commit_file(&repo, "a.txt", "a", "a");
+        let b = commit_file(&repo, "b.txt", "b", "b");
+        let c = commit_file(&repo, "c.txt", "c", "c");
+        assert!(find_undo_target(&repo).unwrap().is_none());
+
+        // drop `b` by replaying `c` onto `a`
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(dir.path())
+            .args(["rebase", "--quiet", "--onto", &a.to_string(), &b.to_string()])
+            .status()
+            .unwrap();
+        assert!(status.success());
+        assert_ne!(repo.head().unwrap().target(), Some(c));
+
+        let target = find_undo_target(&repo).unwrap().unwrap();
+        assert_eq!(target.commit.id, c);
+        assert_eq!(target.commit.message, "c");
+        assert!(target.reflog_message.starts_with("commit"));
+
+        fs::write(dir.path().join("a.txt"), "edited").unwrap();
+        assert!(undo_to(&repo, &target).is_err());
+        fs::write(dir.path().join("a.txt"), "a").unwrap();
+
+        undo_to(&repo, &target).unwrap();
+        assert_eq!(repo.head().unwrap().target(), Some(c));
+        assert!(dir.path().join("b.txt").exists());
+    }
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git").arg("-C").arg(dir).args(args).status().unwrap();
+        assert!(status.success());
+    }
+
+    #[test]
+    fn test_undo_stops_at_newest_rebase() {
+        let (dir, repo) = init_repo();
+
+        let a = commit_file(&repo, "a.txt", "a", "a");
+        let b = commit_file(&repo, "b.txt", "b", "b");
+        commit_file(&repo, "c.txt", "c", "c");
+        commit_file(&repo, "d.txt", "d", "d");
+
+        // two rebases in a row: drop `b`, then drop the new `c`
+        git(dir.path(), &["rebase", "--quiet", "--onto", &a.to_string(), &b.to_string()]);
+        let between = repo.head().unwrap().target().unwrap();
+        git(dir.path(), &["rebase", "--quiet", "--onto", &a.to_string(), "HEAD~1"]);
+
+        let target = find_undo_target(&repo).unwrap().unwrap();
+        assert_eq!(target.commit.id, between);
+        assert!(target.reflog_message.starts_with("rebase"));
+    }
+
+    #[test]
+    fn test_undo_refuses_after_new_commits() {
+        let (dir, repo) = init_repo();
+
+        let a = commit_file(&repo, "a.txt", "a", "a");
+        let b = commit_file(&repo, "b.txt", "b", "b");
+        commit_file(&repo, "c.txt", "c", "c");
+        git(dir.path(), &["rebase", "--quiet", "--onto", &a.to_string(), &b.to_string()]);
+        let after_rebase = commit_file(&repo, "d.txt", "d", "d");
+
+        let target = find_undo_target(&repo).unwrap().unwrap();
+        assert!(undo_to(&repo, &target).is_err());
+        assert_eq!(repo.head().unwrap().target(), Some(after_rebase));
+    }
+}
@@ -1,4 +1,5 @@
 pub mod config;
+pub mod error;
 pub mod git;
 pub mod models;
 pub mod render;
@@ -11,10 +12,18 @@ pub mod craft;
 pub mod terminal;
 pub mod explore;
 pub mod workspace;
+pub mod verify;
+pub mod log;
 
-pub use git::gather_summary;
+#[cfg(test)]
+mod test_support;
+
+pub use error::RepoError;
+pub use git::{gather_summary, summary, SummaryOptions};
 pub use models::RepoSummary;
 pub use render::{render_static, run_tui};
 pub use commit::run_commit_workflow;
 pub use update::{check_for_update, perform_update};
 pub use release::create_release;
+pub use reword::{run_reword, RewordArgs};
+pub use craft::{run_craft, CraftArgs};
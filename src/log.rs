@@ -0,0 +1,129 @@
+use std::fmt::Write;
+
+use anyhow::{Context, Result};
+use colored::Colorize;
+use git2::Repository;
+
+use crate::git::{get_commit_diff, get_commits_since, get_nearest_tag};
+use crate::models::{format_time, CommitInfo, TimeMode};
+use crate::terminal::{colorize_diff_line, page_output};
+
+/// Which commits `repo log` lists
+#[derive(Debug, Clone, PartialEq)]
+pub enum LogRange {
+    /// Last N commits on HEAD
+    Recent(usize),
+    /// `<nearest tag>..HEAD`
+    SinceNearestTag,
+    /// `<tag>..HEAD`
+    SinceTag(String),
+}
+
+/// Resolve a range to its base tag (if any) and commit list
+pub fn resolve_log_range(
+    repo: &Repository,
+    range: &LogRange,
+) -> Result<(Option<String>, Vec<CommitInfo>)> {
+    match range {
+        LogRange::Recent(n) => Ok((None, get_commits_since(repo, None, Some(*n))?)),
+        LogRange::SinceNearestTag => {
+            let tag = get_nearest_tag(repo)?.context("no tags reachable from HEAD")?;
+            let commits = get_commits_since(repo, Some(&tag), None)?;
+            Ok((Some(tag), commits))
+        }
+        LogRange::SinceTag(tag) => {
+            let commits = get_commits_since(repo, Some(tag), None)?;
+            Ok((Some(tag.clone()), commits))
+        }
+    }
+}
+
+/// Colorized diff of a commit against its first parent, or a note for merges
+fn commit_patch(repo: &Repository, commit: &CommitInfo) -> Result<String> {
+    if commit.parents.len() > 1 {
+        return Ok(format!("     {}\n", "(merge commit — diff not shown)".dimmed()));
+    }
+    let diff = get_commit_diff(repo, commit.id)?;
+    let mut out = String::new();
+    for line in diff.lines() {
+        writeln!(out, "     {}", colorize_diff_line(line))?;
+    }
+    Ok(out)
+}
+
+/// `patch_limit`: show diffs (like `git log -p`) for up to that many commits
+pub fn run_log(repo: &Repository, range: LogRange, patch_limit: Option<usize>, date: TimeMode) -> Result<()> {
+    let (tag, commits) = resolve_log_range(repo, &range)?;
+    let mut out = String::new();
+
+    match &tag {
+        Some(t) => writeln!(
+            out,
+            "{} {}",
+            "LOG".bold(),
+            format!("{}..HEAD ({})", t, commits.len()).dimmed()
+        )?,
+        None => writeln!(out, "{}", "LOG".bold())?,
+    }
+
+    if commits.is_empty() {
+        writeln!(out, "   {}", "no commits".dimmed())?;
+        print!("{}", out);
+        return Ok(());
+    }
+
+    for (i, commit) in commits.iter().enumerate() {
+        let time = format_time(&commit.time, date);
+        let author_short = commit.author.split_whitespace().next().unwrap_or(&commit.author);
+        writeln!(
+            out,
+            "   {} {} {}  {}  {}",
+            "●".yellow(),
+            commit.short_id.cyan(),
+            format!("{:>4}", time).dimmed(),
+            commit.message,
+            author_short.dimmed()
+        )?;
+        if patch_limit.is_some_and(|n| i < n) {
+            out.push_str(&commit_patch(repo, commit)?);
+            out.push('\n');
+        }
+    }
+
+    match patch_limit {
+        None => print!("{}", out),
+        Some(n) => {
+            if commits.len() > n {
+                writeln!(
+                    out,
+                    "   {}",
+                    format!("diffs shown for the first {} commit(s) (use -n for more)", n).dimmed()
+                )?;
+            }
+            page_output(&out)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, init_repo};
+
+    #[test]
+    fn test_commit_patch_includes_diff() {
+        let (_dir, repo) = init_repo();
+        commit_file(&repo, "hello.txt", "hello world\n", "add hello");
+
+        let commits = get_commits_since(&repo, None, Some(1)).unwrap();
+        let patch = commit_patch(&repo, &commits[0]).unwrap();
+        assert!(patch.contains("hello.txt"));
+        assert!(patch.contains("+hello world"));
+
+        let mut merge = commits[0].clone();
+        merge.parents = vec![git2::Oid::zero(), git2::Oid::zero()];
+        assert!(commit_patch(&repo, &merge).unwrap().contains("merge commit"));
+    }
+}
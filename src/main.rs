@@ -1,14 +1,19 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
 use clap::{Parser, Subcommand};
 
 use repo_cli::config::Config;
-use repo_cli::git::{fetch_all_remotes, gather_summary, open_repo, print_fetch_warnings};
-use repo_cli::render::render_static;
+use repo_cli::error::{exit_code, EXIT_CODES};
+use repo_cli::git::{
+    fetch_all_remotes, gather_summary, new_upstream_commits, open_repo, print_fetch_warnings,
+};
+use repo_cli::models::TimeMode;
+use repo_cli::render::{render_static, RemoteView};
 use repo_cli::terminal::{restore_title, set_title, repo_display_name};
 
 #[derive(Parser, Debug)]
 #[command(name = "repo")]
 #[command(about = "A visual git repository summary tool", long_about = None)]
+#[command(after_long_help = EXIT_CODES)]
 struct Cli {
     #[command(subcommand)]
     command: Option<Command>,
@@ -17,6 +22,10 @@ struct Cli {
     #[arg(short, long, global = true)]
     interactive: bool,
 
+    /// Print the summary even when config has default_action = "tui"
+    #[arg(long = "static")]
+    static_summary: bool,
+
     /// Show full ASCII branch graph
     #[arg(long, global = true)]
     graph: bool,
@@ -37,10 +46,38 @@ struct Cli {
     #[arg(long, global = true)]
     no_fetch: bool,
 
+    /// Refetch GitHub stats instead of using the cache
+    #[arg(long, global = true)]
+    refresh: bool,
+
+    /// Count every commit for the summary stats (stops at 10000 otherwise)
+    #[arg(long, global = true)]
+    stats: bool,
+
+    /// Never check for updates (same as REPO_NO_UPDATE_CHECK=1)
+    #[arg(long, global = true)]
+    offline: bool,
+
+    /// Remote branches to list in the summary (0 = all)
+    #[arg(long, value_name = "N", default_value = "5")]
+    remote_limit: usize,
+
+    /// List remote branches with no commits in this long (e.g. 3mo, 1y)
+    #[arg(long, value_name = "AGE")]
+    stale: Option<String>,
+
+    /// Print only results (commit SHA, release URL) and errors
+    #[arg(short, long, global = true)]
+    quiet: bool,
+
     /// Show stash details (only count shown by default)
     #[arg(long, global = true)]
     stashes: bool,
 
+    /// Commit dates: relative, short or iso (default: `date_format` config)
+    #[arg(long, value_name = "FORMAT", global = true)]
+    date: Option<TimeMode>,
+
     /// Path to git repository (defaults to current directory)
     #[arg(value_name = "PATH", global = true)]
     path: Option<String>,
@@ -65,6 +102,42 @@ enum Command {
         /// Amend the last commit instead of creating a new one
         #[arg(long)]
         amend: bool,
+
+        /// After a non-interactive commit, open the message in $EDITOR and amend if changed
+        #[arg(long)]
+        edit_after: bool,
+
+        /// Pick unstaged hunks to stage first (like git add -p)
+        #[arg(short, long)]
+        patch: bool,
+
+        /// Print the generated message instead of committing (stages nothing)
+        #[arg(long, conflicts_with_all = ["patch", "edit_after"])]
+        dry_run: bool,
+
+        /// Refuse to commit if the message breaks a `[commit]` lint rule
+        #[arg(long)]
+        strict: bool,
+
+        /// Stage paths matching this pathspec first (repeatable)
+        #[arg(long, value_name = "PATHSPEC", conflicts_with = "dry_run")]
+        stage: Vec<String>,
+
+        /// Commit even with conflict markers staged or files still conflicted
+        #[arg(long)]
+        allow_conflict_markers: bool,
+
+        /// Amend even if the last commit is already pushed, without asking
+        #[arg(long, requires = "amend")]
+        force_amend: bool,
+
+        /// With --amend: keep the last commit's message and amend right away
+        #[arg(long, requires = "amend", conflicts_with_all = ["edit_after", "dry_run"])]
+        no_edit: bool,
+
+        /// Send lockfiles and other `ai_diff_exclude` matches to the AI too
+        #[arg(long)]
+        include_all: bool,
     },
 
     /// Quick commit (non-interactive, alias for `commit --no-interactive`)
@@ -80,6 +153,38 @@ enum Command {
         /// Amend the last commit instead of creating a new one
         #[arg(long)]
         amend: bool,
+
+        /// After a non-interactive commit, open the message in $EDITOR and amend if changed
+        #[arg(long)]
+        edit_after: bool,
+
+        /// Print the generated message instead of committing (stages nothing)
+        #[arg(long, conflicts_with_all = ["edit_after"])]
+        dry_run: bool,
+
+        /// Refuse to commit if the message breaks a `[commit]` lint rule
+        #[arg(long)]
+        strict: bool,
+
+        /// Stage paths matching this pathspec first (repeatable)
+        #[arg(long, value_name = "PATHSPEC", conflicts_with = "dry_run")]
+        stage: Vec<String>,
+
+        /// Commit even with conflict markers staged or files still conflicted
+        #[arg(long)]
+        allow_conflict_markers: bool,
+
+        /// Amend even if the last commit is already pushed, without asking
+        #[arg(long, requires = "amend")]
+        force_amend: bool,
+
+        /// With --amend: keep the last commit's message and amend right away
+        #[arg(long, requires = "amend", conflicts_with_all = ["edit_after", "dry_run"])]
+        no_edit: bool,
+
+        /// Send lockfiles and other `ai_diff_exclude` matches to the AI too
+        #[arg(long)]
+        include_all: bool,
     },
 
     /// Interactive commit (alias for `commit`)
@@ -95,6 +200,38 @@ enum Command {
         /// Amend the last commit instead of creating a new one
         #[arg(long)]
         amend: bool,
+
+        /// Pick unstaged hunks to stage first (like git add -p)
+        #[arg(short, long)]
+        patch: bool,
+
+        /// Print the generated message instead of committing (stages nothing)
+        #[arg(long, conflicts_with_all = ["patch"])]
+        dry_run: bool,
+
+        /// Refuse to commit if the message breaks a `[commit]` lint rule
+        #[arg(long)]
+        strict: bool,
+
+        /// Stage paths matching this pathspec first (repeatable)
+        #[arg(long, value_name = "PATHSPEC", conflicts_with = "dry_run")]
+        stage: Vec<String>,
+
+        /// Commit even with conflict markers staged or files still conflicted
+        #[arg(long)]
+        allow_conflict_markers: bool,
+
+        /// Amend even if the last commit is already pushed, without asking
+        #[arg(long, requires = "amend")]
+        force_amend: bool,
+
+        /// With --amend: keep the last commit's message and amend right away
+        #[arg(long, requires = "amend", conflicts_with = "dry_run")]
+        no_edit: bool,
+
+        /// Send lockfiles and other `ai_diff_exclude` matches to the AI too
+        #[arg(long)]
+        include_all: bool,
     },
 
     /// Check for updates and optionally self-update
@@ -121,22 +258,125 @@ enum Command {
         #[arg(long, value_name = "PATH")]
         notes_from_file: Option<String>,
 
+        /// Build notes from conventional commits since the previous tag
+        /// (generated notes if there's no tag or nothing but chores)
+        #[arg(long, conflicts_with = "notes_from_file")]
+        notes_from_commits: bool,
+
         /// Compute next version from conventional commits since last tag
         #[arg(long)]
         auto: bool,
+
+        /// Upload a file as a release asset (repeatable)
+        #[arg(long = "asset", value_name = "PATH")]
+        assets: Vec<String>,
+    },
+
+    /// Open the repository on GitHub in the browser
+    Open {
+        /// Open issue N, or the issue named in the current branch (e.g. fix/123-crash)
+        #[arg(long, value_name = "N", num_args = 0..=1, conflicts_with = "compare")]
+        issue: Option<Option<u64>>,
+
+        /// Open the compare view for BASE...HEAD (a bare BASE compares with the current branch)
+        #[arg(long, value_name = "BASE...HEAD")]
+        compare: Option<String>,
     },
 
     /// List users who starred this repository
-    Stars,
+    Stars {
+        /// Fetch and show up to N (default: first page, `list_display_limit` shown)
+        #[arg(long, value_name = "N")]
+        limit: Option<usize>,
+    },
 
     /// List forks of this repository
-    Forks,
+    Forks {
+        /// Fetch and show up to N (default: first page, `list_display_limit` shown)
+        #[arg(long, value_name = "N")]
+        limit: Option<usize>,
+    },
+
+    /// Show or change ~/.config/repo/config.toml
+    Config {
+        /// Write a config file with every default spelled out
+        #[arg(long)]
+        init: bool,
+
+        /// Print the config file location
+        #[arg(long = "path")]
+        show_path: bool,
+
+        /// Print a key's current value (dotted for tables: ai.claude.prompt)
+        #[arg(long, value_name = "KEY", conflicts_with = "set")]
+        get: Option<String>,
+
+        /// Set a key and save the file
+        #[arg(long, num_args = 2, value_names = ["KEY", "VALUE"])]
+        set: Option<Vec<String>>,
+    },
+
+    /// Create, delete or rename local branches (lists them with no action)
+    Branch {
+        /// Create a branch at HEAD
+        #[arg(long, value_name = "NAME", conflicts_with_all = ["delete", "rename"])]
+        create: Option<String>,
+
+        /// Delete a branch
+        #[arg(long, value_name = "NAME", conflicts_with = "rename")]
+        delete: Option<String>,
+
+        /// Rename a branch
+        #[arg(long, num_args = 2, value_names = ["OLD", "NEW"])]
+        rename: Option<Vec<String>>,
+
+        /// Delete unmerged branches / overwrite an existing rename target
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Switch to another local branch
+    Switch {
+        /// Branch to switch to
+        name: String,
+
+        /// Create the branch from HEAD first
+        #[arg(short = 'c', long)]
+        create: bool,
+
+        /// Switch even with local changes, discarding changes to tracked files
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Fetch one or all remotes and show which refs changed
+    Fetch {
+        /// Remote to fetch (default: all remotes)
+        #[arg(value_name = "REMOTE")]
+        remote: Option<String>,
+
+        /// Remove tracking refs for branches deleted on the remote
+        #[arg(long)]
+        prune: bool,
+    },
 
     /// Pull and push to sync with remote
     Sync {
         /// Use rebase instead of merge when pulling
         #[arg(long)]
         rebase: bool,
+
+        /// Remove tracking refs for branches deleted on the remote
+        #[arg(long)]
+        prune: bool,
+
+        /// Remote to pull from (default: the branch's upstream)
+        #[arg(long, value_name = "NAME")]
+        remote: Option<String>,
+
+        /// Remote to push to (default: the branch's upstream)
+        #[arg(long, value_name = "NAME")]
+        push_remote: Option<String>,
     },
 
     /// Quick sync (alias for `sync`)
@@ -144,8 +384,37 @@ enum Command {
         /// Use rebase instead of merge when pulling
         #[arg(long)]
         rebase: bool,
+
+        /// Remove tracking refs for branches deleted on the remote
+        #[arg(long)]
+        prune: bool,
+
+        /// Remote to pull from (default: the branch's upstream)
+        #[arg(long, value_name = "NAME")]
+        remote: Option<String>,
+
+        /// Remote to push to (default: the branch's upstream)
+        #[arg(long, value_name = "NAME")]
+        push_remote: Option<String>,
+    },
+
+    /// List files with unresolved merge conflicts
+    Conflicts {
+        /// Print each file's conflict regions
+        #[arg(long)]
+        show: bool,
+    },
+
+    /// Undo the last N commits, keeping their changes staged (git reset --soft)
+    Uncommit {
+        /// Number of commits to undo
+        #[arg(default_value = "1")]
+        count: usize,
     },
 
+    /// Reset HEAD to where it was before the most recent rebase (e.g. craft/reword)
+    Undo,
+
     /// Reword past commit messages via interactive rebase
     Reword {
         /// Auto-select last N commits
@@ -163,6 +432,10 @@ enum Command {
         /// Use $EDITOR instead of inline prompt
         #[arg(long)]
         editor: bool,
+
+        /// Reorder commits after selecting them
+        #[arg(long)]
+        reorder: bool,
     },
 
     /// Surgical commit design — reword, split, squash, reorder, drop via TUI
@@ -174,6 +447,22 @@ enum Command {
         /// Pre-select last N commits
         #[arg(long)]
         last: Option<usize>,
+
+        /// Keep merge commits (passes --rebase-merges to git rebase)
+        #[arg(long)]
+        rebase_merges: bool,
+
+        /// Pre-populate fixup/squash actions from `fixup!` / `squash!` subjects
+        #[arg(long)]
+        autosquash: bool,
+
+        /// Print the rebase todo, messages and split script without running them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Run the plan without the final confirmation prompt
+        #[arg(short = 'y', long)]
+        yes: bool,
     },
 
     /// Explore repository history and branches interactively
@@ -222,6 +511,75 @@ enum Command {
         #[command(subcommand)]
         action: Option<GroupsAction>,
     },
+
+    /// Run read-only preflight checks (for CI); exits non-zero on failure
+    Verify {
+        /// Base ref for commit checks (defaults to the branch upstream)
+        #[arg(long, value_name = "REF")]
+        base: Option<String>,
+
+        /// Don't fail when HEAD is detached (common in CI checkouts)
+        #[arg(long)]
+        allow_detached: bool,
+
+        /// Require conventional commit subjects in base..HEAD
+        #[arg(long)]
+        conventional: bool,
+    },
+
+    /// List commits on HEAD (use -n for count)
+    Log {
+        /// List commits since the nearest tag, or since TAG if given
+        #[arg(long, value_name = "TAG", num_args = 0..=1)]
+        since_tag: Option<Option<String>>,
+
+        /// Show each commit's diff (for at most -n commits), paged
+        #[arg(short, long)]
+        patch: bool,
+    },
+
+    /// Show unstaged (or with --staged, staged) changes, paged
+    Diff {
+        /// Diff the index against HEAD instead of the working tree against the index
+        #[arg(long)]
+        staged: bool,
+    },
+
+    /// List stashes with their age and branch, or show one's diff
+    Stash {
+        #[command(subcommand)]
+        action: Option<StashAction>,
+    },
+
+    /// Commits per author, like `git shortlog -sn`
+    Shortlog {
+        /// Only count commits newer than this, e.g. 2w, 3d, 6mo, 1y
+        #[arg(long, value_name = "AGE")]
+        since: Option<String>,
+    },
+
+    /// Generate a Markdown changelog from conventional commits since the last tag
+    Changelog {
+        /// Start from TAG instead of the nearest tag
+        #[arg(long, value_name = "TAG")]
+        since_tag: Option<String>,
+
+        /// Prepend the section to FILE instead of printing it
+        #[arg(long, short, value_name = "FILE")]
+        output: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum StashAction {
+    /// List stash entries (the default)
+    List,
+    /// Show the diff of a stash entry, paged
+    Show {
+        /// Stash index, as in stash@{N}
+        #[arg(default_value = "0")]
+        index: usize,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -251,22 +609,38 @@ enum GroupsAction {
     },
 }
 
-fn main() -> Result<()> {
+fn main() {
     let cli = Cli::parse();
+    repo_cli::update::set_offline(cli.offline);
+    repo_cli::terminal::set_quiet(cli.quiet);
 
     // Set terminal title based on command
     let subtitle = match &cli.command {
         Some(Command::Commit { .. }) | Some(Command::C { .. }) | Some(Command::Ic { .. }) => "commit",
         Some(Command::Update { .. }) => "update",
         Some(Command::Release { .. }) => "release",
-        Some(Command::Stars) => "stars",
-        Some(Command::Forks) => "forks",
+        Some(Command::Open { .. }) => "open",
+        Some(Command::Stars { .. }) => "stars",
+        Some(Command::Forks { .. }) => "forks",
+        Some(Command::Config { .. }) => "config",
+        Some(Command::Branch { .. }) => "branch",
+        Some(Command::Switch { .. }) => "switch",
+        Some(Command::Fetch { .. }) => "fetch",
         Some(Command::Sync { .. }) | Some(Command::S { .. }) => "sync",
+        Some(Command::Conflicts { .. }) => "conflicts",
+        Some(Command::Uncommit { .. }) => "uncommit",
+        Some(Command::Undo) => "undo",
         Some(Command::Reword { .. }) => "reword",
         Some(Command::Craft { .. }) => "craft",
         Some(Command::Explore { .. }) | Some(Command::E { .. }) => "explore",
         Some(Command::Feed { .. }) => "feed",
         Some(Command::Groups { .. }) => "groups",
+        Some(Command::Verify { .. }) => "verify",
+        Some(Command::Log { .. }) => "log",
+        Some(Command::Shortlog { .. }) => "shortlog",
+        Some(Command::Diff { .. }) => "diff",
+        Some(Command::Stash { .. }) => "stash",
+        Some(Command::Changelog { .. }) => "changelog",
         None => "",
     };
 
@@ -285,24 +659,56 @@ fn main() -> Result<()> {
     set_title(&title);
 
     let result = match cli.command {
-        Some(Command::Commit { ai, model, no_interactive, amend }) => {
-            run_commit_command(ai, model, no_interactive, amend, cli.path)
-        }
-        Some(Command::C { ai, model, amend }) => run_commit_command(ai, model, true, amend, cli.path),
-        Some(Command::Ic { ai, model, amend }) => run_commit_command(ai, model, false, amend, cli.path),
+        Some(Command::Commit {
+            ai, model, no_interactive, amend, edit_after, patch, dry_run, strict, stage, allow_conflict_markers,
+            force_amend, no_edit, include_all,
+        }) => run_commit_command(
+            ai, model, no_interactive, amend, edit_after, patch, dry_run, strict, stage, allow_conflict_markers,
+            force_amend, no_edit, include_all, cli.path,
+        ),
+        Some(Command::C {
+            ai, model, amend, edit_after, dry_run, strict, stage, allow_conflict_markers, force_amend, no_edit,
+            include_all,
+        }) => run_commit_command(
+            ai, model, true, amend, edit_after, false, dry_run, strict, stage, allow_conflict_markers, force_amend,
+            no_edit, include_all, cli.path,
+        ),
+        Some(Command::Ic {
+            ai, model, amend, patch, dry_run, strict, stage, allow_conflict_markers, force_amend, no_edit,
+            include_all,
+        }) => run_commit_command(
+            ai, model, false, amend, false, patch, dry_run, strict, stage, allow_conflict_markers, force_amend,
+            no_edit, include_all, cli.path,
+        ),
         Some(Command::Update { check }) => run_update_command(check),
-        Some(Command::Release { version, draft, dry_run, notes_from_file, auto }) => {
-            run_release_command(version, draft, dry_run, notes_from_file, auto)
+        Some(Command::Release { version, draft, dry_run, notes_from_file, notes_from_commits, auto, assets }) => {
+            run_release_command(version, draft, dry_run, notes_from_file, notes_from_commits, auto, assets)
+        }
+        Some(Command::Open { issue, compare }) => run_open_command(issue, compare, cli.path),
+        Some(Command::Stars { limit }) => run_stars_command(limit, cli.path),
+        Some(Command::Forks { limit }) => run_forks_command(limit, cli.path),
+        Some(Command::Config { init, show_path, get, set }) => {
+            run_config_command(init, show_path, get, set)
+        }
+        Some(Command::Branch { create, delete, rename, force }) => {
+            run_branch_command(create, delete, rename, force, cli.path)
         }
-        Some(Command::Stars) => run_stars_command(cli.path),
-        Some(Command::Forks) => run_forks_command(cli.path),
-        Some(Command::Sync { rebase }) => run_sync_command(rebase, cli.path),
-        Some(Command::S { rebase }) => run_sync_command(rebase, cli.path),
-        Some(Command::Reword { last, all, count, editor }) => {
-            run_reword_command(last, all, count, editor, cli.path)
+        Some(Command::Switch { name, create, force }) => {
+            run_switch_command(name, create, force, cli.path)
         }
-        Some(Command::Craft { count, last }) => {
-            run_craft_command(count, last, cli.path)
+        Some(Command::Fetch { remote, prune }) => run_fetch_command(remote, prune, cli.path),
+        Some(Command::Sync { rebase, prune, remote, push_remote })
+        | Some(Command::S { rebase, prune, remote, push_remote }) => {
+            run_sync_command(rebase, prune, remote, push_remote, cli.path)
+        }
+        Some(Command::Conflicts { show }) => run_conflicts_command(show, cli.path),
+        Some(Command::Uncommit { count }) => run_uncommit_command(count, cli.path),
+        Some(Command::Undo) => run_undo_command(cli.path),
+        Some(Command::Reword { last, all, count, editor, reorder }) => {
+            run_reword_command(last, all, count, editor, reorder, cli.path)
+        }
+        Some(Command::Craft { count, last, rebase_merges, autosquash, dry_run, yes }) => {
+            run_craft_command(count, last, rebase_merges, autosquash, dry_run, yes, cli.path)
         }
         Some(Command::Explore { tab, page_size })
         | Some(Command::E { tab, page_size }) => {
@@ -312,21 +718,51 @@ fn main() -> Result<()> {
             run_feed_command(target, filter, interactive || cli.interactive, depth, cli.no_color)
         }
         Some(Command::Groups { action }) => run_groups_command(action),
+        Some(Command::Verify { base, allow_detached, conventional }) => {
+            run_verify_command(base, allow_detached, conventional, cli.path)
+        }
+        Some(Command::Shortlog { since }) => run_shortlog_command(since, cli.path),
+        Some(Command::Diff { staged }) => run_diff_command(staged, cli.path),
+        Some(Command::Stash { action }) => run_stash_command(action, cli.date, cli.path),
+        Some(Command::Log { since_tag, patch }) => {
+            run_log_command(since_tag, patch, cli.commits, cli.date, cli.path)
+        }
+        Some(Command::Changelog { since_tag, output }) => {
+            run_changelog_command(since_tag, output, cli.path)
+        }
         None => run_summary_command(&cli),
     };
 
     restore_title();
-    result
+    if let Err(e) = result {
+        use colored::Colorize;
+        eprintln!("{} {:#}", "✗".red(), e);
+        std::process::exit(exit_code(&e));
+    }
 }
 
 fn run_summary_command(cli: &Cli) -> Result<()> {
+    use repo_cli::terminal::is_quiet;
+    use std::io::IsTerminal;
+
     let mut repo = match &cli.path {
         Some(p) => open_repo(Some(std::path::Path::new(p)))?,
         None => open_repo(None)?,
     };
 
+    let stale_before = match &cli.stale {
+        Some(s) => Some(repo_cli::models::parse_relative_cutoff(s).ok_or_else(|| {
+            anyhow::anyhow!("invalid --stale '{}' (use e.g. 30m, 12h, 2d, 2w, 3mo, 1y)", s)
+        })?),
+        None => None,
+    };
+    let remotes = RemoteView { limit: cli.remote_limit, stale_before };
+
     // Determine if we should fetch: CLI flags override config
-    let config = Config::load().unwrap_or_default();
+    let mut config = Config::load().unwrap_or_default();
+    if let Some(date) = cli.date {
+        config.date_format = date;
+    }
     let should_fetch = if cli.no_fetch {
         false
     } else if cli.fetch {
@@ -336,38 +772,92 @@ fn run_summary_command(cli: &Cli) -> Result<()> {
     };
 
     if should_fetch {
-        let repo_path = repo.workdir().unwrap_or_else(|| repo.path());
-        let warnings = fetch_all_remotes(repo_path);
+        use colored::Colorize;
+
+        let (reports, warnings) = fetch_all_remotes(&repo);
         print_fetch_warnings(&warnings);
+        if let Some((upstream, count)) = new_upstream_commits(&repo, &reports).filter(|_| !is_quiet()) {
+            println!(
+                "{} {}: +{} new commit{}",
+                "↓".cyan(),
+                upstream,
+                count,
+                if count == 1 { "" } else { "s" }
+            );
+        }
     }
 
-    let summary = gather_summary(&mut repo, cli.commits)?;
+    let summary = gather_summary(&mut repo, cli.commits, cli.refresh, cli.stats)?;
 
-    if cli.interactive {
+    let force_summary = cli.static_summary || !std::io::stdout().is_terminal();
+    if config.opens_tui(cli.interactive, force_summary) {
         use repo_cli::explore;
         explore::run_explore(repo, summary, Some("summary".to_string()), 50, &config)?;
     } else {
-        render_static(&summary, cli.graph, !cli.no_color, cli.stashes);
+        render_static(&summary, cli.graph, !cli.no_color, cli.stashes, config.date_format, remotes);
     }
 
     Ok(())
 }
 
-fn run_commit_command(ai: Option<String>, model: Option<String>, no_interactive: bool, amend: bool, path: Option<String>) -> Result<()> {
-    use repo_cli::commit::run_commit_workflow;
+#[allow(clippy::too_many_arguments)]
+fn run_commit_command(ai: Option<String>, model: Option<String>, no_interactive: bool, amend: bool, edit_after: bool, patch: bool, dry_run: bool, strict: bool, stage: Vec<String>, allow_conflict_markers: bool, force_amend: bool, no_edit: bool, include_all: bool, path: Option<String>) -> Result<()> {
+    use colored::Colorize;
+    use repo_cli::commit::{ensure_provider, run_commit_dry_run, run_commit_workflow, run_patch_staging};
+    use repo_cli::git::{has_staged_changes, require_workdir, stage_pathspec};
+    use repo_cli::terminal::is_quiet;
 
     let repo = match &path {
         Some(p) => open_repo(Some(std::path::Path::new(p)))?,
         None => open_repo(None)?,
     };
+    require_workdir(&repo)?;
+
+    if dry_run {
+        return run_commit_dry_run(&repo, ai, model, amend, strict, include_all);
+    }
+
+    // --stage and --patch change the index, so a missing provider has to
+    // fail before either runs (amend --no-edit never asks the AI)
+    if (!stage.is_empty() || patch) && !(amend && no_edit) {
+        ensure_provider(ai.as_deref())?;
+    }
+
+    if !stage.is_empty() {
+        let staged = stage_pathspec(&repo, &stage)?;
+        if !is_quiet() {
+            println!("{} Staged {} file(s):", "✓".green(), staged.len());
+            for p in &staged {
+                println!("  {}", p);
+            }
+        }
+    }
 
-    run_commit_workflow(repo, ai, model, !no_interactive, amend)
+    // nothing picked and nothing already staged: stop rather than fall into
+    // the stage-all prompt
+    if patch && !run_patch_staging(&repo)? && !has_staged_changes(&repo)? && !amend {
+        return Ok(());
+    }
+
+    run_commit_workflow(
+        repo, ai, model, !no_interactive, amend, edit_after, strict, allow_conflict_markers, force_amend, no_edit,
+        include_all,
+    )
 }
 
 fn run_update_command(check_only: bool) -> Result<()> {
     use colored::Colorize;
     use repo_cli::update;
 
+    if update::update_check_disabled() {
+        println!(
+            "{} Update checks are disabled ({}, --offline or update_check = \"never\")",
+            "!".yellow(),
+            update::NO_UPDATE_CHECK_ENV
+        );
+        return Ok(());
+    }
+
     if check_only {
         match update::check_for_update()? {
             Some(release) => {
@@ -398,27 +888,113 @@ fn run_release_command(
     draft: bool,
     dry_run: bool,
     notes_from_file: Option<String>,
+    notes_from_commits: bool,
     auto: bool,
+    assets: Vec<String>,
 ) -> Result<()> {
     repo_cli::release::create_release(repo_cli::release::ReleaseOpts {
         version,
         draft,
         dry_run,
         notes_from_file,
+        notes_from_commits,
         auto,
+        assets,
     })
 }
 
-fn run_stars_command(path: Option<String>) -> Result<()> {
+fn run_open_command(
+    issue: Option<Option<u64>>,
+    compare: Option<String>,
+    path: Option<String>,
+) -> Result<()> {
+    use anyhow::Context;
     use colored::Colorize;
-    use repo_cli::git::{get_stargazers, open_repo};
+    use repo_cli::git::{parse_compare_spec, parse_github_remote, ticket_from_branch};
 
     let repo = match &path {
         Some(p) => open_repo(Some(std::path::Path::new(p)))?,
         None => open_repo(None)?,
     };
+    let remote = parse_github_remote(&repo).context("Not a GitHub repository")?;
+    let branch = repo
+        .head()
+        .ok()
+        .filter(|h| h.is_branch())
+        .and_then(|h| h.shorthand().map(String::from));
+
+    let url = match (issue, compare) {
+        (Some(Some(n)), _) => remote.issue_url(n),
+        (Some(None), _) => {
+            let branch = branch.context("detached HEAD — pass an issue number")?;
+            let n = ticket_from_branch(&branch)
+                .with_context(|| format!("no issue number in branch `{}`", branch))?;
+            remote.issue_url(n)
+        }
+        (None, Some(spec)) => {
+            let current = branch.as_deref().unwrap_or("HEAD");
+            let (base, head) = parse_compare_spec(&spec, current);
+            remote.compare_url(base, head)
+        }
+        (None, None) => remote.web_url(),
+    };
+
+    println!("{} {}", "↗".cyan(), url);
+    open_in_browser(&url)
+}
 
-    let stargazers = get_stargazers(&repo)?;
+fn open_in_browser(url: &str) -> Result<()> {
+    use anyhow::Context;
+
+    let (program, args): (&str, Vec<&str>) = if cfg!(target_os = "macos") {
+        ("open", vec![url])
+    } else if cfg!(windows) {
+        ("cmd", vec!["/C", "start", "", url])
+    } else {
+        ("xdg-open", vec![url])
+    };
+    std::process::Command::new(program)
+        .args(&args)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to run {}", program))?;
+    Ok(())
+}
+
+/// (fetch, display) caps: `--limit` sets both, else one page is fetched and
+/// `list_display_limit` rows are shown
+fn list_limits(limit: Option<usize>) -> Result<(usize, usize)> {
+    match limit {
+        Some(0) => bail!("--limit must be at least 1"),
+        Some(n) => Ok((n, n)),
+        None => {
+            let display = Config::load().unwrap_or_default().list_display_limit;
+            Ok((display.max(100), display))
+        }
+    }
+}
+
+/// A later page failed (often the rate limit): say the list is partial
+fn warn_partial(fetched: usize, stopped: Option<&str>) {
+    use colored::Colorize;
+    if let Some(reason) = stopped {
+        eprintln!("{} stopped after {}: {}", "⚠".yellow(), fetched, reason);
+    }
+}
+
+fn run_stars_command(limit: Option<usize>, path: Option<String>) -> Result<()> {
+    use colored::Colorize;
+    use repo_cli::git::{get_stargazers, open_repo, Paged};
+    use repo_cli::render::stat::overflow_footer;
+
+    let repo = match &path {
+        Some(p) => open_repo(Some(std::path::Path::new(p)))?,
+        None => open_repo(None)?,
+    };
+
+    let (fetch, display) = list_limits(limit)?;
+    let Paged { items: stargazers, stopped } = get_stargazers(&repo, fetch)?;
 
     if stargazers.is_empty() {
         println!("{}", "No stargazers yet".dimmed());
@@ -426,27 +1002,32 @@ fn run_stars_command(path: Option<String>) -> Result<()> {
     }
 
     println!("{} ({})", "STARGAZERS".bold(), stargazers.len());
-    for user in &stargazers {
+    for user in stargazers.iter().take(display) {
         println!("   {} {}", "★".yellow(), user.login);
     }
 
-    if stargazers.len() == 100 {
-        println!("   {}", "... showing first 100".dimmed());
+    let shown = stargazers.len().min(display);
+    let maybe_more = stopped.is_none() && stargazers.len() == fetch;
+    if let Some(footer) = overflow_footer(stargazers.len(), shown, maybe_more) {
+        println!("   {}", footer.dimmed());
     }
+    warn_partial(stargazers.len(), stopped.as_deref());
 
     Ok(())
 }
 
-fn run_forks_command(path: Option<String>) -> Result<()> {
+fn run_forks_command(limit: Option<usize>, path: Option<String>) -> Result<()> {
     use colored::Colorize;
-    use repo_cli::git::{get_forks, open_repo};
+    use repo_cli::git::{get_forks, open_repo, Paged};
+    use repo_cli::render::stat::overflow_footer;
 
     let repo = match &path {
         Some(p) => open_repo(Some(std::path::Path::new(p)))?,
         None => open_repo(None)?,
     };
 
-    let forks = get_forks(&repo)?;
+    let (fetch, display) = list_limits(limit)?;
+    let Paged { items: forks, stopped } = get_forks(&repo, fetch)?;
 
     if forks.is_empty() {
         println!("{}", "No forks yet".dimmed());
@@ -454,7 +1035,7 @@ fn run_forks_command(path: Option<String>) -> Result<()> {
     }
 
     println!("{} ({})", "FORKS".bold(), forks.len());
-    for fork in &forks {
+    for fork in forks.iter().take(display) {
         let stars_str = if fork.stars > 0 {
             format!(" ★{}", fork.stars).yellow().to_string()
         } else {
@@ -463,68 +1044,276 @@ fn run_forks_command(path: Option<String>) -> Result<()> {
         println!("   {} {}{}", "⑂".dimmed(), fork.repo_name, stars_str);
     }
 
-    if forks.len() == 100 {
-        println!("   {}", "... showing first 100".dimmed());
+    let shown = forks.len().min(display);
+    let maybe_more = stopped.is_none() && forks.len() == fetch;
+    if let Some(footer) = overflow_footer(forks.len(), shown, maybe_more) {
+        println!("   {}", footer.dimmed());
     }
+    warn_partial(forks.len(), stopped.as_deref());
 
     Ok(())
 }
 
-fn run_sync_command(rebase: bool, path: Option<String>) -> Result<()> {
+fn run_config_command(
+    init: bool,
+    show_path: bool,
+    get: Option<String>,
+    set: Option<Vec<String>>,
+) -> Result<()> {
     use colored::Colorize;
-    use std::process::Command as Cmd;
 
-    let repo_path = path.as_deref().unwrap_or(".");
+    let path = Config::config_path();
+    if show_path {
+        println!("{}", path.display());
+    }
+    if init {
+        if path.exists() {
+            eprintln!("{} {} already exists", "!".yellow(), path.display());
+        } else {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            std::fs::write(&path, Config::default_file())?;
+            println!("{} wrote {}", "✓".green(), path.display());
+        }
+    }
+
+    // --set writes the file, so it must not bake in REPO_* overrides
+    let config = if set.is_some() { Config::load_file()? } else { Config::load()? };
+    if let Some(key) = get {
+        // like `git config --get`, an unset key exits 1
+        match config.get_key(&key) {
+            Some(value) => println!("{}", value),
+            None => bail!("{} is not set", key),
+        }
+    } else if let Some([key, value]) = set.as_deref() {
+        let updated = Config::set_in_file(key, value)?;
+        println!("{} {} = {}", "✓".green(), key, updated.get_key(key).unwrap_or_default());
+    } else if !init && !show_path {
+        print!("{}", toml::to_string_pretty(&config)?);
+    }
+    Ok(())
+}
 
-    // Check for uncommitted changes
-    let status = Cmd::new("git")
-        .args(["-C", repo_path, "status", "--porcelain"])
-        .output()?;
+fn run_branch_command(
+    create: Option<String>,
+    delete: Option<String>,
+    rename: Option<Vec<String>>,
+    force: bool,
+    path: Option<String>,
+) -> Result<()> {
+    use colored::Colorize;
+    use repo_cli::git::{create_branch, delete_branch, get_local_branches, rename_branch};
+    use repo_cli::terminal::is_quiet;
 
-    if !status.stdout.is_empty() {
-        eprintln!(
-            "{} uncommitted changes, stash or commit first",
-            "⚠".yellow()
-        );
+    let repo = match &path {
+        Some(p) => open_repo(Some(std::path::Path::new(p)))?,
+        None => open_repo(None)?,
+    };
+
+    let result = if let Some(name) = &create {
+        create_branch(&repo, name).map(|_| format!("created {}", name))
+    } else if let Some(name) = &delete {
+        delete_branch(&repo, name, force).map(|_| format!("deleted {}", name))
+    } else if let Some([old, new]) = rename.as_deref() {
+        rename_branch(&repo, old, new, force).map(|_| format!("renamed {} → {}", old, new))
+    } else {
+        for branch in get_local_branches(&repo)? {
+            if branch.is_head {
+                println!("{} {}", "●".cyan(), branch.name.cyan().bold());
+            } else {
+                println!("  {}", branch.name);
+            }
+        }
         return Ok(());
+    };
+
+    // a refused create/delete/rename goes to main's error output and exit code
+    let done = result?;
+    if !is_quiet() {
+        println!("{} {}", "✓".green(), done);
     }
+    Ok(())
+}
 
-    // Pull
-    print!("{} pulling...", "↓".cyan());
-    std::io::Write::flush(&mut std::io::stdout())?;
+fn run_switch_command(name: String, create: bool, force: bool, path: Option<String>) -> Result<()> {
+    use colored::Colorize;
+    use repo_cli::error::{repo_error, RepoError};
+    use repo_cli::terminal::is_quiet;
 
-    let mut pull_args = vec!["-C", repo_path, "pull"];
-    if rebase {
-        pull_args.push("--rebase");
+    let repo = match &path {
+        Some(p) => open_repo(Some(std::path::Path::new(p)))?,
+        None => open_repo(None)?,
+    };
+    if !create && repo.head().ok().and_then(|h| h.shorthand().map(String::from)).as_deref() == Some(&name) {
+        if !is_quiet() {
+            println!("{} already on {}", "·".dimmed(), name);
+        }
+        return Ok(());
     }
 
-    let pull = Cmd::new("git").args(&pull_args).output()?;
+    match repo_cli::git::switch_branch(&repo, &name, create, force) {
+        Ok(()) if is_quiet() => {}
+        Ok(()) if create => println!("{} switched to new branch {}", "✓".green(), name.cyan()),
+        Ok(()) => println!("{} switched to {}", "✓".green(), name.cyan()),
+        Err(e) if matches!(repo_error(&e), Some(RepoError::DirtyTree)) => {
+            return Err(e.context(format!("switch to {} (--force discards changes to tracked files)", name)));
+        }
+        Err(e) => return Err(e),
+    }
+    Ok(())
+}
 
-    if !pull.status.success() {
-        println!(" {}", "failed".red());
-        let stderr = String::from_utf8_lossy(&pull.stderr);
-        if !stderr.is_empty() {
-            eprintln!("{}", stderr);
+fn run_fetch_command(remote: Option<String>, prune: bool, path: Option<String>) -> Result<()> {
+    use colored::Colorize;
+    use repo_cli::error::{repo_error, RepoError};
+    use repo_cli::git::fetch_remote;
+    use repo_cli::terminal::is_quiet;
+
+    let repo = match &path {
+        Some(p) => open_repo(Some(std::path::Path::new(p)))?,
+        None => open_repo(None)?,
+    };
+    let remotes: Vec<String> = match remote {
+        Some(name) => vec![name],
+        None => repo.remotes()?.iter().flatten().map(String::from).collect(),
+    };
+    if remotes.is_empty() {
+        if !is_quiet() {
+            println!("{} no remotes configured", "·".dimmed());
         }
         return Ok(());
     }
-    println!(" {}", "ok".green());
+
+    let prune = prune || Config::load().unwrap_or_default().auto_prune;
+    let mut failed = Vec::new();
+    let mut network = false;
+    for name in &remotes {
+        let report = match fetch_remote(&repo, name, prune) {
+            Ok(report) => report,
+            Err(e) => {
+                eprintln!("{} {}: {:#}", "✗".red(), name, e);
+                network |= matches!(repo_error(&e), Some(RepoError::Network(_)));
+                failed.push(name.as_str());
+                continue;
+            }
+        };
+        if is_quiet() {
+            continue;
+        }
+        if report.updated.is_empty() && report.pruned.is_empty() {
+            println!("{} {} up to date", "·".dimmed(), name);
+            continue;
+        }
+
+        let mut counts = Vec::new();
+        if !report.updated.is_empty() {
+            counts.push(format!("{} updated", report.updated.len()));
+        }
+        if !report.pruned.is_empty() {
+            counts.push(format!("{} pruned", report.pruned.len()));
+        }
+        println!("{} {}: {}", "✓".green(), name, counts.join(", "));
+        for update in &report.updated {
+            println!("   {} {}", "·".dimmed(), update.name);
+        }
+        for r in &report.pruned {
+            println!("   {} {}", "-".red(), r.dimmed());
+        }
+    }
+
+    // the other remotes were still fetched; the exit code reports the failures
+    let summary =
+        format!("fetch failed for {} of {} remote(s): {}", failed.len(), remotes.len(), failed.join(", "));
+    if network {
+        bail!(RepoError::Network(summary));
+    } else if !failed.is_empty() {
+        bail!(summary);
+    }
+    Ok(())
+}
+
+fn run_sync_command(
+    rebase: bool,
+    prune: bool,
+    remote: Option<String>,
+    push_remote: Option<String>,
+    path: Option<String>,
+) -> Result<()> {
+    use colored::Colorize;
+    use repo_cli::error::RepoError;
+    use repo_cli::git::{
+        fetch_for_sync, has_uncommitted_changes, integrate_fetched, plan_sync, push_for_sync,
+        PullOutcome,
+    };
+    use repo_cli::terminal::is_quiet;
+
+    let repo = match &path {
+        Some(p) => open_repo(Some(std::path::Path::new(p)))?,
+        None => open_repo(None)?,
+    };
+
+    if has_uncommitted_changes(&repo)? {
+        bail!(RepoError::DirtyTree);
+    }
+
+    let plan = plan_sync(&repo, remote.as_deref(), push_remote.as_deref())?;
+    if plan.fallback && !is_quiet() {
+        println!(
+            "{} no upstream for {}; using {}",
+            "·".dimmed(),
+            plan.branch,
+            plan.pull_spec()
+        );
+    }
+
+    // Pull
+    let prune = prune || Config::load().unwrap_or_default().auto_prune;
+    let pruned = fetch_for_sync(&repo, &plan, prune)?;
+    if !pruned.is_empty() && !is_quiet() {
+        println!("{} pruned {} stale ref(s)", "✓".green(), pruned.len());
+        for name in &pruned {
+            println!("   {} {}", "·".dimmed(), name.dimmed());
+        }
+    }
+    match integrate_fetched(&repo, &plan, rebase) {
+        Ok(PullOutcome::UpToDate | PullOutcome::FastForward | PullOutcome::Merged | PullOutcome::Rebased(_))
+            if is_quiet() => {}
+        Ok(PullOutcome::UpToDate) => println!("{} {} up to date", "↓".cyan(), plan.pull_spec()),
+        Ok(PullOutcome::FastForward) => {
+            println!("{} fast-forwarded to {}", "↓".cyan(), plan.pull_spec())
+        }
+        Ok(PullOutcome::Merged) => println!("{} merged {}", "↓".cyan(), plan.pull_spec()),
+        Ok(PullOutcome::Rebased(n)) => {
+            println!("{} rebased {} commit(s) onto {}", "↓".cyan(), n, plan.pull_spec())
+        }
+        Ok(PullOutcome::MergeConflicts(files)) => {
+            bail!(
+                "merging {} stopped on conflicts in {}; resolve them and commit, then sync again",
+                plan.pull_spec(),
+                files.join(", ")
+            );
+        }
+        Ok(PullOutcome::RebaseConflicts(files)) => {
+            return Err(anyhow::Error::new(RepoError::RebaseConflict).context(format!(
+                "rebasing onto {} conflicts in {}; nothing changed",
+                plan.pull_spec(),
+                files.join(", ")
+            )));
+        }
+        Err(e) => return Err(e.context(format!("pull {}", plan.pull_spec()))),
+    }
 
     // Push
-    print!("{} pushing...", "↑".cyan());
+    if is_quiet() {
+        return push_for_sync(&repo, &plan);
+    }
+    print!("{} pushing {}...", "↑".cyan(), plan.push_spec());
     std::io::Write::flush(&mut std::io::stdout())?;
 
-    let push = Cmd::new("git")
-        .args(["-C", repo_path, "push"])
-        .output()?;
-
-    if !push.status.success() {
+    if let Err(e) = push_for_sync(&repo, &plan) {
         println!(" {}", "failed".red());
-        let stderr = String::from_utf8_lossy(&push.stderr);
-        if !stderr.is_empty() {
-            eprintln!("{}", stderr);
-        }
-        return Ok(());
+        return Err(e);
     }
     println!(" {}", "ok".green());
 
@@ -532,11 +1321,135 @@ fn run_sync_command(rebase: bool, path: Option<String>) -> Result<()> {
     Ok(())
 }
 
+fn run_uncommit_command(count: usize, path: Option<String>) -> Result<()> {
+    use colored::Colorize;
+    use repo_cli::git::uncommit;
+    use repo_cli::terminal::is_quiet;
+
+    let repo = match &path {
+        Some(p) => open_repo(Some(std::path::Path::new(p)))?,
+        None => open_repo(None)?,
+    };
+
+    let removed = uncommit(&repo, count)?;
+    if is_quiet() {
+        return Ok(());
+    }
+    println!(
+        "{} uncommitted {} commit(s), changes left staged",
+        "✓".green(),
+        removed.len()
+    );
+    for commit in &removed {
+        println!("   {} {} {}", "·".dimmed(), commit.short_id.yellow(), commit.message);
+    }
+    Ok(())
+}
+
+fn run_undo_command(path: Option<String>) -> Result<()> {
+    use colored::Colorize;
+    use repo_cli::git::{check_undo_safe, find_undo_target, undo_to};
+    use repo_cli::terminal::is_quiet;
+    use std::io::Write;
+
+    let repo = match &path {
+        Some(p) => open_repo(Some(std::path::Path::new(p)))?,
+        None => open_repo(None)?,
+    };
+
+    let Some(target) = find_undo_target(&repo)? else {
+        if !is_quiet() {
+            println!("{} no rebase found in the HEAD reflog", "·".dimmed());
+        }
+        return Ok(());
+    };
+    check_undo_safe(&repo, &target)?;
+
+    println!("{} before the last rebase:", "UNDO".bold());
+    println!(
+        "   {} {} {}",
+        "●".cyan(),
+        target.commit.short_id.yellow(),
+        target.commit.message
+    );
+    println!(
+        "   {} {}",
+        target.selector.dimmed(),
+        target.reflog_message.dimmed()
+    );
+
+    print!(
+        "{} Hard reset HEAD to {}? [y/N] ",
+        "?".cyan(),
+        target.commit.short_id
+    );
+    std::io::stdout().flush()?;
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input)?;
+    if !input.trim().eq_ignore_ascii_case("y") {
+        if !is_quiet() {
+            println!("{}", "cancelled".dimmed());
+        }
+        return Ok(());
+    }
+
+    undo_to(&repo, &target)?;
+    if !is_quiet() {
+        println!("{} HEAD reset to {}", "✓".green(), target.commit.short_id);
+    }
+    Ok(())
+}
+
+fn run_conflicts_command(show: bool, path: Option<String>) -> Result<()> {
+    use colored::Colorize;
+    use repo_cli::git::get_conflicts;
+
+    let repo = match &path {
+        Some(p) => open_repo(Some(std::path::Path::new(p)))?,
+        None => open_repo(None)?,
+    };
+
+    let conflicts = get_conflicts(&repo)?;
+    if conflicts.is_empty() {
+        println!("{} no conflicts", "✓".green());
+        return Ok(());
+    }
+
+    println!("{} ({})", "CONFLICTS".bold(), conflicts.len());
+    for file in &conflicts {
+        println!(
+            "   {} {} {}",
+            "✗".red(),
+            file.path,
+            format!("{} region(s)", file.regions.len()).dimmed()
+        );
+        if !show {
+            continue;
+        }
+        for region in &file.regions {
+            println!("\n     {} line {}", "@".cyan(), region.line);
+            println!("     {} {}", "<<<<<<<".dimmed(), region.ours_label.green());
+            for line in &region.ours {
+                println!("     {}", line.green());
+            }
+            println!("     {}", "=======".dimmed());
+            for line in &region.theirs {
+                println!("     {}", line.yellow());
+            }
+            println!("     {} {}", ">>>>>>>".dimmed(), region.theirs_label.yellow());
+        }
+        println!();
+    }
+
+    Ok(())
+}
+
 fn run_reword_command(
     last: Option<usize>,
     all: bool,
     count: usize,
     editor: bool,
+    reorder: bool,
     path: Option<String>,
 ) -> Result<()> {
     use repo_cli::reword::{run_reword, RewordArgs};
@@ -546,10 +1459,18 @@ fn run_reword_command(
         None => open_repo(None)?,
     };
 
-    run_reword(&repo, RewordArgs { last, all, count, editor })
+    run_reword(&repo, RewordArgs { last, all, count, editor, reorder })
 }
 
-fn run_craft_command(count: usize, last: Option<usize>, path: Option<String>) -> Result<()> {
+fn run_craft_command(
+    count: usize,
+    last: Option<usize>,
+    rebase_merges: bool,
+    autosquash: bool,
+    dry_run: bool,
+    yes: bool,
+    path: Option<String>,
+) -> Result<()> {
     use repo_cli::craft::{run_craft, CraftArgs};
 
     let repo = match &path {
@@ -557,7 +1478,164 @@ fn run_craft_command(count: usize, last: Option<usize>, path: Option<String>) ->
         None => open_repo(None)?,
     };
 
-    run_craft(&repo, CraftArgs { count, last })
+    run_craft(&repo, CraftArgs { count, last, rebase_merges, autosquash, dry_run, yes })
+}
+
+fn run_verify_command(
+    base: Option<String>,
+    allow_detached: bool,
+    conventional: bool,
+    path: Option<String>,
+) -> Result<()> {
+    use repo_cli::verify::{run_verify, VerifyOpts};
+
+    let repo = match &path {
+        Some(p) => open_repo(Some(std::path::Path::new(p)))?,
+        None => open_repo(None)?,
+    };
+
+    run_verify(&repo, VerifyOpts { base, allow_detached, conventional })
+}
+
+fn run_diff_command(staged: bool, path: Option<String>) -> Result<()> {
+    use colored::Colorize;
+    use repo_cli::git::{get_staged_diff, get_unstaged_diff};
+    use repo_cli::terminal::{colorize_diff_line, page_output};
+
+    let repo = match &path {
+        Some(p) => open_repo(Some(std::path::Path::new(p)))?,
+        None => open_repo(None)?,
+    };
+
+    let diff = if staged { get_staged_diff(&repo)? } else { get_unstaged_diff(&repo)? };
+    if diff.trim().is_empty() {
+        let what = if staged { "no staged changes" } else { "no unstaged changes" };
+        println!("{} {}", "·".dimmed(), what);
+        return Ok(());
+    }
+
+    let mut out = String::new();
+    for line in diff.lines() {
+        out.push_str(&colorize_diff_line(line));
+        out.push('\n');
+    }
+    page_output(&out)?;
+    Ok(())
+}
+
+fn run_stash_command(action: Option<StashAction>, date: Option<TimeMode>, path: Option<String>) -> Result<()> {
+    use colored::Colorize;
+    use repo_cli::git::{get_stash_diff, get_stashes, require_workdir};
+    use repo_cli::render::render_stash_list;
+    use repo_cli::terminal::{colorize_diff_line, is_quiet, page_output};
+
+    let mut repo = match &path {
+        Some(p) => open_repo(Some(std::path::Path::new(p)))?,
+        None => open_repo(None)?,
+    };
+    require_workdir(&repo)?;
+
+    match action.unwrap_or(StashAction::List) {
+        StashAction::List => {
+            let stashes = get_stashes(&mut repo)?;
+            if stashes.is_empty() {
+                if !is_quiet() {
+                    println!("{} no stashes", "·".dimmed());
+                }
+                return Ok(());
+            }
+            let date = date.unwrap_or_else(|| Config::load().unwrap_or_default().date_format);
+            render_stash_list(&stashes, date);
+        }
+        StashAction::Show { index } => {
+            let diff = get_stash_diff(&mut repo, index)?;
+            let mut out = String::new();
+            for line in diff.lines() {
+                out.push_str(&colorize_diff_line(line));
+                out.push('\n');
+            }
+            page_output(&out)?;
+        }
+    }
+    Ok(())
+}
+
+fn run_shortlog_command(since: Option<String>, path: Option<String>) -> Result<()> {
+    use colored::Colorize;
+    use repo_cli::git::get_shortlog;
+    use repo_cli::models::parse_relative_cutoff;
+
+    let repo = match &path {
+        Some(p) => open_repo(Some(std::path::Path::new(p)))?,
+        None => open_repo(None)?,
+    };
+
+    let cutoff = match &since {
+        Some(s) => Some(parse_relative_cutoff(s).ok_or_else(|| {
+            anyhow::anyhow!("invalid --since '{}' (use e.g. 30m, 12h, 2d, 2w, 3mo, 1y)", s)
+        })?),
+        None => None,
+    };
+
+    let authors = get_shortlog(&repo, cutoff)?;
+    match &since {
+        Some(s) => println!("{} {}", "SHORTLOG".bold(), format!("since {}", s).dimmed()),
+        None => println!("{}", "SHORTLOG".bold()),
+    }
+    if authors.is_empty() {
+        println!("   {}", "no commits".dimmed());
+        return Ok(());
+    }
+
+    let width = authors.iter().map(|(_, n)| n.to_string().len()).max().unwrap_or(1);
+    for (author, count) in &authors {
+        let (name, email) = author.split_once(" <").unwrap_or((author, ""));
+        println!(
+            "   {} {} {}",
+            format!("{:>width$}", count, width = width).cyan().bold(),
+            name,
+            format!("<{}", email).dimmed()
+        );
+    }
+    Ok(())
+}
+
+fn run_log_command(
+    since_tag: Option<Option<String>>,
+    patch: bool,
+    count: usize,
+    date: Option<TimeMode>,
+    path: Option<String>,
+) -> Result<()> {
+    use repo_cli::log::{run_log, LogRange};
+
+    let repo = match &path {
+        Some(p) => open_repo(Some(std::path::Path::new(p)))?,
+        None => open_repo(None)?,
+    };
+
+    let range = match since_tag {
+        None => LogRange::Recent(count),
+        Some(None) => LogRange::SinceNearestTag,
+        Some(Some(tag)) => LogRange::SinceTag(tag),
+    };
+    let date = date.unwrap_or_else(|| Config::load().unwrap_or_default().date_format);
+    run_log(&repo, range, patch.then_some(count), date)
+}
+
+fn run_changelog_command(
+    since_tag: Option<String>,
+    output: Option<String>,
+    path: Option<String>,
+) -> Result<()> {
+    use repo_cli::release::changelog::run_changelog;
+
+    let repo = match &path {
+        Some(p) => open_repo(Some(std::path::Path::new(p)))?,
+        None => open_repo(None)?,
+    };
+
+    run_changelog(&repo, since_tag, output)
 }
 
 fn run_explore_command(tab: Option<String>, page_size: usize, path: Option<String>) -> Result<()> {
@@ -569,7 +1647,7 @@ fn run_explore_command(tab: Option<String>, page_size: usize, path: Option<Strin
     };
 
     let config = Config::load().unwrap_or_default();
-    let summary = gather_summary(&mut repo, 5)?;
+    let summary = gather_summary(&mut repo, 5, false, false)?;
 
     explore::run_explore(repo, summary, tab, page_size, &config)
 }
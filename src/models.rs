@@ -1,9 +1,16 @@
 use chrono::{DateTime, Local};
-use git2::Oid;
+use git2::{Oid, RepositoryState};
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone)]
 pub struct RepoSummary {
+    /// `owner/name` for a GitHub origin, else the working directory's name
+    pub repo_name: String,
     pub current_branch: BranchInfo,
+    /// No working tree; `status` and `stashes` are left empty
+    pub bare: bool,
+    /// Merge, rebase, cherry-pick etc. left in progress (`repo.state()`)
+    pub state: RepositoryState,
     pub status: WorkingTreeStatus,
     pub recent_commits: Vec<CommitInfo>,
     pub local_branches: Vec<BranchInfo>,
@@ -13,13 +20,47 @@ pub struct RepoSummary {
     pub github_stars: Option<u32>,
     pub github_forks: Option<u32>,
     pub total_commits: usize,
+    /// Commits authored by the configured `user.email`
+    pub my_commits: usize,
+    /// The count stopped at the cap; `--stats` walks everything
+    pub commits_capped: bool,
     pub popular_branches: Vec<BranchCommitCount>,
 }
 
+impl RepoSummary {
+    /// `REBASE`, `MERGE`, ... while an operation is unfinished; None when clean
+    pub fn operation_in_progress(&self) -> Option<&'static str> {
+        operation_name(self.state)
+    }
+}
+
+pub fn operation_name(state: RepositoryState) -> Option<&'static str> {
+    match state {
+        RepositoryState::Clean => None,
+        RepositoryState::Merge => Some("MERGE"),
+        RepositoryState::Revert | RepositoryState::RevertSequence => Some("REVERT"),
+        RepositoryState::CherryPick | RepositoryState::CherryPickSequence => Some("CHERRY-PICK"),
+        RepositoryState::Bisect => Some("BISECT"),
+        RepositoryState::Rebase | RepositoryState::RebaseInteractive | RepositoryState::RebaseMerge => {
+            Some("REBASE")
+        }
+        RepositoryState::ApplyMailbox | RepositoryState::ApplyMailboxOrRebase => Some("AM"),
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CommitCounts {
+    pub total: usize,
+    pub mine: usize,
+    pub capped: bool,
+}
+
 #[derive(Debug, Clone)]
 pub struct BranchCommitCount {
     pub name: String,
     pub count: usize,
+    /// The walk stopped at the cap; `count` is a lower bound
+    pub capped: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -37,12 +78,14 @@ pub struct UpstreamInfo {
     pub behind: usize,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct WorkingTreeStatus {
     pub staged: usize,
     pub modified: usize,
     pub untracked: usize,
     pub conflicted: usize,
+    /// Paths of the `conflicted` entries, in status order
+    pub conflicted_files: Vec<String>,
 }
 
 impl WorkingTreeStatus {
@@ -65,6 +108,14 @@ pub struct CommitInfo {
     pub parents: Vec<Oid>,
 }
 
+impl CommitInfo {
+    /// Case-insensitive substring match on subject or author, for list filters
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        self.message.to_lowercase().contains(&query) || self.author.to_lowercase().contains(&query)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct RemoteBranchInfo {
     pub name: String,
@@ -79,6 +130,19 @@ pub struct RemoteBranchInfo {
 pub struct StashInfo {
     pub index: usize,
     pub message: String,
+    /// Branch named in the `WIP on <branch>:` message, if any
+    pub branch: Option<String>,
+    pub time: DateTime<Local>,
+}
+
+impl StashInfo {
+    /// The message without its `WIP on <branch>: ` / `On <branch>: ` prefix
+    pub fn description(&self) -> &str {
+        match &self.branch {
+            Some(_) => self.message.split_once(": ").map_or(&self.message, |(_, rest)| rest),
+            None => &self.message,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -103,9 +167,50 @@ pub struct GraphEdge {
     pub to_column: usize,
 }
 
+/// How commit dates are shown (`--date`, `date_format`)
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeMode {
+    /// `3d`, `2w`
+    #[default]
+    Relative,
+    /// `2024-05-01`
+    Short,
+    /// `2024-05-01T14:03:12+02:00`
+    Iso,
+}
+
+impl std::str::FromStr for TimeMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "relative" => Ok(Self::Relative),
+            "short" => Ok(Self::Short),
+            "iso" => Ok(Self::Iso),
+            _ => Err(format!("unknown date format '{}' (relative, short or iso)", s)),
+        }
+    }
+}
+
+pub fn format_time(dt: &DateTime<Local>, mode: TimeMode) -> String {
+    match mode {
+        TimeMode::Relative => format_relative_time(dt),
+        TimeMode::Short => dt.format("%Y-%m-%d").to_string(),
+        TimeMode::Iso => dt.format("%Y-%m-%dT%H:%M:%S%:z").to_string(),
+    }
+}
+
 pub fn format_relative_time(dt: &DateTime<Local>) -> String {
-    let now = Local::now();
-    let duration = now.signed_duration_since(*dt);
+    format_elapsed(*dt, Local::now())
+}
+
+/// `3d`, `2w`, `5mo`, `2y`: the time from `dt` to `now`. Under 30 days is
+/// shown in weeks (so 28–29 days read `4w`), months round to the nearest
+/// 365/12 days, and years start at 52 weeks.
+fn format_elapsed(dt: DateTime<Local>, now: DateTime<Local>) -> String {
+    let duration = now.signed_duration_since(dt);
+    let days = duration.num_days();
 
     if duration.num_minutes() < 1 {
         "now".to_string()
@@ -113,15 +218,51 @@ pub fn format_relative_time(dt: &DateTime<Local>) -> String {
         format!("{}m", duration.num_minutes())
     } else if duration.num_hours() < 24 {
         format!("{}h", duration.num_hours())
-    } else if duration.num_days() < 7 {
-        format!("{}d", duration.num_days())
-    } else if duration.num_weeks() < 4 {
-        format!("{}w", duration.num_weeks())
+    } else if days < 7 {
+        format!("{}d", days)
+    } else if days < 30 {
+        format!("{}w", days / 7)
+    } else if duration.num_weeks() < 52 {
+        format!("{}mo", ((days * 12 + 182) / 365).max(1))
     } else {
-        format!("{}mo", duration.num_days() / 30)
+        format!("{}y", (days / 365).max(1))
     }
 }
 
+/// `s` cut to at most `max_len` chars, ending in `...` when shortened.
+/// Counts chars rather than bytes so multi-byte text never splits mid-char.
+pub fn truncate(s: &str, max_len: usize) -> String {
+    if s.chars().count() <= max_len {
+        s.to_string()
+    } else {
+        // below 3 chars there's only room for part of the ellipsis
+        let dots = max_len.min(3);
+        let kept: String = s.chars().take(max_len - dots).collect();
+        format!("{}{}", kept, &"..."[..dots])
+    }
+}
+
+/// Cutoff for a relative age like `30m`, `12h`, `2d`, `2w`, `3mo` or `1y`
+/// (the units `format_relative_time` prints, with the same 365/12-day
+/// month), counted back from now. None for ages past chrono's range.
+pub fn parse_relative_cutoff(s: &str) -> Option<DateTime<Local>> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit())?;
+    let (num, unit) = s.split_at(split);
+    let n: i64 = num.parse().ok()?;
+    let minutes: i64 = match unit {
+        "m" => 1,
+        "h" => 60,
+        "d" => 24 * 60,
+        "w" => 7 * 24 * 60,
+        "mo" => 365 * 24 * 60 / 12,
+        "y" => 365 * 24 * 60,
+        _ => return None,
+    };
+    let duration = chrono::TimeDelta::try_minutes(n.checked_mul(minutes)?)?;
+    Local::now().checked_sub_signed(duration)
+}
+
 // --- Explore types ---
 
 #[derive(Debug, Clone, PartialEq)]
@@ -165,3 +306,69 @@ pub struct BranchDetail {
     pub status: BranchStatus,
     pub is_merged: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operation_name() {
+        assert_eq!(operation_name(RepositoryState::Clean), None);
+        assert_eq!(operation_name(RepositoryState::Merge), Some("MERGE"));
+        assert_eq!(operation_name(RepositoryState::RebaseInteractive), Some("REBASE"));
+        assert_eq!(operation_name(RepositoryState::RebaseMerge), Some("REBASE"));
+        assert_eq!(operation_name(RepositoryState::CherryPickSequence), Some("CHERRY-PICK"));
+        assert_eq!(operation_name(RepositoryState::ApplyMailbox), Some("AM"));
+    }
+
+    #[test]
+    fn test_format_elapsed_buckets() {
+        use chrono::TimeZone;
+        let now = Local.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+        let ago = |d: chrono::Duration| format_elapsed(now - d, now);
+        assert_eq!(ago(chrono::Duration::seconds(30)), "now");
+        assert_eq!(ago(chrono::Duration::minutes(59)), "59m");
+        assert_eq!(ago(chrono::Duration::hours(23)), "23h");
+        assert_eq!(ago(chrono::Duration::days(6)), "6d");
+        assert_eq!(ago(chrono::Duration::days(21)), "3w");
+        assert_eq!(ago(chrono::Duration::days(29)), "4w");
+        assert_eq!(ago(chrono::Duration::days(30)), "1mo");
+        assert_eq!(ago(chrono::Duration::days(150)), "5mo");
+        assert_eq!(ago(chrono::Duration::days(364)), "1y");
+        assert_eq!(ago(chrono::Duration::days(400)), "1y");
+        assert_eq!(ago(chrono::Duration::days(800)), "2y");
+    }
+
+    #[test]
+    fn test_format_time_modes() {
+        use chrono::TimeZone;
+        let dt = Local.with_ymd_and_hms(2024, 5, 1, 14, 3, 12).unwrap();
+        assert_eq!(format_time(&dt, TimeMode::Short), "2024-05-01");
+        assert!(format_time(&dt, TimeMode::Iso).starts_with("2024-05-01T14:03:12"));
+        assert_eq!(format_time(&dt, TimeMode::Relative), format_relative_time(&dt));
+        assert_eq!("iso".parse::<TimeMode>(), Ok(TimeMode::Iso));
+        assert!("long".parse::<TimeMode>().is_err());
+    }
+
+    #[test]
+    fn test_truncate_multibyte() {
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(truncate("fix: 修复登录页面的错误", 12), "fix: 修复登录...");
+        assert_eq!(truncate("feat: 🎉🎉🎉 launch", 10), "feat: 🎉...");
+        assert_eq!(truncate("🎉🎉🎉", 3), "🎉🎉🎉");
+        assert_eq!(truncate("abcdef", 2), "..");
+        assert_eq!(truncate("abcdef", 0), "");
+    }
+
+    #[test]
+    fn test_parse_relative_cutoff() {
+        let two_weeks = parse_relative_cutoff("2w").unwrap();
+        assert_eq!(format_relative_time(&two_weeks), "2w");
+        assert_eq!(format_relative_time(&parse_relative_cutoff("3d").unwrap()), "3d");
+        assert!(parse_relative_cutoff("3mo").unwrap() < two_weeks);
+        assert_eq!(format_relative_time(&parse_relative_cutoff("5mo").unwrap()), "5mo");
+        for bad in ["", "2", "w", "2x", "-1d", "9999999999999y", "100000000000000w", "99999999999999999999d"] {
+            assert!(parse_relative_cutoff(bad).is_none(), "{}", bad);
+        }
+    }
+}
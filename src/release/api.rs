@@ -0,0 +1,151 @@
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::error::RepoError;
+use crate::git::{open_repo, parse_github_remote};
+
+const TOKEN_ENV: &str = "GITHUB_TOKEN";
+const TIMEOUT: Duration = Duration::from_secs(30);
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(600);
+
+#[derive(Serialize, Debug)]
+struct CreateReleaseRequest<'a> {
+    tag_name: &'a str,
+    name: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    body: Option<&'a str>,
+    draft: bool,
+    generate_release_notes: bool,
+}
+
+#[derive(Deserialize)]
+struct CreateReleaseResponse {
+    html_url: String,
+    upload_url: String,
+}
+
+/// Token for the API path, if set
+pub fn token() -> Option<String> {
+    std::env::var(TOKEN_ENV).ok().filter(|t| !t.trim().is_empty())
+}
+
+/// Create a GitHub release via the REST API (no gh CLI needed)
+pub fn create_release(
+    version: &str,
+    title: Option<&str>,
+    notes: Option<&str>,
+    draft: bool,
+    assets: &[String],
+) -> Result<String> {
+    let token = token().ok_or_else(|| anyhow!("{} not set", TOKEN_ENV))?;
+
+    let repo = open_repo(None)?;
+    let remote = parse_github_remote(&repo).context("origin is not a GitHub remote")?;
+
+    let tag = if version.starts_with('v') {
+        version.to_string()
+    } else {
+        format!("v{}", version)
+    };
+    let default_title = format!("Release {}", tag);
+    let request = build_request(&tag, title.unwrap_or(&default_title), notes, draft);
+
+    let url = format!(
+        "{}/repos/{}/{}/releases",
+        remote.api_base(),
+        remote.owner,
+        remote.name
+    );
+    let client = reqwest::blocking::Client::new();
+    let resp = client
+        .post(&url)
+        .header("User-Agent", "repo-cli")
+        .header("Accept", "application/vnd.github+json")
+        .bearer_auth(&token)
+        .timeout(TIMEOUT)
+        .json(&request)
+        .send()
+        .map_err(|e| RepoError::Network(e.to_string()))
+        .context("Failed to reach GitHub API")?;
+
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let body = resp.text().unwrap_or_default();
+        return Err(RepoError::Network(format!("Failed to create release ({}): {}", status, body)).into());
+    }
+
+    let created: CreateReleaseResponse = resp.json().context("Unexpected GitHub API response")?;
+
+    // upload_url is a URI template: https://uploads.github.com/.../assets{?name,label}
+    let upload_base = created.upload_url.split('{').next().unwrap_or(&created.upload_url);
+    for asset in assets {
+        let path = Path::new(asset);
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| asset.clone());
+        let data = fs::read(path).with_context(|| format!("Failed to read asset: {}", asset))?;
+        let resp = client
+            .post(upload_base)
+            .query(&[("name", &name)])
+            .header("User-Agent", "repo-cli")
+            .header("Content-Type", "application/octet-stream")
+            .bearer_auth(&token)
+            .timeout(UPLOAD_TIMEOUT)
+            .body(data)
+            .send()
+            .map_err(|e| RepoError::Network(e.to_string()))
+            .with_context(|| format!("Failed to upload asset: {}", name))?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            return Err(RepoError::Network(format!("Failed to upload {} ({})", name, status)).into());
+        }
+    }
+
+    Ok(created.html_url)
+}
+
+/// Without notes, ask GitHub to generate them (same as `gh --generate-notes`)
+fn build_request<'a>(
+    tag: &'a str,
+    title: &'a str,
+    notes: Option<&'a str>,
+    draft: bool,
+) -> CreateReleaseRequest<'a> {
+    CreateReleaseRequest {
+        tag_name: tag,
+        name: title,
+        body: notes,
+        draft,
+        generate_release_notes: notes.is_none(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_request_payload() {
+        let req = build_request("v1.0.0", "Release v1.0.0", Some("## Fixes"), true);
+        let with_notes = serde_json::to_value(req).unwrap();
+        assert_eq!(
+            with_notes,
+            serde_json::json!({
+                "tag_name": "v1.0.0",
+                "name": "Release v1.0.0",
+                "body": "## Fixes",
+                "draft": true,
+                "generate_release_notes": false,
+            })
+        );
+
+        let req = build_request("v1.0.0", "Release v1.0.0", None, false);
+        let generated = serde_json::to_value(req).unwrap();
+        assert!(generated.get("body").is_none());
+        assert_eq!(generated["generate_release_notes"], true);
+    }
+}
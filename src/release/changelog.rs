@@ -0,0 +1,163 @@
+use anyhow::{Context, Result};
+use chrono::Local;
+use colored::Colorize;
+use git2::Repository;
+use std::fs;
+use std::path::Path;
+
+use crate::commit::parse_conventional;
+use crate::git::{get_commits_since, get_nearest_tag};
+
+/// Render one Markdown section from `(short_sha, subject)` pairs, grouped
+/// into Breaking / Features / Fixes / Other
+pub fn build_changelog(heading: &str, commits: &[(String, String)]) -> String {
+    let mut breaking = Vec::new();
+    let mut features = Vec::new();
+    let mut fixes = Vec::new();
+    let mut other = Vec::new();
+
+    for (sha, subject) in commits {
+        match parse_conventional(subject) {
+            Some(c) => {
+                let text = match c.scope {
+                    Some(scope) => format!("**{}:** {}", scope, c.description),
+                    None => c.description.to_string(),
+                };
+                let bullet = format!("- {} ({})", text, sha);
+                if c.breaking {
+                    breaking.push(bullet);
+                } else if c.kind == "feat" {
+                    features.push(bullet);
+                } else if c.kind == "fix" {
+                    fixes.push(bullet);
+                } else {
+                    other.push(bullet);
+                }
+            }
+            None => other.push(format!("- {} ({})", subject, sha)),
+        }
+    }
+
+    let mut out = format!("## {}\n", heading);
+    for (title, items) in [
+        ("Breaking", breaking),
+        ("Features", features),
+        ("Fixes", fixes),
+        ("Other", other),
+    ] {
+        if items.is_empty() {
+            continue;
+        }
+        out.push_str(&format!("\n### {}\n\n", title));
+        for item in items {
+            out.push_str(&item);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Insert `section` above existing entries, keeping a leading `# ` title in place
+fn prepend_section(existing: &str, section: &str) -> String {
+    if let Some(rest) = existing.strip_prefix("# ") {
+        let (title, body) = rest.split_once('\n').unwrap_or((rest, ""));
+        let body = body.trim_start_matches('\n');
+        if body.is_empty() {
+            return format!("# {}\n\n{}", title, section);
+        }
+        return format!("# {}\n\n{}\n{}", title, section, body);
+    }
+    if existing.trim().is_empty() {
+        return section.to_string();
+    }
+    format!("{}\n{}", section, existing)
+}
+
+/// Print a changelog section for `tag..HEAD` (nearest tag by default), or
+/// prepend it to `output`
+pub fn run_changelog(
+    repo: &Repository,
+    since_tag: Option<String>,
+    output: Option<String>,
+) -> Result<()> {
+    let tag = match since_tag {
+        Some(t) => Some(t),
+        None => get_nearest_tag(repo)?,
+    };
+    let commits: Vec<(String, String)> = get_commits_since(repo, tag.as_deref(), None)?
+        .into_iter()
+        .map(|c| (c.short_id, c.message))
+        .collect();
+
+    let date = Local::now().format("%Y-%m-%d");
+    let heading = match &tag {
+        Some(t) => format!("Unreleased (since {}) - {}", t, date),
+        None => format!("Unreleased - {}", date),
+    };
+    let section = build_changelog(&heading, &commits);
+
+    match output {
+        Some(path) => {
+            let path = Path::new(&path);
+            let existing = if path.exists() {
+                fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read {}", path.display()))?
+            } else {
+                String::new()
+            };
+            fs::write(path, prepend_section(&existing, &section))
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            println!(
+                "{} Added {} commit(s) to {}",
+                "✓".green(),
+                commits.len(),
+                path.display()
+            );
+        }
+        None => print!("{}", section),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_changelog_groups() {
+        let commits: Vec<(String, String)> = [
+            ("a1b2c3d", "feat(log): add --since-tag"),
+            ("b2c3d4e", "fix: handle empty repo"),
+            ("c3d4e5f", "refactor!: rename config keys"),
+            ("d4e5f6a", "docs: update readme"),
+            ("e5f6a7b", "Initial commit"),
+            ("f6a7b8c", "feat: add changelog"),
+        ]
+        .iter()
+        .map(|(sha, msg)| (sha.to_string(), msg.to_string()))
+        .collect();
+
+        let out = build_changelog("v0.2.0", &commits);
+        assert_eq!(
+            out,
+            "## v0.2.0\n\
+             \n### Breaking\n\n- rename config keys (c3d4e5f)\n\
+             \n### Features\n\n- **log:** add --since-tag (a1b2c3d)\n- add changelog (f6a7b8c)\n\
+             \n### Fixes\n\n- handle empty repo (b2c3d4e)\n\
+             \n### Other\n\n- update readme (d4e5f6a)\n- Initial commit (e5f6a7b)\n"
+        );
+    }
+
+    #[test]
+    fn test_prepend_section() {
+        let section = "## New\n\n- x\n";
+        assert_eq!(prepend_section("", section), section);
+        assert_eq!(
+            prepend_section("# Changelog\n\n## Old\n", section),
+            "# Changelog\n\n## New\n\n- x\n\n## Old\n"
+        );
+        assert_eq!(prepend_section("## Old\n", section), "## New\n\n- x\n\n## Old\n");
+        assert_eq!(prepend_section("# Changelog\n", section), "# Changelog\n\n## New\n\n- x\n");
+    }
+}
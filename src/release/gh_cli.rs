@@ -33,6 +33,7 @@ pub fn create_release(
     title: Option<&str>,
     notes: Option<&str>,
     draft: bool,
+    assets: &[String],
 ) -> Result<String> {
     check_gh_cli()?;
 
@@ -68,6 +69,11 @@ pub fn create_release(
         args.push("--draft");
     }
 
+    // gh uploads trailing positional paths as release assets
+    for asset in assets {
+        args.push(asset);
+    }
+
     let output = Command::new("gh")
         .args(&args)
         .output()
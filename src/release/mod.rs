@@ -1,15 +1,23 @@
+pub mod api;
+pub mod changelog;
 pub mod gh_cli;
 
 use anyhow::{bail, Context, Result};
 use colored::Colorize;
-use std::process::Command;
+use git2::Repository;
+
+use crate::commit::parse_conventional;
+use crate::git::{get_commits_since, get_nearest_tag, open_repo};
+use crate::terminal::is_quiet;
 
 pub struct ReleaseOpts {
     pub version: Option<String>,
     pub draft: bool,
     pub dry_run: bool,
     pub notes_from_file: Option<String>,
+    pub notes_from_commits: bool,
     pub auto: bool,
+    pub assets: Vec<String>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -22,8 +30,9 @@ pub enum Bump {
 
 /// Create a new GitHub release
 pub fn create_release(opts: ReleaseOpts) -> Result<()> {
+    let repo = open_repo(None)?;
     // Resolve version
-    let (version, auto_ctx) = resolve_version(&opts)?;
+    let (version, auto_ctx) = resolve_version(&repo, &opts)?;
 
     let tag = if version.starts_with('v') {
         version.clone()
@@ -31,34 +40,77 @@ pub fn create_release(opts: ReleaseOpts) -> Result<()> {
         format!("v{}", version)
     };
 
+    check_assets(&opts.assets)?;
+
     // Resolve notes
-    let notes: Option<String> = match &opts.notes_from_file {
-        Some(path) => Some(
-            std::fs::read_to_string(path)
-                .with_context(|| format!("Failed to read notes file: {}", path))?,
+    let (notes, notes_source): (Option<String>, &str) = match &opts.notes_from_file {
+        Some(path) => (
+            Some(
+                std::fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read notes file: {}", path))?,
+            ),
+            "(from file)",
         ),
-        None => None,
+        None if opts.notes_from_commits => {
+            let notes = match get_nearest_tag(&repo)? {
+                Some(prev) => build_release_notes(&commits_since(&repo, Some(&prev))?),
+                // No prior tag: nothing to diff against
+                None => String::new(),
+            };
+            // Nothing worth listing (no prior tag, or only chores): let gh generate notes
+            if notes.is_empty() {
+                (None, "")
+            } else {
+                (Some(notes), "(from commits)")
+            }
+        }
+        None => (None, ""),
     };
 
     // Dry run: print plan and exit
     if opts.dry_run {
-        print_plan(&tag, &opts, notes.as_deref(), auto_ctx.as_ref());
+        print_plan(&tag, &opts, notes.as_deref(), notes_source, auto_ctx.as_ref());
         return Ok(());
     }
 
-    println!("{}", "Checking gh CLI...".cyan());
-    gh_cli::check_gh_cli()?;
+    let quiet = is_quiet();
+    if !quiet {
+        println!("{}", "Checking gh CLI...".cyan());
+    }
+    // Prefer gh; fall back to the REST API when it's missing but a token is set
+    let use_gh = match gh_cli::check_gh_cli() {
+        Ok(()) => true,
+        Err(e) if api::token().is_some() => {
+            println!("{} {}, using GitHub API (GITHUB_TOKEN)", "!".yellow(), e);
+            false
+        }
+        Err(e) => return Err(e.context("set GITHUB_TOKEN to create releases without gh")),
+    };
 
-    println!(
-        "Creating {} release {}...",
-        if opts.draft { "draft" } else { "public" },
-        tag.green()
-    );
+    if !quiet {
+        println!(
+            "Creating {} release {}...",
+            if opts.draft { "draft" } else { "public" },
+            tag.green()
+        );
+    }
 
-    let url = gh_cli::create_release(&version, None, notes.as_deref(), opts.draft)?;
+    let url = if use_gh {
+        gh_cli::create_release(&version, None, notes.as_deref(), opts.draft, &opts.assets)?
+    } else {
+        api::create_release(&version, None, notes.as_deref(), opts.draft, &opts.assets)?
+    };
+
+    if quiet {
+        println!("{}", url);
+        return Ok(());
+    }
 
     println!("\n{} Release created!", "✓".green());
     println!("  {}", url.cyan());
+    for name in asset_names(&opts.assets) {
+        println!("  {} {}", "↑".green(), name);
+    }
 
     if opts.draft {
         println!(
@@ -75,19 +127,45 @@ pub fn create_release(opts: ReleaseOpts) -> Result<()> {
     Ok(())
 }
 
+/// Fail up front, listing every asset path that doesn't exist
+fn check_assets(assets: &[String]) -> Result<()> {
+    let missing: Vec<&str> = assets
+        .iter()
+        .filter(|a| !std::path::Path::new(a).is_file())
+        .map(|a| a.as_str())
+        .collect();
+    if !missing.is_empty() {
+        bail!("asset file(s) not found: {}", missing.join(", "));
+    }
+    Ok(())
+}
+
+fn asset_names(assets: &[String]) -> Vec<String> {
+    assets
+        .iter()
+        .map(|a| {
+            std::path::Path::new(a)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| a.clone())
+        })
+        .collect()
+}
+
 struct AutoContext {
     from_tag: String,
     bump: Bump,
     commits: Vec<String>,
 }
 
-fn resolve_version(opts: &ReleaseOpts) -> Result<(String, Option<AutoContext>)> {
+fn resolve_version(repo: &Repository, opts: &ReleaseOpts) -> Result<(String, Option<AutoContext>)> {
     match (&opts.version, opts.auto) {
         (Some(_), true) => bail!("--auto cannot be combined with an explicit version"),
         (Some(v), false) => Ok((v.clone(), None)),
         (None, true) => {
-            let (from_tag, current) = latest_tag_and_version()?;
-            let commits = commits_since(&from_tag)?;
+            let (tag, current) = latest_tag_and_version(repo)?;
+            let commits = commits_since(repo, tag.as_deref())?;
+            let from_tag = tag.unwrap_or_else(|| "v0.0.0".to_string());
             if commits.is_empty() {
                 bail!("no commits since {}", from_tag);
             }
@@ -108,81 +186,71 @@ fn resolve_version(opts: &ReleaseOpts) -> Result<(String, Option<AutoContext>)>
     }
 }
 
-fn latest_tag_and_version() -> Result<(String, String)> {
-    let output = Command::new("git")
-        .args(["describe", "--tags", "--abbrev=0"])
-        .output()
-        .context("Failed to run git describe")?;
-
-    let tag = if output.status.success() {
-        String::from_utf8_lossy(&output.stdout).trim().to_string()
-    } else {
-        "v0.0.0".to_string()
-    };
-    let version = tag.strip_prefix('v').unwrap_or(&tag).to_string();
+/// Latest tag (`v0.0.0` when there is none) and its version without the `v`
+fn latest_tag_and_version(repo: &Repository) -> Result<(Option<String>, String)> {
+    let tag = get_nearest_tag(repo)?;
+    let name = tag.as_deref().unwrap_or("v0.0.0");
+    let version = name.strip_prefix('v').unwrap_or(name).to_string();
     Ok((tag, version))
 }
 
-fn commits_since(tag: &str) -> Result<Vec<String>> {
-    let range = format!("{}..HEAD", tag);
-    let output = Command::new("git")
-        .args(["log", &range, "--pretty=format:%s"])
-        .output()
-        .context("Failed to run git log")?;
-
-    if !output.status.success() {
-        // Fall back to full log (fresh repo with no prior tag)
-        let full = Command::new("git")
-            .args(["log", "--pretty=format:%s"])
-            .output()
-            .context("Failed to run git log fallback")?;
-        return Ok(String::from_utf8_lossy(&full.stdout)
-            .lines()
-            .map(|l| l.to_string())
-            .filter(|l| !l.is_empty())
-            .collect());
-    }
-
-    Ok(String::from_utf8_lossy(&output.stdout)
-        .lines()
-        .map(|l| l.to_string())
-        .filter(|l| !l.is_empty())
+/// Subjects of the commits in `tag..HEAD`, or all of HEAD without a tag
+fn commits_since(repo: &Repository, tag: Option<&str>) -> Result<Vec<String>> {
+    Ok(get_commits_since(repo, tag, None)?
+        .into_iter()
+        .map(|c| c.message)
+        .filter(|m| !m.is_empty())
         .collect())
 }
 
 fn classify_bump(commits: &[String]) -> Bump {
     let mut best = Bump::None;
-    for msg in commits {
-        if msg.starts_with("BREAKING CHANGE:") || has_type_bang(msg) {
+    for c in commits.iter().filter_map(|msg| parse_conventional(msg)) {
+        if c.breaking {
             return Bump::Major;
         }
-        if has_prefix(msg, "feat") {
+        if c.kind == "feat" {
             best = Bump::Minor;
-        } else if best == Bump::None
-            && (has_prefix(msg, "fix") || has_prefix(msg, "perf") || has_prefix(msg, "refactor"))
-        {
+        } else if best == Bump::None && matches!(c.kind, "fix" | "perf" | "refactor") {
             best = Bump::Patch;
         }
     }
     best
 }
 
-/// Matches `<prefix>:` or `<prefix>(scope):`
-fn has_prefix(s: &str, prefix: &str) -> bool {
-    let Some(rest) = s.strip_prefix(prefix) else {
-        return false;
-    };
-    rest.starts_with(':') || (rest.starts_with('(') && rest.contains("):"))
-}
+/// Group commit subjects into Features / Fixes / Other markdown sections.
+/// `chore:` and `[skip ci]` commits are left out.
+fn build_release_notes(commits: &[String]) -> String {
+    let mut features = Vec::new();
+    let mut fixes = Vec::new();
+    let mut other = Vec::new();
 
-/// Matches `<type>!:` or `<type>(scope)!:` (any lowercase type)
-fn has_type_bang(s: &str) -> bool {
-    let type_end = s.find(|c: char| !c.is_ascii_lowercase()).unwrap_or(0);
-    if type_end == 0 {
-        return false;
+    for msg in commits {
+        let kind = parse_conventional(msg).map(|c| c.kind);
+        if kind == Some("chore") || msg.contains("[skip ci]") {
+            continue;
+        }
+        match kind {
+            Some("feat") => features.push(msg),
+            Some("fix") => fixes.push(msg),
+            _ => other.push(msg),
+        }
     }
-    let rest = &s[type_end..];
-    rest.starts_with("!:") || (rest.starts_with('(') && rest.contains(")!:"))
+
+    let mut notes = String::new();
+    for (title, items) in [("Features", features), ("Fixes", fixes), ("Other", other)] {
+        if items.is_empty() {
+            continue;
+        }
+        if !notes.is_empty() {
+            notes.push('\n');
+        }
+        notes.push_str(&format!("## {}\n\n", title));
+        for msg in items {
+            notes.push_str(&format!("- {}\n", msg));
+        }
+    }
+    notes
 }
 
 fn apply_bump(current: &str, bump: Bump) -> Result<String> {
@@ -212,7 +280,13 @@ fn apply_bump(current: &str, bump: Bump) -> Result<String> {
     Ok(format!("{}.{}.{}", major, minor, patch))
 }
 
-fn print_plan(tag: &str, opts: &ReleaseOpts, notes: Option<&str>, auto: Option<&AutoContext>) {
+fn print_plan(
+    tag: &str,
+    opts: &ReleaseOpts,
+    notes: Option<&str>,
+    notes_source: &str,
+    auto: Option<&AutoContext>,
+) {
     println!("{}", "── dry run ──".cyan().bold());
     println!("  tag:     {}", tag.green());
     println!(
@@ -230,9 +304,12 @@ fn print_plan(tag: &str, opts: &ReleaseOpts, notes: Option<&str>, auto: Option<&
             println!("    {} {}", "·".dimmed(), c);
         }
     }
+    if !opts.assets.is_empty() {
+        println!("  assets:  {}", asset_names(&opts.assets).join(", "));
+    }
     match notes {
         Some(body) => {
-            println!("  notes:   {}", "(from file)".dimmed());
+            println!("  notes:   {}", notes_source.dimmed());
             println!("{}", "─".repeat(40).dimmed());
             for line in body.lines().take(20) {
                 println!("  {}", line);
@@ -249,3 +326,58 @@ fn print_plan(tag: &str, opts: &ReleaseOpts, notes: Option<&str>, auto: Option<&
     }
     println!("\n{} Not calling gh.", "→".yellow());
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subjects(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_build_release_notes_sections() {
+        let notes = build_release_notes(&subjects(&[
+            "feat(tui): add help overlay",
+            "fix: handle empty repo",
+            "chore: bump deps",
+            "docs: update readme",
+            "feat: add verify command",
+            "ci: tweak workflow [skip ci]",
+        ]));
+        assert_eq!(
+            notes,
+            "## Features\n\n- feat(tui): add help overlay\n- feat: add verify command\n\n\
+             ## Fixes\n\n- fix: handle empty repo\n\n\
+             ## Other\n\n- docs: update readme\n"
+        );
+    }
+
+    #[test]
+    fn test_classify_bump() {
+        assert_eq!(classify_bump(&subjects(&["docs: readme", "fix(git): typo"])), Bump::Patch);
+        assert_eq!(classify_bump(&subjects(&["fix: a", "feat(tui): b"])), Bump::Minor);
+        assert_eq!(classify_bump(&subjects(&["fix: a", "refactor(core)!: rename"])), Bump::Major);
+        assert_eq!(classify_bump(&subjects(&["chore: deps", "Initial commit"])), Bump::None);
+    }
+
+    #[test]
+    fn test_check_assets_lists_missing() {
+        let dir = tempfile::tempdir().unwrap();
+        let present = dir.path().join("repo-linux.tar.gz");
+        std::fs::write(&present, b"bin").unwrap();
+        let present = present.to_string_lossy().to_string();
+
+        assert!(check_assets(&[present.clone()]).is_ok());
+        let err = check_assets(&[present, "a.zip".into(), "b.zip".into()]).unwrap_err();
+        assert_eq!(err.to_string(), "asset file(s) not found: a.zip, b.zip");
+        assert_eq!(asset_names(&["dist/repo.zip".into()]), vec!["repo.zip"]);
+    }
+
+    #[test]
+    fn test_build_release_notes_omits_empty_sections() {
+        let notes = build_release_notes(&subjects(&["fix: one", "chore(release): v1.0.0"]));
+        assert_eq!(notes, "## Fixes\n\n- fix: one\n");
+        assert!(build_release_notes(&subjects(&["chore: only"])).is_empty());
+    }
+}
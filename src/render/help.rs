@@ -0,0 +1,102 @@
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+/// A key and what it does, shared by TUI footers and the `?` overlay
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyHelp {
+    pub key: String,
+    pub desc: &'static str,
+}
+
+impl KeyHelp {
+    pub fn new(key: impl Into<String>, desc: &'static str) -> Self {
+        Self { key: key.into(), desc }
+    }
+
+    pub fn list(keys: &[(&str, &'static str)]) -> Vec<Self> {
+        keys.iter().map(|(k, d)| Self::new(*k, d)).collect()
+    }
+}
+
+/// Footer line: `key:desc  key:desc ...`
+pub fn footer_spans(keys: &[KeyHelp]) -> Vec<Span<'static>> {
+    keys.iter()
+        .enumerate()
+        .flat_map(|(i, k)| {
+            let mut spans = vec![
+                Span::styled(k.key.clone(), Style::default().fg(Color::Cyan)),
+                Span::styled(":", Style::default().fg(Color::Gray)),
+                Span::styled(k.desc, Style::default().fg(Color::White)),
+            ];
+            if i < keys.len() - 1 {
+                spans.push(Span::raw("  "));
+            }
+            spans
+        })
+        .collect()
+}
+
+/// Overlay body: one aligned `key  description` row per binding
+pub fn help_lines(keys: &[KeyHelp]) -> Vec<Line<'static>> {
+    let width = keys.iter().map(|k| k.key.chars().count()).max().unwrap_or(0);
+    let mut lines: Vec<Line> = keys
+        .iter()
+        .map(|k| {
+            Line::from(vec![
+                Span::styled(
+                    format!(" {:<width$}  ", k.key, width = width),
+                    Style::default().fg(Color::Cyan).bold(),
+                ),
+                Span::raw(k.desc),
+            ])
+        })
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        " ?/Esc  close",
+        Style::default().fg(Color::DarkGray),
+    )));
+    lines
+}
+
+/// Draw the help overlay centered over `area`
+pub fn render_help_overlay(f: &mut Frame, title: &str, keys: &[KeyHelp], area: Rect) {
+    let lines = help_lines(keys);
+    let content_width = lines.iter().map(|l| l.width()).max().unwrap_or(0) as u16;
+    let width = (content_width + 4).max(title.len() as u16 + 6).min(area.width);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let rect = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+
+    let popup = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title(format!(" {} ", title)),
+    );
+    f.render_widget(Clear, rect);
+    f.render_widget(popup, rect);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_help_lines_align_keys() {
+        let keys = KeyHelp::list(&[("j/k", "navigate"), ("Enter", "open")]);
+        let text: Vec<String> = help_lines(&keys)
+            .iter()
+            .map(|l| l.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect();
+        assert_eq!(text[0], " j/k    navigate");
+        assert_eq!(text[1], " Enter  open");
+        assert_eq!(text.last().unwrap(), " ?/Esc  close");
+    }
+}
@@ -1,6 +1,9 @@
 pub mod graph;
+pub mod help;
+pub mod redraw;
+pub mod scroll;
 pub mod stat;
 pub mod tui;
 
-pub use stat::render_static;
+pub use stat::{render_stash_list, render_static, RemoteView};
 pub use tui::run_tui;
@@ -0,0 +1,96 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use crossterm::event::{self, Event, KeyEventKind};
+
+/// Poll interval while something on screen animates
+const ACTIVE_POLL: Duration = Duration::from_millis(100);
+/// Poll interval when idle; key presses still wake the loop immediately
+const IDLE_POLL: Duration = Duration::from_millis(500);
+
+/// Tracks whether a TUI frame needs drawing, so event loops only call
+/// `terminal.draw` after input, a resize, or while animating
+pub struct Redraw {
+    dirty: bool,
+}
+
+impl Default for Redraw {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Redraw {
+    /// Starts dirty so the first frame is drawn
+    pub fn new() -> Self {
+        Self { dirty: true }
+    }
+
+    /// Force a redraw, e.g. after returning from an external editor
+    pub fn mark(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Whether to draw now; clears the flag
+    pub fn take(&mut self, animating: bool) -> bool {
+        std::mem::take(&mut self.dirty) || animating
+    }
+
+    /// Record the outcome of one poll; `None` means it timed out
+    pub fn observe(&mut self, event: Option<&Event>) {
+        match event {
+            None | Some(Event::FocusGained) | Some(Event::FocusLost) => {}
+            Some(Event::Key(key)) if key.kind != KeyEventKind::Press => {}
+            Some(_) => self.dirty = true,
+        }
+    }
+
+    /// Wait for the next event, shorter while animating
+    pub fn next_event(&mut self, animating: bool) -> Result<Option<Event>> {
+        let timeout = if animating { ACTIVE_POLL } else { IDLE_POLL };
+        let event = if event::poll(timeout)? {
+            Some(event::read()?)
+        } else {
+            None
+        };
+        self.observe(event.as_ref());
+        Ok(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyCode, KeyEvent, KeyEventState, KeyModifiers};
+
+    fn key(kind: KeyEventKind) -> Event {
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('j'),
+            modifiers: KeyModifiers::NONE,
+            kind,
+            state: KeyEventState::NONE,
+        })
+    }
+
+    #[test]
+    fn test_redraw_dirty_flag() {
+        let mut redraw = Redraw::new();
+        assert!(redraw.take(false), "initial frame");
+        assert!(!redraw.take(false));
+
+        redraw.observe(None);
+        assert!(!redraw.take(false), "idle poll stays clean");
+
+        redraw.observe(Some(&key(KeyEventKind::Release)));
+        assert!(!redraw.take(false));
+
+        redraw.observe(Some(&key(KeyEventKind::Press)));
+        assert!(redraw.take(false));
+        assert!(!redraw.take(false));
+
+        redraw.observe(Some(&Event::Resize(80, 24)));
+        assert!(redraw.take(false));
+
+        assert!(redraw.take(true), "animations redraw every tick");
+    }
+}
@@ -0,0 +1,93 @@
+/// Lines PageUp/PageDown move the cursor in a text editor pane
+pub const PAGE_LINES: isize = 10;
+
+/// Byte offset of the char before `pos` (0 at the start). Editor cursors are
+/// byte offsets kept on char boundaries so `split_at`/`insert` never panic.
+pub fn prev_char(text: &str, pos: usize) -> usize {
+    text[..pos.min(text.len())].char_indices().next_back().map_or(0, |(i, _)| i)
+}
+
+/// Byte offset just past the char at `pos` (clamped to the end)
+pub fn next_char(text: &str, pos: usize) -> usize {
+    let pos = pos.min(text.len());
+    text[pos..].chars().next().map_or(pos, |c| pos + c.len_utf8())
+}
+
+/// Zero-based line of byte offset `pos` in `text`
+pub fn cursor_line(text: &str, pos: usize) -> usize {
+    text[..pos.min(text.len())].matches('\n').count()
+}
+
+/// Move `pos` by `delta` lines, keeping its column where the target line is
+/// long enough. Clamps at the first and last line.
+pub fn move_lines(text: &str, pos: usize, delta: isize) -> usize {
+    let pos = pos.min(text.len());
+    let starts: Vec<usize> = std::iter::once(0)
+        .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    let line = cursor_line(text, pos);
+    let column = text[starts[line]..pos].chars().count();
+
+    let target = (line as isize + delta).clamp(0, starts.len() as isize - 1) as usize;
+    let start = starts[target];
+    let end = starts.get(target + 1).map_or(text.len(), |next| next - 1);
+    text[start..end]
+        .char_indices()
+        .nth(column)
+        .map_or(end, |(i, _)| start + i)
+}
+
+/// Scroll offset for a pane `height` rows tall that keeps `line` visible,
+/// moving as little as possible from `scroll`
+pub fn follow_cursor(scroll: u16, line: usize, height: u16) -> u16 {
+    let line = line.min(u16::MAX as usize) as u16;
+    let height = height.max(1);
+    if line < scroll {
+        line
+    } else if line >= scroll + height {
+        line + 1 - height
+    } else {
+        scroll
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_move_lines_keeps_column() {
+        let text = "subject\n\nlonger body line\nend";
+        assert_eq!(cursor_line(text, 0), 0);
+        assert_eq!(cursor_line(text, text.len()), 3);
+
+        // column 3 on the subject; the empty line clamps it to its end
+        assert_eq!(move_lines(text, 3, 1), 8);
+        assert_eq!(move_lines(text, 3, 2), 12);
+        assert_eq!(move_lines(text, 3, 10), text.len());
+        assert_eq!(move_lines(text, text.len(), -10), 3);
+        assert_eq!(move_lines("héllo\nhi", 3, 1), 9);
+        assert_eq!(move_lines("hi\nhéllo", 2, 1), 6);
+    }
+
+    #[test]
+    fn test_char_steps_skip_multibyte() {
+        let text = "a🎉é";
+        assert_eq!(next_char(text, 0), 1);
+        assert_eq!(next_char(text, 1), 5);
+        assert_eq!(next_char(text, 5), 7);
+        assert_eq!(next_char(text, 7), 7);
+        assert_eq!(prev_char(text, 7), 5);
+        assert_eq!(prev_char(text, 5), 1);
+        assert_eq!(prev_char(text, 0), 0);
+    }
+
+    #[test]
+    fn test_follow_cursor() {
+        assert_eq!(follow_cursor(0, 3, 8), 0);
+        assert_eq!(follow_cursor(0, 8, 8), 1);
+        assert_eq!(follow_cursor(5, 20, 8), 13);
+        assert_eq!(follow_cursor(13, 2, 8), 2);
+        assert_eq!(follow_cursor(0, 0, 0), 0);
+    }
+}
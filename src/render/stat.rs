@@ -1,18 +1,35 @@
+use chrono::{DateTime, Local};
 use colored::Colorize;
 
-use crate::models::{format_relative_time, RepoSummary};
+use crate::models::{format_time, truncate, RemoteBranchInfo, RepoSummary, StashInfo, TimeMode};
 use crate::render::graph::render_simple_graph;
 
-pub fn render_static(summary: &RepoSummary, show_graph: bool, use_color: bool, show_stashes: bool) {
+/// Which remote branches the summary lists
+#[derive(Debug, Clone, Copy)]
+pub struct RemoteView {
+    /// Most branches to print (`0` = all)
+    pub limit: usize,
+    /// Only branches whose last commit is older than this
+    pub stale_before: Option<DateTime<Local>>,
+}
+
+impl Default for RemoteView {
+    fn default() -> Self {
+        Self { limit: 5, stale_before: None }
+    }
+}
+
+pub fn render_static(summary: &RepoSummary, show_graph: bool, use_color: bool, show_stashes: bool, date: TimeMode, remotes: RemoteView) {
     if !use_color {
         colored::control::set_override(false);
     }
 
     render_header(summary);
     render_status(summary, !show_stashes);
+    render_conflicts(summary);
     render_stats(summary);
     println!();
-    render_recent_commits(summary);
+    render_recent_commits(summary, date);
 
     if show_graph {
         println!();
@@ -21,20 +38,24 @@ pub fn render_static(summary: &RepoSummary, show_graph: bool, use_color: bool, s
 
     if !summary.remote_branches.is_empty() {
         println!();
-        render_remote_branches(summary);
+        render_remote_branches(summary, date, remotes);
     }
 
     if show_stashes && !summary.stashes.is_empty() {
         println!();
-        render_stashes(summary);
+        render_stashes(summary, date);
     }
 }
 
 fn render_header(summary: &RepoSummary) {
     let branch = &summary.current_branch;
+
+    if let Some(op) = summary.operation_in_progress() {
+        println!("{}", format!("⚠ {} IN PROGRESS", op).yellow().bold());
+    }
     let branch_display = format!("{}", branch.name).cyan().bold();
 
-    print!("{} ON: {}", "📍".to_string(), branch_display);
+    print!("{} {} {} ON: {}", "📍".to_string(), summary.repo_name.bold(), "·".dimmed(), branch_display);
 
     if let Some(ref upstream) = branch.upstream {
         let mut parts = Vec::new();
@@ -75,6 +96,10 @@ fn render_status(summary: &RepoSummary, show_stash_count: bool) {
     let status = &summary.status;
     let stash_count = summary.stashes.len();
 
+    if summary.bare {
+        println!("   {}", "bare repository (no working tree)".dimmed());
+        return;
+    }
     if status.is_clean() && stash_count == 0 {
         println!("   {}", "working tree clean".dimmed());
         return;
@@ -114,14 +139,35 @@ fn render_status(summary: &RepoSummary, show_stash_count: bool) {
     }
 }
 
+fn render_conflicts(summary: &RepoSummary) {
+    let files = &summary.status.conflicted_files;
+    if files.is_empty() {
+        return;
+    }
+    println!("   {} ({})", "CONFLICTS".red().bold(), files.len());
+    for file in files {
+        println!("     {} {}", "✗".red(), file.red());
+    }
+}
+
+/// `120 total commits (34 yours)`, or `10000+ commits` when the count was capped
+pub fn commit_count_text(total: usize, mine: usize, capped: bool) -> String {
+    let mut text = if capped {
+        format!("{}+ commits", total)
+    } else {
+        format!("{} total commit{}", total, if total == 1 { "" } else { "s" })
+    };
+    if mine > 0 {
+        text.push_str(&format!(" ({}{} yours)", mine, if capped { "+" } else { "" }));
+    }
+    text
+}
+
 fn render_stats(summary: &RepoSummary) {
     let mut parts = Vec::new();
 
     // Total commits
-    parts.push(format!("{} total commit{}",
-        summary.total_commits,
-        if summary.total_commits == 1 { "" } else { "s" }
-    ));
+    parts.push(commit_count_text(summary.total_commits, summary.my_commits, summary.commits_capped));
 
     // Total branches
     let total_branches = summary.popular_branches.len();
@@ -135,7 +181,7 @@ fn render_stats(summary: &RepoSummary) {
         let top_branches: Vec<String> = summary.popular_branches
             .iter()
             .take(3)
-            .map(|b| format!("{} ({})", b.name.cyan(), b.count))
+            .map(|b| format!("{} ({}{})", b.name.cyan(), b.count, if b.capped { "+" } else { "" }))
             .collect();
 
         if !top_branches.is_empty() {
@@ -148,11 +194,11 @@ fn render_stats(summary: &RepoSummary) {
     }
 }
 
-fn render_recent_commits(summary: &RepoSummary) {
+fn render_recent_commits(summary: &RepoSummary, date: TimeMode) {
     println!("{}", "RECENT".bold());
 
     for commit in &summary.recent_commits {
-        let time = format_relative_time(&commit.time);
+        let time = format_time(&commit.time, date);
         let time_padded = format!("{:>4}", time);
         let author_short = commit.author.split_whitespace().next().unwrap_or(&commit.author);
 
@@ -166,11 +212,28 @@ fn render_recent_commits(summary: &RepoSummary) {
     }
 }
 
-fn render_remote_branches(summary: &RepoSummary) {
-    println!("{}", "REMOTE".bold());
+/// Branches `view` keeps, newest first
+fn matching_remotes(branches: &[RemoteBranchInfo], view: RemoteView) -> Vec<&RemoteBranchInfo> {
+    branches
+        .iter()
+        .filter(|b| view.stale_before.is_none_or(|cutoff| b.last_commit_time < cutoff))
+        .collect()
+}
 
-    for branch in summary.remote_branches.iter().take(5) {
-        let time = format_relative_time(&branch.last_commit_time);
+fn render_remote_branches(summary: &RepoSummary, date: TimeMode, view: RemoteView) {
+    let matching = matching_remotes(&summary.remote_branches, view);
+    if view.stale_before.is_some() {
+        println!("{} ({} stale)", "REMOTE".bold(), matching.len());
+        if matching.is_empty() {
+            println!("   {}", "no remote branches past the threshold".dimmed());
+        }
+    } else {
+        println!("{}", "REMOTE".bold());
+    }
+
+    let shown = if view.limit == 0 { matching.len() } else { view.limit.min(matching.len()) };
+    for branch in &matching[..shown] {
+        let time = format_time(&branch.last_commit_time, date);
         let time_padded = format!("{:>4}", time);
         let author_short = branch.last_commit_author.split_whitespace().next().unwrap_or(&branch.last_commit_author);
 
@@ -183,26 +246,91 @@ fn render_remote_branches(summary: &RepoSummary) {
         );
     }
 
-    if summary.remote_branches.len() > 5 {
+    if matching.len() > shown {
         println!(
             "   {}",
-            format!("... and {} more", summary.remote_branches.len() - 5).dimmed()
+            format!("... and {} more (use --remote-limit 0 to see all)", matching.len() - shown).dimmed()
         );
     }
 }
 
-fn render_stashes(summary: &RepoSummary) {
-    println!("{} ({})", "STASHES".bold(), summary.stashes.len());
+fn render_stashes(summary: &RepoSummary, date: TimeMode) {
+    render_stash_list(&summary.stashes, date);
+}
+
+/// `STASHES (n)` followed by one line per entry: index, message, age, branch
+pub fn render_stash_list(stashes: &[StashInfo], date: TimeMode) {
+    println!("{} ({})", "STASHES".bold(), stashes.len());
 
-    for stash in &summary.stashes {
-        println!("   {}: {}", stash.index, truncate(&stash.message, 40));
+    for stash in stashes {
+        let branch = stash.branch.as_deref().map(|b| format!(" · {}", b)).unwrap_or_default();
+        println!(
+            "   {}: {} {}",
+            stash.index,
+            truncate(stash.description(), 40),
+            format!("{}{}", format_time(&stash.time, date), branch).dimmed()
+        );
     }
 }
 
-fn truncate(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
-        s.to_string()
+/// Footer for a list cut to `shown` of `fetched` items. `maybe_more` means the
+/// fetch hit its limit, so the remote may have more than `fetched`.
+pub fn overflow_footer(fetched: usize, shown: usize, maybe_more: bool) -> Option<String> {
+    let hidden = fetched.saturating_sub(shown);
+    let plus = if maybe_more { "+" } else { "" };
+    if hidden > 0 {
+        Some(format!("... and {}{} more (use --limit to see more)", hidden, plus))
+    } else if maybe_more {
+        Some("... and more (use --limit to see more)".to_string())
     } else {
-        format!("{}...", &s[..max_len - 3])
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commit_count_text() {
+        assert_eq!(commit_count_text(1, 0, false), "1 total commit");
+        assert_eq!(commit_count_text(120, 34, false), "120 total commits (34 yours)");
+        assert_eq!(commit_count_text(10000, 12, true), "10000+ commits (12+ yours)");
+    }
+
+    #[test]
+    fn test_matching_remotes_stale_cutoff() {
+        let now = Local::now();
+        let branch = |name: &str, days: i64| RemoteBranchInfo {
+            name: name.to_string(),
+            remote: "origin".to_string(),
+            short_name: name.to_string(),
+            last_commit_time: now - chrono::Duration::days(days),
+            last_commit_message: String::new(),
+            last_commit_author: String::new(),
+        };
+        let branches = vec![branch("fresh", 1), branch("month", 40), branch("old", 400)];
+
+        assert_eq!(matching_remotes(&branches, RemoteView::default()).len(), 3);
+        let stale = RemoteView { limit: 0, stale_before: Some(now - chrono::Duration::days(30)) };
+        let names: Vec<&str> = matching_remotes(&branches, stale).iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(names, ["month", "old"]);
+    }
+
+    #[test]
+    fn test_overflow_footer() {
+        assert_eq!(overflow_footer(30, 30, false), None);
+        assert_eq!(
+            overflow_footer(100, 50, true).as_deref(),
+            Some("... and 50+ more (use --limit to see more)")
+        );
+        assert_eq!(
+            overflow_footer(80, 50, false).as_deref(),
+            Some("... and 30 more (use --limit to see more)")
+        );
+        assert_eq!(
+            overflow_footer(200, 200, true).as_deref(),
+            Some("... and more (use --limit to see more)")
+        );
     }
 }
@@ -2,16 +2,23 @@ use std::io::stdout;
 
 use anyhow::Result;
 use crossterm::{
-    event::{self, Event, KeyCode, KeyEventKind},
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-    ExecutableCommand,
+    event::{Event, KeyCode, KeyEventKind, MouseButton, MouseEvent, MouseEventKind},
 };
+use git2::Repository;
 use ratatui::{
     prelude::*,
     widgets::{Block, Borders, List, ListItem, Paragraph},
 };
 
-use crate::models::{format_relative_time, RepoSummary};
+use super::help::{footer_spans, render_help_overlay, KeyHelp};
+use super::redraw::Redraw;
+use crate::error::RepoError;
+use crate::git::{gather_summary, is_dirty, switch_branch};
+use crate::models::{format_time, truncate, RepoSummary, TimeMode};
+use crate::terminal::TuiGuard;
+
+/// Rows one mouse-wheel notch scrolls
+const WHEEL_ROWS: usize = 3;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum Panel {
@@ -23,9 +30,33 @@ enum Panel {
 struct App {
     summary: RepoSummary,
     active_panel: Panel,
+    /// Position within the filtered branch list
     branch_index: usize,
     commit_index: usize,
+    /// First row shown in each list panel
+    branch_offset: usize,
+    commit_offset: usize,
+    /// Terminal size at the last draw, for mapping mouse clicks to panels
+    area: Rect,
+    date: TimeMode,
     should_quit: bool,
+    show_help: bool,
+    filter: String,
+    filtering: bool,
+    /// Branch waiting for a y/n before checkout
+    confirm_checkout: Option<String>,
+    /// Confirmed; the event loop does the checkout
+    checkout: Option<String>,
+    message: Option<String>,
+}
+
+/// Case-insensitive subsequence match: `fbar` matches `feature/bar`
+fn fuzzy_match(name: &str, pattern: &str) -> bool {
+    let mut chars = name.chars().flat_map(char::to_lowercase);
+    pattern
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|p| chars.any(|c| c == p))
 }
 
 impl App {
@@ -35,12 +66,91 @@ impl App {
             active_panel: Panel::Branches,
             branch_index: 0,
             commit_index: 0,
+            branch_offset: 0,
+            commit_offset: 0,
+            area: Rect::default(),
+            date: TimeMode::default(),
             should_quit: false,
+            show_help: false,
+            filter: String::new(),
+            filtering: false,
+            confirm_checkout: None,
+            checkout: None,
+            message: None,
+        }
+    }
+
+    /// Indices into `local_branches` that match the filter
+    fn visible_branches(&self) -> Vec<usize> {
+        (0..self.summary.local_branches.len())
+            .filter(|&i| fuzzy_match(&self.summary.local_branches[i].name, &self.filter))
+            .collect()
+    }
+
+    fn selected_branch(&self) -> Option<&crate::models::BranchInfo> {
+        let idx = *self.visible_branches().get(self.branch_index)?;
+        self.summary.local_branches.get(idx)
+    }
+
+    fn handle_filter_key(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Esc => {
+                self.filter.clear();
+                self.filtering = false;
+            }
+            KeyCode::Enter => self.filtering = false,
+            KeyCode::Backspace => {
+                self.filter.pop();
+            }
+            KeyCode::Char(c) => self.filter.push(c),
+            _ => return,
+        }
+        self.branch_index = 0;
+    }
+
+    fn request_checkout(&mut self) {
+        let Some(branch) = self.selected_branch() else {
+            return;
+        };
+        if branch.is_head {
+            self.message = Some(format!("already on {}", branch.name));
+        } else if !self.summary.status.is_clean() {
+            self.message = Some("dirty working tree — commit or stash changes first".to_string());
+        } else {
+            self.confirm_checkout = Some(branch.name.clone());
         }
     }
 
     fn handle_key(&mut self, key: KeyCode) {
+        if self.show_help {
+            if matches!(key, KeyCode::Char('?') | KeyCode::Esc) {
+                self.show_help = false;
+            }
+            return;
+        }
+        if let Some(name) = self.confirm_checkout.take() {
+            if matches!(key, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                self.checkout = Some(name);
+            }
+            return;
+        }
+        if self.filtering {
+            self.handle_filter_key(key);
+            return;
+        }
+        self.message = None;
+
         match key {
+            KeyCode::Char('?') => self.show_help = true,
+            KeyCode::Char('/') => {
+                self.active_panel = Panel::Branches;
+                self.filtering = true;
+            }
+            KeyCode::Enter if self.active_panel == Panel::Branches => self.request_checkout(),
+            KeyCode::Esc if !self.filter.is_empty() => {
+                self.filter.clear();
+                self.branch_index = 0;
+            }
             KeyCode::Char('q') | KeyCode::Esc => self.should_quit = true,
             KeyCode::Tab => {
                 self.active_panel = match self.active_panel {
@@ -57,10 +167,75 @@ impl App {
         }
     }
 
+    /// Rows each list panel has room for
+    fn list_rows(&self) -> (usize, usize) {
+        let [branches, commits, _] = panel_areas(self.area);
+        (inner_rows(branches), inner_rows(commits))
+    }
+
+    /// Scroll each list just far enough that its selection is on screen
+    fn keep_selection_visible(&mut self) {
+        let (branch_rows, commit_rows) = self.list_rows();
+        self.branch_offset = follow(self.branch_offset, self.branch_index, branch_rows);
+        self.commit_offset = follow(self.commit_offset, self.commit_index, commit_rows);
+    }
+
+    /// Wheel scrolls the list under the pointer, dragging the selection
+    /// along; a left click focuses the panel and selects the clicked row
+    fn handle_mouse(&mut self, event: MouseEvent) {
+        if self.show_help || self.confirm_checkout.is_some() || self.filtering {
+            return;
+        }
+        let areas = panel_areas(self.area);
+        let Some(at) = areas.iter().position(|r| contains(*r, event.column, event.row)) else {
+            return;
+        };
+        let panel = [Panel::Branches, Panel::Commits, Panel::Status][at];
+        let rows = inner_rows(areas[at]);
+        let len = match panel {
+            Panel::Branches => self.visible_branches().len(),
+            Panel::Commits => self.summary.recent_commits.len(),
+            Panel::Status => 0,
+        };
+        let (index, offset) = match panel {
+            Panel::Branches => (&mut self.branch_index, &mut self.branch_offset),
+            Panel::Commits => (&mut self.commit_index, &mut self.commit_offset),
+            Panel::Status => {
+                if matches!(event.kind, MouseEventKind::Down(MouseButton::Left)) {
+                    self.active_panel = panel;
+                }
+                return;
+            }
+        };
+
+        match event.kind {
+            MouseEventKind::ScrollDown | MouseEventKind::ScrollUp => {
+                let max_offset = len.saturating_sub(rows);
+                *offset = if event.kind == MouseEventKind::ScrollDown {
+                    (*offset + WHEEL_ROWS).min(max_offset)
+                } else {
+                    offset.saturating_sub(WHEEL_ROWS)
+                };
+                *index = (*index).clamp(*offset, (*offset + rows).saturating_sub(1).min(len.saturating_sub(1)));
+            }
+            MouseEventKind::Down(MouseButton::Left) => {
+                self.message = None;
+                let row = (event.row as usize).checked_sub(areas[at].y as usize + 1);
+                if let Some(clicked) = row.filter(|&r| r < rows).map(|r| *offset + r) {
+                    if clicked < len {
+                        *index = clicked;
+                    }
+                }
+                self.active_panel = panel;
+            }
+            _ => {}
+        }
+    }
+
     fn move_down(&mut self) {
         match self.active_panel {
             Panel::Branches => {
-                let max = self.summary.local_branches.len().saturating_sub(1);
+                let max = self.visible_branches().len().saturating_sub(1);
                 self.branch_index = (self.branch_index + 1).min(max);
             }
             Panel::Commits => {
@@ -100,22 +275,37 @@ impl App {
     }
 }
 
-pub fn run_tui(summary: RepoSummary) -> Result<()> {
-    enable_raw_mode()?;
-    stdout().execute(EnterAlternateScreen)?;
-
+/// Dashboard over `summary`. Checking out a branch re-gathers the summary
+/// from `repo` with `commit_limit` commits.
+pub fn run_tui(repo: &mut Repository, summary: RepoSummary, commit_limit: usize, date: TimeMode) -> Result<()> {
+    let _guard = TuiGuard::with_mouse()?;
     let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
     let mut app = App::new(summary);
+    app.date = date;
 
+    let mut redraw = Redraw::new();
     loop {
-        terminal.draw(|f| ui(f, &app))?;
+        if redraw.take(false) {
+            app.area = terminal.size()?;
+            app.keep_selection_visible();
+            terminal.draw(|f| ui(f, &app))?;
+        }
 
-        if event::poll(std::time::Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    app.handle_key(key.code);
-                }
+        match redraw.next_event(false)? {
+            Some(Event::Key(key)) if key.kind == KeyEventKind::Press => {
+                app.handle_key(key.code);
+                app.keep_selection_visible();
+            }
+            Some(Event::Mouse(mouse)) => {
+                app.handle_mouse(mouse);
+                redraw.mark();
             }
+            _ => {}
+        }
+
+        if let Some(name) = app.checkout.take() {
+            checkout(repo, &mut app, &name, commit_limit);
+            redraw.mark();
         }
 
         if app.should_quit {
@@ -123,11 +313,70 @@ pub fn run_tui(summary: RepoSummary) -> Result<()> {
         }
     }
 
-    disable_raw_mode()?;
-    stdout().execute(LeaveAlternateScreen)?;
     Ok(())
 }
 
+/// Branches, commits and status columns within a terminal of `size`
+fn panel_areas(size: Rect) -> [Rect; 3] {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Header
+            Constraint::Min(10),   // Main content
+            Constraint::Length(3), // Footer
+        ])
+        .split(size);
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(30),
+            Constraint::Percentage(40),
+            Constraint::Percentage(30),
+        ])
+        .split(chunks[1]);
+    [columns[0], columns[1], columns[2]]
+}
+
+/// Rows inside a bordered panel
+fn inner_rows(area: Rect) -> usize {
+    area.height.saturating_sub(2) as usize
+}
+
+fn contains(area: Rect, column: u16, row: u16) -> bool {
+    column >= area.x && column < area.right() && row >= area.y && row < area.bottom()
+}
+
+/// Offset that keeps `index` within `rows` visible rows starting at `offset`
+fn follow(offset: usize, index: usize, rows: usize) -> usize {
+    if index < offset {
+        index
+    } else if rows > 0 && index >= offset + rows {
+        index + 1 - rows
+    } else {
+        offset
+    }
+}
+
+fn checkout(repo: &mut Repository, app: &mut App, name: &str, commit_limit: usize) {
+    let result = match is_dirty(repo) {
+        Ok(true) => Err(RepoError::DirtyTree.into()),
+        Ok(false) => switch_branch(repo, name, false, false),
+        Err(e) => Err(e),
+    }
+    .and_then(|_| gather_summary(repo, commit_limit, false, !app.summary.commits_capped));
+
+    match result {
+        Ok(summary) => {
+            app.summary = summary;
+            app.filter.clear();
+            app.branch_index = 0;
+            app.commit_index = 0;
+            app.message = Some(format!("switched to {}", name));
+        }
+        Err(e) => app.message = Some(format!("{:#}", e)),
+    }
+}
+
 fn ui(f: &mut Frame, app: &App) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -143,14 +392,7 @@ fn ui(f: &mut Frame, app: &App) {
     f.render_widget(header, chunks[0]);
 
     // Main content - split into columns
-    let main_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage(30),
-            Constraint::Percentage(40),
-            Constraint::Percentage(30),
-        ])
-        .split(chunks[1]);
+    let main_chunks = panel_areas(f.size());
 
     // Branches panel
     let branches = render_branches(app);
@@ -165,13 +407,30 @@ fn ui(f: &mut Frame, app: &App) {
     f.render_widget(status, main_chunks[2]);
 
     // Footer
-    let footer = render_footer();
+    let footer = render_footer(app);
     f.render_widget(footer, chunks[2]);
+
+    if app.show_help {
+        render_help_overlay(f, "Keys", &keys(), f.size());
+    }
+}
+
+fn keys() -> Vec<KeyHelp> {
+    KeyHelp::list(&[
+        ("j/k ↑↓", "navigate"),
+        ("tab", "next panel"),
+        ("h/l ←→", "previous / next panel"),
+        ("Enter", "check out branch"),
+        ("/", "filter branches"),
+        ("mouse", "scroll / click panel"),
+        ("?", "help"),
+        ("q/Esc", "quit"),
+    ])
 }
 
 fn render_header(app: &App) -> Paragraph<'static> {
     let branch = &app.summary.current_branch;
-    let mut text = format!(" ON: {}", branch.name);
+    let mut text = format!(" {} · ON: {}", app.summary.repo_name, branch.name);
 
     if let Some(ref upstream) = branch.upstream {
         if upstream.ahead > 0 || upstream.behind > 0 {
@@ -179,7 +438,15 @@ fn render_header(app: &App) -> Paragraph<'static> {
         }
     }
 
-    Paragraph::new(text)
+    let mut spans = vec![Span::raw(text)];
+    if let Some(op) = app.summary.operation_in_progress() {
+        spans.push(Span::styled(
+            format!("  ⚠ {} IN PROGRESS", op),
+            Style::default().fg(Color::Yellow).bold(),
+        ));
+    }
+
+    Paragraph::new(Line::from(spans))
         .style(Style::default().fg(Color::Cyan).bold())
         .block(
             Block::default()
@@ -191,11 +458,12 @@ fn render_header(app: &App) -> Paragraph<'static> {
 
 fn render_branches(app: &App) -> List<'static> {
     let items: Vec<ListItem> = app
-        .summary
-        .local_branches
-        .iter()
+        .visible_branches()
+        .into_iter()
         .enumerate()
-        .map(|(i, b)| {
+        .skip(app.branch_offset)
+        .map(|(i, idx)| {
+            let b = &app.summary.local_branches[idx];
             let marker = if b.is_head { "* " } else { "  " };
             let text = format!("{}{}", marker, b.name);
 
@@ -216,12 +484,17 @@ fn render_branches(app: &App) -> List<'static> {
     } else {
         Style::default()
     };
+    let title = if app.filtering || !app.filter.is_empty() {
+        format!(" Branches /{}{} ", app.filter, if app.filtering { "_" } else { "" })
+    } else {
+        " Branches ".to_string()
+    };
 
     List::new(items).block(
         Block::default()
             .borders(Borders::ALL)
             .border_style(border_style)
-            .title(" Branches "),
+            .title(title),
     )
 }
 
@@ -231,8 +504,9 @@ fn render_commits(app: &App) -> List<'static> {
         .recent_commits
         .iter()
         .enumerate()
+        .skip(app.commit_offset)
         .map(|(i, c)| {
-            let time = format_relative_time(&c.time);
+            let time = format_time(&c.time, app.date);
             let text = format!("{:>4}  {}", time, truncate(&c.message, 35));
 
             let style = if i == app.commit_index && app.active_panel == Panel::Commits {
@@ -264,7 +538,9 @@ fn render_status(app: &App) -> Paragraph<'static> {
 
     let mut lines = Vec::new();
 
-    if status.is_clean() {
+    if app.summary.bare {
+        lines.push(Line::from("Bare repository"));
+    } else if status.is_clean() {
         lines.push(Line::from("Working tree clean"));
     } else {
         if status.staged > 0 {
@@ -277,10 +553,11 @@ fn render_status(app: &App) -> Paragraph<'static> {
             lines.push(Line::from(format!("Untracked: {}", status.untracked)));
         }
         if status.conflicted > 0 {
-            lines.push(Line::from(Span::styled(
-                format!("Conflicted: {}", status.conflicted),
-                Style::default().fg(Color::Red),
-            )));
+            let red = Style::default().fg(Color::Red);
+            lines.push(Line::from(Span::styled(format!("Conflicted: {}", status.conflicted), red)));
+            for file in &status.conflicted_files {
+                lines.push(Line::from(Span::styled(format!("  {}", truncate(file, 22)), red)));
+            }
         }
     }
 
@@ -288,7 +565,8 @@ fn render_status(app: &App) -> Paragraph<'static> {
     lines.push(Line::from(format!("Stashes: {}", app.summary.stashes.len())));
 
     for stash in app.summary.stashes.iter().take(3) {
-        lines.push(Line::from(format!("  {}: {}", stash.index, truncate(&stash.message, 20))));
+        let time = format_time(&stash.time, app.date);
+        lines.push(Line::from(format!("  {}: {} {}", stash.index, truncate(stash.description(), 20), time)));
     }
 
     let border_style = if app.active_panel == Panel::Status {
@@ -305,16 +583,77 @@ fn render_status(app: &App) -> Paragraph<'static> {
     )
 }
 
-fn render_footer() -> Paragraph<'static> {
-    Paragraph::new(" q: quit | tab: switch panel | j/k: navigate | ↑↓←→: move")
-        .style(Style::default().fg(Color::DarkGray))
-        .block(Block::default().borders(Borders::ALL))
+fn render_footer(app: &App) -> Paragraph<'static> {
+    let mut spans = vec![Span::raw(" ")];
+    if let Some(name) = &app.confirm_checkout {
+        spans.push(Span::styled(
+            format!("Check out {}? y/N", name),
+            Style::default().fg(Color::Yellow).bold(),
+        ));
+    } else if let Some(message) = &app.message {
+        spans.push(Span::styled(message.clone(), Style::default().fg(Color::Yellow)));
+    } else {
+        spans.extend(footer_spans(&keys()));
+    }
+    Paragraph::new(Line::from(spans)).block(Block::default().borders(Borders::ALL))
 }
 
-fn truncate(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
-        s.to_string()
-    } else {
-        format!("{}...", &s[..max_len.saturating_sub(3)])
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_match() {
+        assert!(fuzzy_match("feature/bar", "fbar"));
+        assert!(fuzzy_match("feature/bar", "FEAT"));
+        assert!(fuzzy_match("main", ""));
+        assert!(!fuzzy_match("main", "mian"));
+        assert!(!fuzzy_match("fix", "fixes"));
+    }
+
+    fn summary_with_branches(count: usize) -> RepoSummary {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut repo = Repository::init(dir.path()).unwrap();
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        let tree = repo.find_tree(repo.index().unwrap().write_tree().unwrap()).unwrap();
+        let oid = repo.commit(Some("HEAD"), &sig, &sig, "init", &tree, &[]).unwrap();
+        let commit = repo.find_commit(oid).unwrap();
+        for i in 0..count {
+            repo.branch(&format!("topic-{:02}", i), &commit, false).unwrap();
+        }
+        drop(commit);
+        drop(tree);
+        gather_summary(&mut repo, 10, false, false).unwrap()
+    }
+
+    fn mouse(kind: MouseEventKind, column: u16, row: u16) -> MouseEvent {
+        MouseEvent { kind, column, row, modifiers: crossterm::event::KeyModifiers::NONE }
+    }
+
+    #[test]
+    fn test_mouse_scroll_and_click() {
+        let mut app = App::new(summary_with_branches(30));
+        // 80x20: panels span rows 3..17, so 12 list rows; branches are columns 0..24
+        app.area = Rect::new(0, 0, 80, 20);
+        let total = app.visible_branches().len();
+
+        app.handle_mouse(mouse(MouseEventKind::ScrollDown, 5, 8));
+        assert_eq!(app.branch_offset, WHEEL_ROWS);
+        assert_eq!(app.branch_index, WHEEL_ROWS, "selection stays on screen");
+        for _ in 0..20 {
+            app.handle_mouse(mouse(MouseEventKind::ScrollDown, 5, 8));
+        }
+        assert_eq!(app.branch_offset, total - 12);
+
+        // focus the commits panel, then click the third visible branch row
+        app.handle_mouse(mouse(MouseEventKind::Down(MouseButton::Left), 40, 4));
+        assert_eq!(app.active_panel, Panel::Commits);
+        app.handle_mouse(mouse(MouseEventKind::Down(MouseButton::Left), 5, 6));
+        assert_eq!(app.active_panel, Panel::Branches);
+        assert_eq!(app.branch_index, total - 12 + 2);
+
+        app.branch_index = 0;
+        app.keep_selection_visible();
+        assert_eq!(app.branch_offset, 0);
     }
 }
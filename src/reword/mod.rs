@@ -5,6 +5,8 @@ use anyhow::{bail, Result};
 use colored::Colorize;
 use git2::Repository;
 
+use crate::error::RepoError;
+use crate::git::{is_dirty, is_pushed};
 use crate::models::{format_relative_time, CommitInfo};
 
 pub struct RewordArgs {
@@ -12,6 +14,7 @@ pub struct RewordArgs {
     pub all: bool,
     pub count: usize,
     pub editor: bool,
+    pub reorder: bool,
 }
 
 pub fn run_reword(repo: &Repository, args: RewordArgs) -> Result<()> {
@@ -24,71 +27,84 @@ pub fn run_reword(repo: &Repository, args: RewordArgs) -> Result<()> {
     }
 
     let selected = select_commits(&commits, &args)?;
-    if selected.is_empty() {
+    if selected.is_empty() && !args.reorder {
         println!("{} no commits selected", "!".yellow());
         return Ok(());
     }
 
     let messages = collect_new_messages(&commits, &selected, args.editor)?;
-    if messages.is_empty() {
+
+    // Newest-first permutation of `commits`; identity unless --reorder moved something
+    let order = if args.reorder {
+        picker::reorder_commits(&commits, &selected)?
+    } else {
+        (0..commits.len()).collect()
+    };
+    let moved: Vec<usize> = (0..order.len()).filter(|&i| order[i] != i).collect();
+
+    if messages.is_empty() && moved.is_empty() {
         println!("{} no messages changed", "!".yellow());
         return Ok(());
     }
 
-    // Build SHAs from messages (only commits with actual new messages)
-    let reword_shas: Vec<String> = messages
-        .iter()
-        .map(|(sha, _)| sha[..7].to_string())
-        .collect();
-
-    // Find indices of commits being reworded to determine base
-    let reword_indices: Vec<usize> = commits
+    // Find indices of commits being reworded or moved to determine base
+    let mut affected: Vec<usize> = commits
         .iter()
         .enumerate()
         .filter(|(_, c)| messages.iter().any(|(sha, _)| c.id.to_string() == *sha))
         .map(|(i, _)| i)
         .collect();
+    affected.extend(&moved);
 
-    warn_pushed_commits(repo, &commits, &reword_indices)?;
+    warn_pushed_commits(repo, &commits, &affected)?;
 
-    let has_root = reword_indices.iter().any(|&i| commits[i].parents.is_empty());
+    let oldest_idx = *affected.iter().max().unwrap();
+    let has_root = (0..=oldest_idx).any(|i| commits[i].parents.is_empty());
     let base_sha = if has_root {
         None
     } else {
-        let oldest_idx = *reword_indices.iter().max().unwrap();
         Some(commits[oldest_idx].parents[0].to_string())
     };
 
+    // Every commit after the base, oldest-first in the (possibly reordered) order
+    let range = &order[..=oldest_idx];
+    if range.iter().any(|&i| is_merge(&commits[i])) {
+        bail!("range contains merge commits — use `repo craft --rebase-merges` instead");
+    }
+    let todo: Vec<(String, String)> = range
+        .iter()
+        .rev()
+        .map(|&i| (commits[i].id.to_string(), commits[i].message.clone()))
+        .collect();
+
     let repo_path = repo
         .workdir()
         .unwrap_or_else(|| repo.path())
         .to_path_buf();
 
-    rebase::run_interactive_rebase(&repo_path, base_sha.as_deref(), &reword_shas, &messages)?;
+    rebase::run_interactive_rebase(
+        &repo_path,
+        base_sha.as_deref(),
+        &todo,
+        &messages,
+    )?;
 
-    println!("{} reworded {} commit(s)", "✓".green(), messages.len());
+    if !moved.is_empty() {
+        println!("{} reordered {} commit(s)", "✓".green(), moved.len());
+    }
+    if !messages.is_empty() {
+        println!("{} reworded {} commit(s)", "✓".green(), messages.len());
+    }
     Ok(())
 }
 
 fn validate_state(repo: &Repository) -> Result<()> {
     if repo.head_detached()? {
-        bail!("detached HEAD — cannot reword");
+        bail!(RepoError::DetachedHead("reword"));
     }
 
-    let statuses = repo.statuses(None)?;
-    let dirty = statuses.iter().any(|s| {
-        let st = s.status();
-        st.intersects(
-            git2::Status::INDEX_NEW
-                | git2::Status::INDEX_MODIFIED
-                | git2::Status::INDEX_DELETED
-                | git2::Status::WT_MODIFIED
-                | git2::Status::WT_NEW
-                | git2::Status::WT_DELETED,
-        )
-    });
-    if dirty {
-        bail!("dirty working tree — commit or stash changes first");
+    if is_dirty(repo)? {
+        bail!(RepoError::DirtyTree);
     }
 
     Ok(())
@@ -99,7 +115,7 @@ fn load_commits(repo: &Repository, limit: usize) -> Result<Vec<CommitInfo>> {
 
     let head = repo.head()?;
     let mut revwalk = repo.revwalk()?;
-    revwalk.set_sorting(git2::Sort::TIME)?;
+    revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)?;
     revwalk.push(head.target().unwrap())?;
 
     let mut commits = Vec::new();
@@ -227,7 +243,8 @@ fn prompt_inline(current: &str) -> Result<Option<String>> {
     Ok(Some(input.to_string()))
 }
 
-fn edit_with_editor(current: &str) -> Result<Option<String>> {
+/// Open `$EDITOR` (default vi) on `current`; None if unchanged or emptied
+pub(crate) fn edit_with_editor(current: &str) -> Result<Option<String>> {
     let editor = std::env::var("EDITOR").unwrap_or_else(|_| "vi".to_string());
 
     let tmp = std::env::temp_dir().join(format!("repo-reword-{}.txt", std::process::id()));
@@ -257,34 +274,19 @@ fn warn_pushed_commits(
     commits: &[CommitInfo],
     selected: &[usize],
 ) -> Result<()> {
-    // check if current branch has an upstream
-    let head = repo.head()?;
-    let branch_name = head.shorthand().unwrap_or("");
-    let upstream_ref = format!("refs/remotes/origin/{}", branch_name);
-
-    if repo.find_reference(&upstream_ref).is_ok() {
-        // some selected commits may be pushed
-        let upstream_oid = repo.find_reference(&upstream_ref)?.target();
-        if let Some(upstream_oid) = upstream_oid {
-            let has_pushed = selected.iter().any(|&i| {
-                repo.graph_descendant_of(upstream_oid, commits[i].id).unwrap_or(false)
-                    || upstream_oid == commits[i].id
-            });
-
-            if has_pushed {
-                use std::io::{self, Write};
-                eprint!(
-                    "{} selected commits are already pushed — force-push needed. continue? [y/N] ",
-                    "⚠".yellow()
-                );
-                io::stdout().flush()?;
-
-                let mut input = String::new();
-                io::stdin().read_line(&mut input)?;
-                if !input.trim().eq_ignore_ascii_case("y") {
-                    bail!("aborted");
-                }
-            }
+    // some selected commits may already be on the upstream
+    if selected.iter().any(|&i| is_pushed(repo, commits[i].id)) {
+        use std::io::{self, Write};
+        eprint!(
+            "{} selected commits are already pushed — force-push needed. continue? [y/N] ",
+            "⚠".yellow()
+        );
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        if !input.trim().eq_ignore_ascii_case("y") {
+            bail!("aborted");
         }
     }
 
@@ -3,16 +3,25 @@ use colored::Colorize;
 
 use crate::models::{format_relative_time, CommitInfo};
 
-pub fn pick_commits(commits: &[CommitInfo]) -> Result<Vec<usize>> {
-    use std::io::{self, Write};
-
-    let is_merge: Vec<bool> = commits.iter().map(|c| c.parents.len() > 1).collect();
+/// Positions of the commits matching `filter` (all when empty)
+fn filter_commits(commits: &[CommitInfo], filter: &str) -> Vec<usize> {
+    (0..commits.len())
+        .filter(|&i| filter.is_empty() || commits[i].matches(filter))
+        .collect()
+}
 
-    // display numbered list
+fn print_commit_list(commits: &[CommitInfo], is_merge: &[bool], visible: &[usize], filter: &str) {
     println!("\n{}", "SELECT COMMITS TO REWORD".bold());
+    if !filter.is_empty() {
+        println!(
+            "  {}",
+            format!("filter: {} ({} of {})", filter, visible.len(), commits.len()).dimmed()
+        );
+    }
     println!("{}", "─".repeat(60).dimmed());
 
-    for (i, c) in commits.iter().enumerate() {
+    for &i in visible {
+        let c = &commits[i];
         let num = format!("{:>3}", i + 1);
         let merge_tag = if is_merge[i] {
             " (merge)".dimmed().to_string()
@@ -32,9 +41,18 @@ pub fn pick_commits(commits: &[CommitInfo]) -> Result<Vec<usize>> {
 
     println!("{}", "─".repeat(60).dimmed());
     println!(
-        "  {} toggle: 1, 1-5, 1,3,5 | a=all n=none Enter=confirm",
+        "  {} toggle: 1, 1-5, 1,3,5 | a=all n=none /text=filter /=clear Enter=confirm",
         "?".cyan()
     );
+}
+
+pub fn pick_commits(commits: &[CommitInfo]) -> Result<Vec<usize>> {
+    use std::io::{self, Write};
+
+    let is_merge: Vec<bool> = commits.iter().map(|c| c.parents.len() > 1).collect();
+    let mut filter = String::new();
+    let mut visible = filter_commits(commits, &filter);
+    print_commit_list(commits, &is_merge, &visible, &filter);
 
     let mut selected = vec![false; commits.len()];
 
@@ -51,9 +69,17 @@ pub fn pick_commits(commits: &[CommitInfo]) -> Result<Vec<usize>> {
             break;
         }
 
+        if let Some(query) = input.strip_prefix('/') {
+            filter = query.trim().to_string();
+            visible = filter_commits(commits, &filter);
+            print_commit_list(commits, &is_merge, &visible, &filter);
+            continue;
+        }
+
         match input {
             "a" => {
-                for i in 0..commits.len() {
+                // numbers stay tied to the full list; `a` only takes what's shown
+                for &i in &visible {
                     selected[i] = !is_merge[i];
                 }
             }
@@ -106,6 +132,89 @@ pub fn pick_commits(commits: &[CommitInfo]) -> Result<Vec<usize>> {
     Ok(result)
 }
 
+/// Line-based counterpart of craft's reorder mode. Returns commit indices in
+/// the new newest-first order; merges stay in place.
+pub fn reorder_commits(commits: &[CommitInfo], selected: &[usize]) -> Result<Vec<usize>> {
+    use std::io::{self, Write};
+
+    let mut order: Vec<usize> = (0..commits.len()).collect();
+
+    loop {
+        println!("\n{}", "REORDER COMMITS (newest first)".bold());
+        println!("{}", "─".repeat(60).dimmed());
+        for (pos, &idx) in order.iter().enumerate() {
+            let c = &commits[idx];
+            let mark = if selected.contains(&idx) { "*".green().to_string() } else { " ".to_string() };
+            let moved = if pos != idx { " (moved)".dimmed().to_string() } else { String::new() };
+            println!(
+                "  {}{} {} {}{}",
+                format!("{:>3}", pos + 1).cyan(),
+                mark,
+                c.short_id.yellow(),
+                c.message,
+                moved
+            );
+        }
+        println!("{}", "─".repeat(60).dimmed());
+        print!(
+            "  {} 3k=up 3j=down 3:1=move to 1 | r=reset Enter=confirm\n  > ",
+            "?".cyan()
+        );
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        let input = input.trim();
+
+        if input.is_empty() {
+            return Ok(order);
+        }
+        if input == "r" {
+            order = (0..commits.len()).collect();
+            continue;
+        }
+
+        match parse_move(input, order.len()) {
+            Some((from, to)) => {
+                let blocked = order[from.min(to)..=from.max(to)]
+                    .iter()
+                    .any(|&i| commits[i].parents.len() > 1);
+                if blocked {
+                    eprintln!("  {} can't move commits across a merge", "⚠".yellow());
+                } else {
+                    move_item(&mut order, from, to);
+                }
+            }
+            None => eprintln!("  {} invalid input", "!".red()),
+        }
+    }
+}
+
+/// Parse `3k` / `3j` / `3:1` (1-based) into 0-based (from, to) positions
+fn parse_move(input: &str, len: usize) -> Option<(usize, usize)> {
+    let (from, to) = if let Some((a, b)) = input.split_once(':') {
+        (a.trim().parse::<usize>().ok()?, b.trim().parse::<usize>().ok()?)
+    } else if let Some(n) = input.strip_suffix('k') {
+        let n = n.trim().parse::<usize>().ok()?;
+        (n, n.checked_sub(1)?)
+    } else if let Some(n) = input.strip_suffix('j') {
+        let n = n.trim().parse::<usize>().ok()?;
+        (n, n + 1)
+    } else {
+        return None;
+    };
+
+    if from == 0 || to == 0 || from > len || to > len {
+        return None;
+    }
+    Some((from - 1, to - 1))
+}
+
+fn move_item<T>(items: &mut Vec<T>, from: usize, to: usize) {
+    let item = items.remove(from);
+    items.insert(to, item);
+}
+
 fn print_selection_summary(selected: &[bool], _commits: &[CommitInfo]) {
     let count = selected.iter().filter(|&&s| s).count();
     if count == 0 {
@@ -162,3 +271,52 @@ fn parse_selection(input: &str, max: usize) -> Option<Vec<usize>> {
 
     Some(indices)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(message: &str, author: &str) -> CommitInfo {
+        CommitInfo {
+            id: git2::Oid::zero(),
+            short_id: String::new(),
+            message: message.to_string(),
+            author: author.to_string(),
+            time: chrono::Local::now(),
+            parents: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_filter_commits() {
+        let commits = vec![
+            commit("fix parser crash", "ana"),
+            commit("add docs", "Bob"),
+            commit("Parser: speed up", "cy"),
+        ];
+        assert_eq!(filter_commits(&commits, ""), vec![0, 1, 2]);
+        assert_eq!(filter_commits(&commits, "parser"), vec![0, 2]);
+        assert_eq!(filter_commits(&commits, "bob"), vec![1]);
+        assert!(filter_commits(&commits, "zzz").is_empty());
+    }
+
+    #[test]
+    fn test_parse_move() {
+        assert_eq!(parse_move("3k", 5), Some((2, 1)));
+        assert_eq!(parse_move("3j", 5), Some((2, 3)));
+        assert_eq!(parse_move("4:1", 5), Some((3, 0)));
+        assert_eq!(parse_move("1k", 5), None);
+        assert_eq!(parse_move("5j", 5), None);
+        assert_eq!(parse_move("6:1", 5), None);
+        assert_eq!(parse_move("x", 5), None);
+    }
+
+    #[test]
+    fn test_move_item() {
+        let mut order = vec![0, 1, 2, 3];
+        move_item(&mut order, 3, 0);
+        assert_eq!(order, vec![3, 0, 1, 2]);
+        move_item(&mut order, 0, 2);
+        assert_eq!(order, vec![0, 1, 3, 2]);
+    }
+}
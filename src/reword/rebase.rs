@@ -1,24 +1,29 @@
-use anyhow::{bail, Result};
-use std::fs;
-use std::path::{Path, PathBuf};
+use anyhow::{anyhow, Result};
+use std::path::Path;
 use std::process::Command;
 
+use crate::git::{
+    is_rebase_conflict, resolve_rebase_conflicts, write_commit_editor, write_todo_editor, ScratchDir,
+};
+
 /// Run `git rebase -i` with custom sequence and commit editors.
 ///
 /// `base_sha`: parent of oldest commit to reword, or None for `--root`
-/// `selected_shas`: short SHAs to mark as `reword`
-/// `messages`: vec of (full_sha, new_message) pairs — oldest-first order
+/// `todo`: every commit after the base as (full_sha, subject), oldest-first in
+/// the desired order
+/// `messages`: (full_sha, new_message) pairs for the commits to reword
 pub fn run_interactive_rebase(
     repo_path: &Path,
     base_sha: Option<&str>,
-    selected_shas: &[String],
+    todo: &[(String, String)],
     messages: &[(String, String)],
 ) -> Result<()> {
-    let tmp_dir = std::env::temp_dir().join(format!("repo-reword-{}", std::process::id()));
-    fs::create_dir_all(&tmp_dir)?;
+    // removed on return, once git and any conflict resolution are done with it
+    let scratch = ScratchDir::new("repo-reword")?;
+    let tmp_dir = scratch.path();
 
-    let seq_script = write_sequence_editor(&tmp_dir, selected_shas)?;
-    let msg_script = write_commit_editor(&tmp_dir, messages)?;
+    let seq_script = write_todo_editor(tmp_dir, &build_todo(todo, messages))?;
+    let msg_script = write_commit_editor(tmp_dir, messages)?;
 
     let mut args = vec![
         "-C".to_string(),
@@ -38,92 +43,64 @@ pub fn run_interactive_rebase(
         .env("GIT_EDITOR", &msg_script)
         .output()?;
 
-    // cleanup
-    fs::remove_dir_all(&tmp_dir).ok();
-
-    if !output.status.success() {
+    // Conflicts pause for resolution; the editor scripts must outlive it
+    if output.status.success() {
+        Ok(())
+    } else if is_rebase_conflict(&output) {
+        resolve_rebase_conflicts(
+            repo_path,
+            &[("GIT_SEQUENCE_EDITOR", &seq_script), ("GIT_EDITOR", &msg_script)],
+        )
+    } else {
         let stderr = String::from_utf8_lossy(&output.stderr);
         let stdout = String::from_utf8_lossy(&output.stdout);
-
-        if stderr.contains("CONFLICT") || stderr.contains("could not apply") {
-            bail!(
-                "rebase failed with conflict:\n{}\n{}\nresolve manually, then:\n  git rebase --continue\nor:\n  git rebase --abort",
-                stdout,
-                stderr
-            );
-        }
-
-        bail!("rebase failed:\n{}\n{}", stdout, stderr);
+        Err(anyhow!("rebase failed:\n{}\n{}", stdout, stderr))
     }
-
-    Ok(())
 }
 
-/// Write a shell script that replaces `pick` with `reword` for selected SHAs.
-fn write_sequence_editor(tmp_dir: &Path, selected_shas: &[String]) -> Result<PathBuf> {
-    let script_path = tmp_dir.join("seq-editor.sh");
-
-    let sed_cmds: Vec<String> = selected_shas
-        .iter()
-        .map(|sha| format!("s/^pick {sha}/reword {sha}/"))
-        .collect();
-
-    let sed_expr = sed_cmds.join("; ");
-    let script = format!("#!/bin/sh\nsed -i.bak '{sed_expr}' \"$1\"\n");
-
-    fs::write(&script_path, &script)?;
-    make_executable(&script_path)?;
-
-    Ok(script_path)
+/// Render the todo: `reword` for commits with a new message, `pick` otherwise
+fn build_todo(todo: &[(String, String)], messages: &[(String, String)]) -> String {
+    todo.iter()
+        .map(|(sha, subject)| {
+            let action = if messages.iter().any(|(s, _)| s == sha) { "reword" } else { "pick" };
+            format!("{} {} {}\n", action, sha, subject)
+        })
+        .collect()
 }
 
-/// Write numbered message files and a script that serves them sequentially.
-///
-/// During `git rebase -i`, each `reword` commit invokes GIT_EDITOR once.
-/// We write each new message to `msg_0`, `msg_1`, ... and use a counter file
-/// to track which message to serve next.
-fn write_commit_editor(tmp_dir: &Path, messages: &[(String, String)]) -> Result<PathBuf> {
-    let script_path = tmp_dir.join("msg-editor.sh");
-    let counter_path = tmp_dir.join("counter");
-
-    // Initialize counter to 0
-    fs::write(&counter_path, "0")?;
-
-    // Write each message as a separate file: msg_0, msg_1, ...
-    for (i, (_sha, msg)) in messages.iter().enumerate() {
-        let msg_path = tmp_dir.join(format!("msg_{}", i));
-        fs::write(&msg_path, msg)?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, init_repo};
+
+    #[test]
+    fn test_build_todo() {
+        let todo = vec![
+            ("bbb".to_string(), "second".to_string()),
+            ("aaa".to_string(), "first".to_string()),
+        ];
+        let messages = vec![("aaa".to_string(), "new first".to_string())];
+        assert_eq!(build_todo(&todo, &messages), "pick bbb second\nreword aaa first\n");
     }
 
-    let counter_str = counter_path.to_string_lossy();
-    let tmp_str = tmp_dir.to_string_lossy();
-
-    // Script: read counter, copy corresponding message file to $1, increment counter
-    let script = format!(
-        r#"#!/bin/sh
-COUNTER_FILE="{counter_str}"
-IDX=$(cat "$COUNTER_FILE")
-MSG_FILE="{tmp_str}/msg_$IDX"
-if [ -f "$MSG_FILE" ]; then
-    cp "$MSG_FILE" "$1"
-fi
-echo $((IDX + 1)) > "$COUNTER_FILE"
-"#
-    );
-
-    fs::write(&script_path, &script)?;
-    make_executable(&script_path)?;
-
-    Ok(script_path)
-}
-
-fn make_executable(path: &Path) -> Result<()> {
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let mut perms = fs::metadata(path)?.permissions();
-        perms.set_mode(0o755);
-        fs::set_permissions(path, perms)?;
+    #[test]
+    fn test_reorder_and_reword() {
+        let (dir, repo) = init_repo();
+        let commit = |name: &str, msg: &str| commit_file(&repo, name, name, msg).to_string();
+        let base = commit("base.txt", "base");
+        let a = commit("a.txt", "add a");
+        let b = commit("b.txt", "add b");
+
+        // swap the two and reword both
+        let todo = vec![(b.clone(), "add b".to_string()), (a.clone(), "add a".to_string())];
+        let messages = vec![(a, "new a\n".to_string()), (b, "new b\n".to_string())];
+        run_interactive_rebase(dir.path(), Some(&base), &todo, &messages).unwrap();
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.summary(), Some("new a"));
+        assert!(head.tree().unwrap().get_name("a.txt").is_some());
+        let parent = head.parent(0).unwrap();
+        assert_eq!(parent.summary(), Some("new b"));
+        assert!(parent.tree().unwrap().get_name("a.txt").is_none());
     }
-    Ok(())
 }
@@ -1,13 +1,43 @@
-use std::io::{self, Write};
+use std::io::{self, IsTerminal, Write};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use colored::Colorize;
+use crossterm::{
+    cursor,
+    event::{DisableMouseCapture, EnableMouseCapture},
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    ExecutableCommand,
+};
+use indicatif::{ProgressBar, ProgressStyle};
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Drop informational output for the rest of the process (`--quiet`)
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+/// Whether `--quiet` is on: print results and errors only
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
 
 /// Set the terminal window/tab title via OSC escape sequence.
 pub fn set_title(title: &str) {
+    if is_quiet() {
+        return;
+    }
     let _ = write!(io::stdout(), "\x1b]0;{}\x07", title);
     let _ = io::stdout().flush();
 }
 
 /// Restore the terminal title to the default (empty resets to shell default).
 pub fn restore_title() {
+    if is_quiet() {
+        return;
+    }
     // Setting empty title lets the terminal/shell reclaim the title
     let _ = write!(io::stdout(), "\x1b]0;\x07");
     let _ = io::stdout().flush();
@@ -22,3 +52,149 @@ pub fn repo_display_name(repo: &git2::Repository) -> String {
         .unwrap_or("repo")
         .to_string()
 }
+
+/// Color one unified-diff line: additions green, removals red, hunk headers
+/// cyan, binary-file placeholders magenta
+pub fn colorize_diff_line(line: &str) -> String {
+    if line.starts_with(crate::git::BINARY_MARKER) {
+        line.magenta().italic().to_string()
+    } else if line.starts_with('+') && !line.starts_with("+++") {
+        line.green().to_string()
+    } else if line.starts_with('-') && !line.starts_with("---") {
+        line.red().to_string()
+    } else if line.starts_with("@@") {
+        line.cyan().to_string()
+    } else {
+        line.to_string()
+    }
+}
+
+/// Show `text` through `$PAGER` (default `less -FRX`) when stdout is a
+/// terminal; print it directly otherwise or if the pager can't start.
+pub fn page_output(text: &str) -> io::Result<()> {
+    if !io::stdout().is_terminal() {
+        return io::stdout().write_all(text.as_bytes());
+    }
+
+    let pager = std::env::var("PAGER")
+        .ok()
+        .filter(|p| !p.trim().is_empty())
+        .unwrap_or_else(|| "less -FRX".to_string());
+    let child = Command::new("sh")
+        .args(["-c", &pager])
+        .stdin(Stdio::piped())
+        .spawn();
+
+    match child {
+        Ok(mut child) => {
+            if let Some(mut stdin) = child.stdin.take() {
+                // the user quitting the pager early closes the pipe; not an error
+                let _ = stdin.write_all(text.as_bytes());
+            }
+            child.wait()?;
+            Ok(())
+        }
+        Err(_) => io::stdout().write_all(text.as_bytes()),
+    }
+}
+
+/// Run `f` behind a spinner labelled `message`, cleared when it returns.
+/// When stdout isn't a terminal the label is printed once to stderr instead,
+/// so piped output stays clean.
+pub fn with_spinner<T>(message: String, f: impl FnOnce() -> T) -> T {
+    if is_quiet() {
+        return f();
+    }
+    if !io::stdout().is_terminal() {
+        eprintln!("{}", message);
+        return f();
+    }
+
+    let pb = ProgressBar::new_spinner();
+    pb.set_style(ProgressStyle::default_spinner().template("{spinner:.cyan} {msg}").unwrap());
+    pb.set_message(message);
+    pb.enable_steady_tick(Duration::from_millis(80));
+    let result = f();
+    pb.finish_and_clear();
+    result
+}
+
+/// Raw mode and the alternate screen (plus mouse capture, if asked) for a
+/// full-screen TUI. Restored on drop, so an early `?` return or a panic
+/// inside the event loop still hands back a usable shell.
+pub struct TuiGuard {
+    mouse: bool,
+    restore: fn(bool),
+}
+
+impl TuiGuard {
+    pub fn enter() -> io::Result<Self> {
+        Self::start(false)
+    }
+
+    pub fn with_mouse() -> io::Result<Self> {
+        Self::start(true)
+    }
+
+    fn start(mouse: bool) -> io::Result<Self> {
+        enable_raw_mode()?;
+        // from here on a failure still has to undo raw mode
+        let guard = Self { mouse, restore: restore_terminal };
+        io::stdout().execute(EnterAlternateScreen)?;
+        if mouse {
+            io::stdout().execute(EnableMouseCapture)?;
+        }
+        Ok(guard)
+    }
+}
+
+impl Drop for TuiGuard {
+    fn drop(&mut self) {
+        (self.restore)(self.mouse);
+    }
+}
+
+/// Best effort: each step runs even if an earlier one fails
+fn restore_terminal(mouse: bool) {
+    let mut out = io::stdout();
+    if mouse {
+        let _ = out.execute(DisableMouseCapture);
+    }
+    let _ = out.execute(LeaveAlternateScreen);
+    let _ = out.execute(cursor::Show);
+    let _ = disable_raw_mode();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static RESTORED: AtomicUsize = AtomicUsize::new(0);
+
+    fn count_restore(_mouse: bool) {
+        RESTORED.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn fake_guard() -> TuiGuard {
+        TuiGuard { mouse: false, restore: count_restore }
+    }
+
+    #[test]
+    fn test_guard_restores_on_error_and_panic() {
+        fn event_loop() -> anyhow::Result<()> {
+            let _guard = fake_guard();
+            anyhow::bail!("draw failed");
+        }
+
+        assert!(event_loop().is_err());
+        assert_eq!(RESTORED.load(Ordering::SeqCst), 1);
+
+        let panicked = std::panic::catch_unwind(|| {
+            let _guard = fake_guard();
+            panic!("bug in a key handler");
+        });
+        assert!(panicked.is_err());
+        assert_eq!(RESTORED.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,36 @@
+use std::fs;
+use std::path::Path;
+
+use git2::{Oid, Repository, Signature};
+use tempfile::TempDir;
+
+/// A fresh repository in a temp dir with `user.name`/`user.email` set, so
+/// git subprocesses can commit too; keep the `TempDir` alive for the test
+pub fn init_repo() -> (TempDir, Repository) {
+    let dir = TempDir::new().unwrap();
+    let repo = Repository::init(dir.path()).unwrap();
+    let mut config = repo.config().unwrap();
+    config.set_str("user.name", "Test").unwrap();
+    config.set_str("user.email", "test@example.com").unwrap();
+    (dir, repo)
+}
+
+/// Write and stage `files` (name, content), then commit them on HEAD
+pub fn commit_files(repo: &Repository, files: &[(&str, &str)], msg: &str) -> Oid {
+    let mut index = repo.index().unwrap();
+    for (name, content) in files {
+        fs::write(repo.workdir().unwrap().join(name), content).unwrap();
+        index.add_path(Path::new(name)).unwrap();
+    }
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let sig = Signature::now("Test", "test@example.com").unwrap();
+    let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+    repo.commit(Some("HEAD"), &sig, &sig, msg, &tree, &parents).unwrap()
+}
+
+/// `commit_files` for a single file
+pub fn commit_file(repo: &Repository, name: &str, content: &str, msg: &str) -> Oid {
+    commit_files(repo, &[(name, content)], msg)
+}
@@ -2,15 +2,104 @@ pub mod github;
 pub mod installer;
 pub mod version;
 
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use anyhow::{Context, Result};
 use colored::Colorize;
 
+use crate::config::{Config, UpdateCheck};
+
 pub use github::Release;
 pub use version::CURRENT_VERSION;
 
+/// Env var that disables all update checks when set (to anything but "", "0" or "false")
+pub const NO_UPDATE_CHECK_ENV: &str = "REPO_NO_UPDATE_CHECK";
+
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+/// Disable update checks for the rest of the process (`--offline`)
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
+
+/// Whether update checks are disabled. Env var and `--offline` win over config.
+pub fn update_check_disabled() -> bool {
+    let env = std::env::var(NO_UPDATE_CHECK_ENV).ok();
+    let config_enabled = Config::load().map_or(true, |c| c.update_check != UpdateCheck::Never);
+    is_disabled(env.as_deref(), OFFLINE.load(Ordering::Relaxed), config_enabled)
+}
+
+fn is_disabled(env: Option<&str>, offline: bool, config_enabled: bool) -> bool {
+    let env_set = env.is_some_and(|v| !matches!(v.trim(), "" | "0" | "false"));
+    env_set || offline || !config_enabled
+}
+
+const DAY_SECS: u64 = 24 * 60 * 60;
+
+/// Unix time of the last post-commit release check; `None` without a cache dir
+fn last_check_path() -> Option<PathBuf> {
+    dirs::cache_dir().map(|d| d.join("repo").join("last-update-check"))
+}
+
+fn check_due(mode: UpdateCheck, last: Option<u64>, now: u64) -> bool {
+    match mode {
+        UpdateCheck::Never => false,
+        UpdateCheck::Always => true,
+        UpdateCheck::Daily => last.is_none_or(|t| now.saturating_sub(t) >= DAY_SECS),
+    }
+}
+
+/// Start the post-commit release check on a background thread if
+/// `update_check` says one is due. Poll the receiver after committing: a
+/// check that hasn't finished by then is dropped, never waited on.
+pub fn spawn_hint_check() -> Option<Receiver<Release>> {
+    if crate::terminal::is_quiet() || update_check_disabled() {
+        return None;
+    }
+    let mode = Config::load().unwrap_or_default().update_check;
+    let path = last_check_path()?;
+    let last = fs::read_to_string(&path).ok().and_then(|s| s.trim().parse().ok());
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    if !check_due(mode, last, now) {
+        return None;
+    }
+
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let Ok(release) = check_for_update() else {
+            return;
+        };
+        // only a completed check counts towards `daily`
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        let _ = fs::write(&path, now.to_string());
+        if let Some(release) = release {
+            let _ = tx.send(release);
+        }
+    });
+    Some(rx)
+}
+
 /// Check for updates and return release info if available
 pub fn check_for_update() -> Result<Option<Release>> {
-    let release = match github::fetch_latest_release() {
+    check_with(update_check_disabled(), github::fetch_latest_release)
+}
+
+fn check_with(
+    disabled: bool,
+    fetch: impl FnOnce() -> Result<Release>,
+) -> Result<Option<Release>> {
+    if disabled {
+        return Ok(None);
+    }
+
+    let release = match fetch() {
         Ok(r) => r,
         Err(e) => {
             let msg = e.to_string();
@@ -89,3 +178,51 @@ pub fn perform_update() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_disabled_precedence() {
+        assert!(!is_disabled(None, false, true));
+        assert!(is_disabled(Some("1"), false, true));
+        assert!(is_disabled(Some("yes"), false, true));
+        assert!(!is_disabled(Some("0"), false, true));
+        assert!(!is_disabled(Some("false"), false, true));
+        assert!(!is_disabled(Some(""), false, true));
+        assert!(is_disabled(None, true, true));
+        assert!(is_disabled(None, false, false));
+        // A falsy env var doesn't re-enable checks disabled in config
+        assert!(is_disabled(Some("0"), false, false));
+    }
+
+    #[test]
+    fn test_check_due_by_mode() {
+        let now = 10 * DAY_SECS;
+        assert!(!check_due(UpdateCheck::Never, None, now));
+        assert!(check_due(UpdateCheck::Always, Some(now), now));
+        assert!(check_due(UpdateCheck::Daily, None, now));
+        assert!(!check_due(UpdateCheck::Daily, Some(now - 60), now));
+        assert!(check_due(UpdateCheck::Daily, Some(now - DAY_SECS), now));
+        // a clock that went backwards doesn't trigger a check
+        assert!(!check_due(UpdateCheck::Daily, Some(now + 60), now));
+    }
+
+    #[test]
+    fn test_disabled_check_skips_network() {
+        let disabled = is_disabled(Some("1"), false, true);
+        let result = check_with(disabled, || panic!("checker must not be called"));
+        assert!(result.unwrap().is_none());
+    }
+
+    #[test]
+    fn test_enabled_check_calls_checker() {
+        let mut called = false;
+        let _ = check_with(false, || {
+            called = true;
+            anyhow::bail!("No releases found")
+        });
+        assert!(called);
+    }
+}
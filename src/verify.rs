@@ -0,0 +1,330 @@
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use git2::{BranchType, Oid, Repository};
+
+use crate::commit::{parse_conventional, CONVENTIONAL_TYPES};
+use crate::git::get_working_tree_status;
+
+pub struct VerifyOpts {
+    /// Range base for commit checks; falls back to the branch upstream
+    pub base: Option<String>,
+    pub allow_detached: bool,
+    pub conventional: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CheckStatus {
+    Pass,
+    Fail(String),
+    Skip(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub status: CheckStatus,
+}
+
+impl CheckResult {
+    fn new(name: &'static str, status: CheckStatus) -> Self {
+        Self { name, status }
+    }
+
+    pub fn failed(&self) -> bool {
+        matches!(self.status, CheckStatus::Fail(_))
+    }
+}
+
+/// Run all checks, print a checklist, and fail if any check failed
+pub fn run_verify(repo: &Repository, opts: VerifyOpts) -> Result<()> {
+    let results = run_checks(repo, &opts)?;
+
+    println!("{}", "VERIFY".bold());
+    for r in &results {
+        match &r.status {
+            CheckStatus::Pass => println!("   {} {}", "✓".green(), r.name),
+            CheckStatus::Fail(why) => {
+                println!("   {} {}  {}", "✗".red(), r.name, why.dimmed())
+            }
+            CheckStatus::Skip(why) => {
+                println!("   {} {}  {}", "·".dimmed(), r.name.dimmed(), why.dimmed())
+            }
+        }
+    }
+
+    let failed = results.iter().filter(|r| r.failed()).count();
+    if failed > 0 {
+        bail!("{} check(s) failed", failed);
+    }
+
+    println!("{} all checks passed", "✓".green());
+    Ok(())
+}
+
+pub fn run_checks(repo: &Repository, opts: &VerifyOpts) -> Result<Vec<CheckResult>> {
+    let range = resolve_range(repo, opts.base.as_deref())?;
+
+    let mut results = vec![
+        check_head_attached(repo, opts.allow_detached)?,
+        check_no_conflicts(repo)?,
+    ];
+
+    match &range {
+        Some((base_name, commits)) => {
+            results.push(check_commit_messages(commits, base_name));
+            if opts.conventional {
+                results.push(check_conventional(commits, base_name));
+            }
+        }
+        None => {
+            let why = "no --base and no upstream".to_string();
+            results.push(CheckResult::new("commit messages", CheckStatus::Skip(why.clone())));
+            if opts.conventional {
+                results.push(CheckResult::new("conventional commits", CheckStatus::Skip(why)));
+            }
+        }
+    }
+
+    results.push(check_clean(repo)?);
+    Ok(results)
+}
+
+pub fn check_head_attached(repo: &Repository, allow_detached: bool) -> Result<CheckResult> {
+    let name = "HEAD attached";
+    if !repo.head_detached()? {
+        return Ok(CheckResult::new(name, CheckStatus::Pass));
+    }
+    let status = if allow_detached {
+        CheckStatus::Skip("detached (allowed)".to_string())
+    } else {
+        CheckStatus::Fail("detached HEAD".to_string())
+    };
+    Ok(CheckResult::new(name, status))
+}
+
+pub fn check_no_conflicts(repo: &Repository) -> Result<CheckResult> {
+    let status = get_working_tree_status(repo)?;
+    let result = if status.conflicted > 0 {
+        CheckStatus::Fail(format!("{} conflicted file(s)", status.conflicted))
+    } else {
+        CheckStatus::Pass
+    };
+    Ok(CheckResult::new("no merge conflicts", result))
+}
+
+pub fn check_clean(repo: &Repository) -> Result<CheckResult> {
+    let status = get_working_tree_status(repo)?;
+    let result = if status.is_clean() {
+        CheckStatus::Pass
+    } else {
+        CheckStatus::Fail(format!(
+            "{} staged, {} modified, {} untracked",
+            status.staged, status.modified, status.untracked
+        ))
+    };
+    Ok(CheckResult::new("working tree clean", result))
+}
+
+/// Commits in `base..HEAD` as (short_sha, full message)
+pub type RangeCommits = Vec<(String, String)>;
+
+pub fn check_commit_messages(commits: &RangeCommits, base_name: &str) -> CheckResult {
+    let empty: Vec<&str> = commits
+        .iter()
+        .filter(|(_, msg)| msg.trim().is_empty())
+        .map(|(sha, _)| sha.as_str())
+        .collect();
+    let status = if empty.is_empty() {
+        CheckStatus::Pass
+    } else {
+        CheckStatus::Fail(format!("empty message: {} ({}..HEAD)", empty.join(", "), base_name))
+    };
+    CheckResult::new("commit messages", status)
+}
+
+pub fn check_conventional(commits: &RangeCommits, base_name: &str) -> CheckResult {
+    let bad: Vec<&str> = commits
+        .iter()
+        .filter(|(_, msg)| !is_conventional(msg.lines().next().unwrap_or("")))
+        .map(|(sha, _)| sha.as_str())
+        .collect();
+    let status = if bad.is_empty() {
+        CheckStatus::Pass
+    } else {
+        CheckStatus::Fail(format!("not conventional: {} ({}..HEAD)", bad.join(", "), base_name))
+    };
+    CheckResult::new("conventional commits", status)
+}
+
+/// Matches `type: desc`, `type(scope): desc`, and the `!` breaking variants
+/// with a type from [`CONVENTIONAL_TYPES`]
+pub fn is_conventional(subject: &str) -> bool {
+    parse_conventional(subject).is_some_and(|c| CONVENTIONAL_TYPES.contains(&c.kind))
+}
+
+/// Resolve the commit range to check: explicit base, else the branch upstream
+fn resolve_range(repo: &Repository, base: Option<&str>) -> Result<Option<(String, RangeCommits)>> {
+    let (base_name, base_oid) = match base {
+        Some(b) => {
+            let obj = repo
+                .revparse_single(b)
+                .with_context(|| format!("unknown base ref: {}", b))?;
+            (b.to_string(), obj.peel_to_commit()?.id())
+        }
+        None => match upstream_oid(repo) {
+            Some(found) => found,
+            None => return Ok(None),
+        },
+    };
+
+    let commits = commits_in_range(repo, base_oid)?;
+    Ok(Some((base_name, commits)))
+}
+
+fn upstream_oid(repo: &Repository) -> Option<(String, Oid)> {
+    let head = repo.head().ok()?;
+    if !head.is_branch() {
+        return None;
+    }
+    let branch = repo.find_branch(head.shorthand()?, BranchType::Local).ok()?;
+    let upstream = branch.upstream().ok()?;
+    let name = upstream.name().ok()??.to_string();
+    Some((name, upstream.get().target()?))
+}
+
+fn commits_in_range(repo: &Repository, base: Oid) -> Result<RangeCommits> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push_head()?;
+    revwalk.hide(base)?;
+
+    let mut commits = Vec::new();
+    for oid in revwalk {
+        let oid = oid?;
+        let commit = repo.find_commit(oid)?;
+        commits.push((
+            oid.to_string()[..7].to_string(),
+            commit.message().unwrap_or("").to_string(),
+        ));
+    }
+    Ok(commits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::commit_file;
+    use std::fs;
+    use std::path::Path;
+
+    fn init_test_repo(dir: &Path) -> (Repository, Oid) {
+        let repo = Repository::init(dir).unwrap();
+        let base = commit_file(&repo, "init.txt", "init", "chore: init");
+        (repo, base)
+    }
+
+    #[test]
+    fn test_is_conventional() {
+        assert!(is_conventional("feat: add verify"));
+        assert!(is_conventional("fix(git): handle detached HEAD"));
+        assert!(is_conventional("feat!: drop old flag"));
+        assert!(is_conventional("refactor(core)!: rename"));
+        assert!(!is_conventional("add verify"));
+        assert!(!is_conventional("feat:missing space"));
+        assert!(!is_conventional("wip(x): stuff"));
+        assert!(!is_conventional("feat(): empty scope"));
+        assert!(!is_conventional("feat: "));
+    }
+
+    #[test]
+    fn test_head_attached() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (repo, base) = init_test_repo(tmp.path());
+        assert_eq!(check_head_attached(&repo, false).unwrap().status, CheckStatus::Pass);
+
+        repo.set_head_detached(base).unwrap();
+        assert!(check_head_attached(&repo, false).unwrap().failed());
+        assert!(matches!(
+            check_head_attached(&repo, true).unwrap().status,
+            CheckStatus::Skip(_)
+        ));
+    }
+
+    #[test]
+    fn test_clean_tree() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (repo, _) = init_test_repo(tmp.path());
+        assert_eq!(check_clean(&repo).unwrap().status, CheckStatus::Pass);
+
+        fs::write(tmp.path().join("stray.txt"), "x").unwrap();
+        assert!(check_clean(&repo).unwrap().failed());
+        // untracked files are not conflicts
+        assert_eq!(check_no_conflicts(&repo).unwrap().status, CheckStatus::Pass);
+    }
+
+    #[test]
+    fn test_conflicts() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (repo, base) = init_test_repo(tmp.path());
+        let main_ref = repo.head().unwrap().name().unwrap().to_string();
+
+        let base_commit = repo.find_commit(base).unwrap();
+        repo.branch("other", &base_commit, false).unwrap();
+        commit_file(&repo, "init.txt", "main side", "fix: main");
+
+        repo.set_head("refs/heads/other").unwrap();
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+            .unwrap();
+        let other = commit_file(&repo, "init.txt", "other side", "fix: other");
+
+        repo.set_head(&main_ref).unwrap();
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+            .unwrap();
+        assert_eq!(check_no_conflicts(&repo).unwrap().status, CheckStatus::Pass);
+
+        let annotated = repo.find_annotated_commit(other).unwrap();
+        repo.merge(&[&annotated], None, None).unwrap();
+        assert!(check_no_conflicts(&repo).unwrap().failed());
+    }
+
+    #[test]
+    fn test_commit_messages_in_range() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (repo, base) = init_test_repo(tmp.path());
+        commit_file(&repo, "a.txt", "a", "feat: add a");
+
+        let commits = commits_in_range(&repo, base).unwrap();
+        assert_eq!(commits.len(), 1);
+        assert_eq!(check_commit_messages(&commits, "base").status, CheckStatus::Pass);
+
+        commit_file(&repo, "b.txt", "b", "  \n");
+        let commits = commits_in_range(&repo, base).unwrap();
+        assert!(check_commit_messages(&commits, "base").failed());
+    }
+
+    #[test]
+    fn test_conventional_in_range() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (repo, base) = init_test_repo(tmp.path());
+        commit_file(&repo, "a.txt", "a", "feat(a): add a\n\nbody text");
+
+        let commits = commits_in_range(&repo, base).unwrap();
+        assert_eq!(check_conventional(&commits, "base").status, CheckStatus::Pass);
+
+        commit_file(&repo, "b.txt", "b", "updated stuff");
+        let commits = commits_in_range(&repo, base).unwrap();
+        assert!(check_conventional(&commits, "base").failed());
+    }
+
+    #[test]
+    fn test_run_checks_skips_range_without_base() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (repo, _) = init_test_repo(tmp.path());
+        let opts = VerifyOpts { base: None, allow_detached: false, conventional: true };
+
+        let results = run_checks(&repo, &opts).unwrap();
+        assert!(results.iter().all(|r| !r.failed()));
+        assert!(results
+            .iter()
+            .any(|r| r.name == "conventional commits" && matches!(r.status, CheckStatus::Skip(_))));
+    }
+}
@@ -7,8 +7,9 @@ use walkdir::WalkDir;
 
 use crate::config::Config;
 use crate::git::{
-    get_current_branch, get_recent_commits, get_working_tree_status, open_repo,
+    get_current_branch, get_recent_commits, get_working_tree_status, open_repo_exact,
 };
+use crate::models::WorkingTreeStatus;
 use crate::workspace::groups::{expand_path, Group};
 use crate::workspace::{RepoSnapshot, WorkspaceSource, WorkspaceSummary};
 
@@ -213,9 +214,13 @@ fn load_snapshots_parallel(
 }
 
 fn load_snapshot(path: &Path, stale_days: u64) -> Result<RepoSnapshot> {
-    let repo = open_repo(Some(path))?;
+    let repo = open_repo_exact(path)?;
     let branch_info = get_current_branch(&repo)?;
-    let status = get_working_tree_status(&repo)?;
+    let status = if repo.is_bare() {
+        WorkingTreeStatus::default()
+    } else {
+        get_working_tree_status(&repo)?
+    };
     let recent = get_recent_commits(&repo, RECENT_COMMITS_PER_REPO)?;
 
     let last_activity = recent.first().map(|c| c.time);
@@ -3,11 +3,7 @@ pub mod feed;
 pub mod picker;
 
 use anyhow::Result;
-use crossterm::{
-    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
 use ratatui::{
     backend::CrosstermBackend,
     layout::{Constraint, Direction, Layout, Rect},
@@ -18,6 +14,8 @@ use ratatui::{
 };
 use std::io::stdout;
 
+use crate::render::help::{render_help_overlay, KeyHelp};
+use crate::terminal::TuiGuard;
 use crate::workspace::filter::{parse_filters, WorkspaceFilter};
 use crate::workspace::WorkspaceSummary;
 
@@ -54,6 +52,7 @@ pub struct WorkspaceApp {
     feed: FeedState,
     dashboard: DashboardState,
     status: String,
+    show_help: bool,
     should_quit: bool,
 }
 
@@ -71,10 +70,29 @@ impl WorkspaceApp {
             feed,
             dashboard,
             status: String::new(),
+            show_help: false,
             should_quit: false,
         }
     }
 
+    /// Keys for the footer and the `?` overlay
+    fn keys(&self) -> Vec<KeyHelp> {
+        if self.filter_editing {
+            KeyHelp::list(&[("Enter", "apply"), ("Esc", "cancel"), ("^U", "clear")])
+        } else {
+            KeyHelp::list(&[
+                ("Tab", "switch"),
+                ("/", "filter"),
+                ("x", "clear filter"),
+                ("j/k", "nav"),
+                ("PgUp/PgDn", "page"),
+                ("g/G", "top/bottom"),
+                ("?", "help"),
+                ("q", "quit"),
+            ])
+        }
+    }
+
     fn reapply_filters(&mut self) {
         self.filters = parse_filters(&self.filter_input);
         self.feed = FeedState::new(&self.summary, &self.filters);
@@ -83,20 +101,11 @@ impl WorkspaceApp {
 }
 
 pub fn run_workspace_tui(summary: WorkspaceSummary, initial_filter: String) -> Result<()> {
-    enable_raw_mode()?;
-    let mut out = stdout();
-    execute!(out, EnterAlternateScreen)?;
-    let backend = CrosstermBackend::new(out);
-    let mut terminal = Terminal::new(backend)?;
+    let _guard = TuiGuard::enter()?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
 
     let mut app = WorkspaceApp::new(summary, initial_filter);
-
-    let result = run_loop(&mut terminal, &mut app);
-
-    disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-    terminal.show_cursor()?;
-    result
+    run_loop(&mut terminal, &mut app)
 }
 
 fn run_loop<B: ratatui::backend::Backend>(
@@ -117,6 +126,12 @@ fn run_loop<B: ratatui::backend::Backend>(
 }
 
 fn handle_key(app: &mut WorkspaceApp, key: crossterm::event::KeyEvent) {
+    if app.show_help {
+        if matches!(key.code, KeyCode::Char('?') | KeyCode::Esc) {
+            app.show_help = false;
+        }
+        return;
+    }
     if app.filter_editing {
         match key.code {
             KeyCode::Enter | KeyCode::Esc => {
@@ -140,6 +155,7 @@ fn handle_key(app: &mut WorkspaceApp, key: crossterm::event::KeyEvent) {
     }
 
     match key.code {
+        KeyCode::Char('?') => app.show_help = true,
         KeyCode::Char('q') | KeyCode::Esc => app.should_quit = true,
         KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
             app.should_quit = true
@@ -205,6 +221,10 @@ fn render(f: &mut Frame, app: &WorkspaceApp) {
         Tab::Dashboard => dashboard::render(f, chunks[3], &app.dashboard, &app.summary),
     }
     render_footer(f, chunks[4], app);
+
+    if app.show_help {
+        render_help_overlay(f, "Workspace keys", &app.keys(), f.size());
+    }
 }
 
 fn render_header(f: &mut Frame, area: Rect, app: &WorkspaceApp) {
@@ -263,11 +283,8 @@ fn render_filter(f: &mut Frame, area: Rect, app: &WorkspaceApp) {
 }
 
 fn render_footer(f: &mut Frame, area: Rect, app: &WorkspaceApp) {
-    let help = if app.filter_editing {
-        "Enter: apply  Esc: cancel  ^U: clear"
-    } else {
-        "Tab: switch  /: filter  x: clear filter  j/k: nav  g/G: top/bottom  q: quit"
-    };
+    let keys: Vec<String> = app.keys().iter().map(|k| format!("{}: {}", k.key, k.desc)).collect();
+    let help = keys.join("  ");
     let content = if app.status.is_empty() {
         help.to_string()
     } else {
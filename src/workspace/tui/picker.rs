@@ -1,9 +1,5 @@
 use anyhow::Result;
-use crossterm::{
-    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
 use ratatui::{
     backend::CrosstermBackend,
     layout::{Constraint, Direction, Layout, Rect},
@@ -15,6 +11,7 @@ use ratatui::{
 use std::io::stdout;
 use std::path::PathBuf;
 
+use crate::terminal::TuiGuard;
 use crate::workspace::groups::{contract_path, expand_path, Group, GroupsFile};
 use crate::workspace::scan::{discover_repos, ScanOptions};
 
@@ -176,19 +173,13 @@ pub fn run_picker(
     initial_root: Option<String>,
     initial_alias: Option<String>,
 ) -> Result<Option<Group>> {
-    enable_raw_mode()?;
-    let mut out = stdout();
-    execute!(out, EnterAlternateScreen)?;
-    let backend = CrosstermBackend::new(out);
-    let mut terminal = Terminal::new(backend)?;
+    let guard = TuiGuard::enter()?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
 
     let mut app = PickerApp::new(initial_root, initial_alias);
 
     let result = run_loop(&mut terminal, &mut app);
-
-    disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-    terminal.show_cursor()?;
+    drop(guard);
     result?;
     Ok(app.done)
 }